@@ -1,5 +1,7 @@
-use crate::hardware::{GpuBackend, SystemSpecs};
-use crate::models::{self, LlmModel, UseCase};
+use crate::gpu_specs;
+use crate::hardware::{GpuBackend, GpuLinkTopology, PCIE_SPLIT_RESERVE_GB, SystemSpecs};
+use crate::models::{self, GgufQuantization, KvCacheQuant, LlmModel, ModelArchConfig, UseCase};
+use std::fmt;
 
 /// Inference runtime — the software framework used for inference.
 /// Orthogonal to `GpuBackend` which represents hardware.
@@ -63,6 +65,47 @@ pub enum FitLevel {
     TooTight, // Does not fit in available memory
 }
 
+impl FitLevel {
+    /// Best to worst.
+    pub const ALL: [FitLevel; 4] = [
+        FitLevel::Perfect,
+        FitLevel::Good,
+        FitLevel::Marginal,
+        FitLevel::TooTight,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FitLevel::Perfect => "Perfect",
+            FitLevel::Good => "Good",
+            FitLevel::Marginal => "Marginal",
+            FitLevel::TooTight => "Too Tight",
+        }
+    }
+
+    /// Is this level as good as `min` or better? (`--min-fit` filtering.)
+    pub fn at_least(&self, min: FitLevel) -> bool {
+        Self::ALL.iter().position(|l| l == self) <= Self::ALL.iter().position(|l| *l == min)
+    }
+}
+
+impl std::str::FromStr for FitLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "perfect" => Ok(FitLevel::Perfect),
+            "good" => Ok(FitLevel::Good),
+            "marginal" => Ok(FitLevel::Marginal),
+            "tootight" | "tight" => Ok(FitLevel::TooTight),
+            _ => Err(format!(
+                "unknown fit level '{}', expected one of: perfect, good, marginal, too-tight",
+                s
+            )),
+        }
+    }
+}
+
 /// Execution path -- how will inference run?
 /// This is the "optimization" dimension, independent of memory fit.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
@@ -103,6 +146,13 @@ pub struct ModelFit {
     pub use_case: UseCase,         // inferred use case category
     pub runtime: InferenceRuntime, // inference runtime (MLX or llama.cpp)
     pub installed: bool,           // model found in a local runtime provider
+    /// CpuOffload only: layers to keep on the GPU (llama.cpp
+    /// `--n-gpu-layers`); the rest run on the CPU.
+    pub gpu_layers: Option<u32>,
+    /// TooTight only: tokens/sec when llama.cpp mmaps the weights and pages
+    /// what doesn't fit in memory in from the NVMe drive. See
+    /// [`mmap_tokens_per_sec`].
+    pub mmap_tokens_per_sec: Option<f64>,
 }
 
 impl ModelFit {
@@ -148,7 +198,7 @@ impl ModelFit {
                 // Unified memory (Apple Silicon or NVIDIA Tegra/Grace Blackwell):
                 // GPU and CPU share the same memory pool.
                 // No CpuOffload -- there's no separate pool to spill to.
-                if let Some(pool) = system.gpu_vram_gb {
+                if let Some(pool) = system.available_gpu_memory_gb() {
                     notes.push("Unified memory: GPU and CPU share the same pool".to_string());
                     if model.is_moe {
                         notes.push(format!(
@@ -167,9 +217,10 @@ impl ModelFit {
                 } else {
                     cpu_path(model, system, runtime, estimation_ctx, &mut notes)
                 }
-            } else if let Some(system_vram) = system.total_gpu_vram_gb {
+            } else if let Some(system_vram) = system.available_gpu_memory_gb() {
                 // Use total VRAM across all same-model GPUs for fit scoring.
                 // Multi-GPU inference (tensor splitting) is supported by llama.cpp, vLLM, etc.
+                // Without NVLink, each extra card keeps a small split reserve.
                 if model.is_moe && min_vram <= system_vram {
                     // Fits in VRAM -- GPU path
                     notes.push("GPU: model loaded into VRAM".to_string());
@@ -209,6 +260,86 @@ impl ModelFit {
             cpu_path(model, system, runtime, estimation_ctx, &mut notes)
         };
 
+        // Tensor splitting works, but a model spread over several smaller cards
+        // is not the same as one card with the same total VRAM.
+        if run_mode == RunMode::Gpu
+            && !system.unified_memory
+            && system.gpu_vram_gb_per_device.len() > 1
+            && let Some(&largest) = system.gpu_vram_gb_per_device.first()
+            && mem_required > largest
+            && mem_required <= mem_available
+        {
+            notes.push(format!(
+                "Multi-GPU: model must be split across cards (largest has {:.1} GB)",
+                largest
+            ));
+            match system.gpu_link_topology {
+                GpuLinkTopology::NvlinkPod => notes.push(
+                    "NVLink: tensor parallelism runs at full interconnect bandwidth".to_string(),
+                ),
+                GpuLinkTopology::PcieSli => notes.push(
+                    "PCIe only: prefer pipeline (layer) split over tensor parallelism".to_string(),
+                ),
+                GpuLinkTopology::Independent => notes.push(
+                    "Mixed GPUs: split layers by each card's VRAM (e.g. --tensor-split)"
+                        .to_string(),
+                ),
+                GpuLinkTopology::SingleGpu => {}
+            }
+        }
+
+        // The GPU paths above already left out VRAM held by other processes.
+        if run_mode != RunMode::CpuOnly
+            && !system.unified_memory
+            && system.vram_contended()
+            && let (Some(used), Some(free)) =
+                (system.gpu_vram_used_gb, system.gpu_vram_available_gb)
+        {
+            notes.push(
+                FitWarning::VramPartiallyOccupied {
+                    used_gb: used,
+                    free_gb: free,
+                }
+                .to_string(),
+            );
+        }
+
+        // Pre-Volta cards lack tensor cores; quantized matmuls fall back to
+        // slower DP4A / FP32 kernels.
+        if system.backend == GpuBackend::Cuda
+            && run_mode != RunMode::CpuOnly
+            && let Some((major, minor)) = system.cuda_compute_capability
+        {
+            if major < 7 {
+                notes.push(format!(
+                    "Compute capability {}.{} has no tensor cores; quantized kernels will be slower",
+                    major, minor
+                ));
+            }
+            // Long contexts lean on FlashAttention to keep attention memory in check
+            if !system.supports_flash_attention_2() && estimation_ctx >= 32_768 {
+                notes.push(format!(
+                    "FlashAttention-2 needs sm_80+ (this GPU is sm_{}{}); long contexts will be slower",
+                    major, minor
+                ));
+            }
+        }
+
+        if system.backend == GpuBackend::Rocm
+            && run_mode != RunMode::CpuOnly
+            && let Some(rocm) = system.rocm_version.as_deref()
+            && rocm
+                .split('.')
+                .next()
+                .and_then(|m| m.parse::<u32>().ok())
+                .is_some_and(|major| major < 6)
+        {
+            notes.push(format!(
+                "ROCm {} is older than 6.0; many recent inference builds require ROCm 6+",
+                rocm
+            ));
+        }
+
         // Score fit purely on memory headroom (Perfect requires GPU)
         let fit_level = score_fit(
             mem_required,
@@ -217,6 +348,19 @@ impl ModelFit {
             run_mode,
         );
 
+        // Swap never counts toward the verdict, but say when it would rescue a
+        // model that lives in system RAM.
+        if fit_level == FitLevel::TooTight
+            && matches!(run_mode, RunMode::CpuOnly | RunMode::CpuOffload)
+            && system.swap_available_gb > 0.0
+            && mem_required <= mem_available + system.swap_available_gb
+        {
+            notes.push(format!(
+                "Would fit using {:.1} GB of swap (very slow)",
+                mem_required - mem_available
+            ));
+        }
+
         let utilization_pct = if mem_available > 0.0 {
             (mem_required / mem_available) * 100.0
         } else {
@@ -226,11 +370,19 @@ impl ModelFit {
         // Supplementary notes
         if run_mode == RunMode::CpuOnly {
             notes.push("No GPU -- inference will be slow".to_string());
+        } else if let Some(warning) = thermal_warning(system) {
+            notes.push(warning.to_string());
         }
         if matches!(run_mode, RunMode::CpuOffload | RunMode::CpuOnly) && system.total_cpu_cores < 4
         {
             notes.push("Low CPU core count may bottleneck inference".to_string());
         }
+        if matches!(run_mode, RunMode::CpuOffload | RunMode::CpuOnly) && system.numa_nodes > 1 {
+            notes.push(format!(
+                "{} NUMA nodes -- pin to one node (numactl --cpunodebind=0 --membind=0) or use llama.cpp --numa",
+                system.numa_nodes
+            ));
+        }
 
         // Compute MoE offloaded amount if applicable
         let moe_offloaded_gb = if run_mode == RunMode::MoeOffload {
@@ -267,8 +419,41 @@ impl ModelFit {
             model.quantization.clone()
         };
 
+        // A download that won't fit on disk is worth knowing before pulling.
+        let download_gb = model.params_b() * models::quant_bpp(&best_quant_str);
+        if let Some(disk) = system.disk_available_gb
+            && download_gb > disk
+        {
+            notes.push(format!(
+                "Download needs ~{:.1} GB but only {:.1} GB free on disk",
+                download_gb, disk
+            ));
+        }
+
+        let gpu_layers = if run_mode == RunMode::CpuOffload {
+            system.available_gpu_memory_gb().map(|vram| {
+                let layers = offload_gpu_layers(model, &best_quant_str, estimation_ctx, vram);
+                notes.push(format!(
+                    "Offload {}/{} layers to GPU (--n-gpu-layers {})",
+                    layers,
+                    model.estimated_layer_count(),
+                    layers
+                ));
+                layers
+            })
+        } else {
+            None
+        };
+
         // Speed estimation
         let estimated_tps = estimate_tps(model, &best_quant_str, system, run_mode, runtime);
+        if model.is_moe && model.active_parameters.is_some() {
+            notes.push(format!(
+                "MoE: memory sized for all {:.1}B params, speed for {:.1}B active",
+                model.params_b(),
+                model.active_params_b()
+            ));
+        }
 
         // Add runtime comparison note on Apple Silicon
         if runtime == InferenceRuntime::Mlx {
@@ -290,6 +475,17 @@ impl ModelFit {
             }
         }
 
+        // Too big for memory, but mmap can still stream it from a fast SSD
+        let mmap_tps = (fit_level == FitLevel::TooTight)
+            .then(|| mmap_tokens_per_sec(system, download_gb))
+            .flatten();
+        if let Some(tps) = mmap_tps {
+            notes.push(format!(
+                "mmap from NVMe: ~{:.2} tok/s, paging in weights that don't fit in memory",
+                tps
+            ));
+        }
+
         // Multi-dimensional scoring
         let score_components = compute_scores(
             model,
@@ -321,6 +517,8 @@ impl ModelFit {
             use_case,
             runtime,
             installed: false, // set later by App after provider detection
+            gpu_layers,
+            mmap_tokens_per_sec: mmap_tps,
         }
     }
 
@@ -334,12 +532,7 @@ impl ModelFit {
     }
 
     pub fn fit_text(&self) -> &str {
-        match self.fit_level {
-            FitLevel::Perfect => "Perfect",
-            FitLevel::Good => "Good",
-            FitLevel::Marginal => "Marginal",
-            FitLevel::TooTight => "Too Tight",
-        }
+        self.fit_level.label()
     }
 
     pub fn runtime_text(&self) -> &str {
@@ -356,6 +549,456 @@ impl ModelFit {
     }
 }
 
+/// Something that limits how well a model runs, attached to a
+/// [`ModelFitResult`]. `Display` gives the sentence shown to the user, and
+/// that is also how it serializes. Memory figures are in GB.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FitWarning {
+    /// The model runs on the GPU but part of it spills to system RAM.
+    SpillsToRam { needed_gb: f64, vram_gb: f64 },
+    /// A GPU was found but its VRAM couldn't be read.
+    VramUnknown,
+    /// Fits only once free swap is counted.
+    FitsWithSwap {
+        needed_gb: f64,
+        ram_gb: f64,
+        swap_gb: f64,
+    },
+    /// Doesn't fit in the memory pool at all.
+    InsufficientMemory { needed_gb: f64, available_gb: f64 },
+    /// Fits, with less than 10% to spare.
+    NearCapacity {
+        on_gpu: bool,
+        needed_gb: f64,
+        available_gb: f64,
+    },
+    /// Other processes hold more than [`crate::hardware::VRAM_CONTENTION_THRESHOLD`]
+    /// of the card; the fit was judged against what they left free.
+    VramPartiallyOccupied { used_gb: f64, free_gb: f64 },
+    /// The GPU was already hot enough to throttle when detected; see
+    /// [`SystemSpecs::thermal_throttle_risk`].
+    ThermalThrottlingRisk { temperature_c: f32 },
+    /// Weights kept in RAM exceed RLIMIT_MEMLOCK, so `--mlock` fails.
+    MlockLimitTooLow { weights_gb: f64, limit_gb: f64 },
+    /// No usable GPU; the model runs on the CPU.
+    CpuOnly,
+    /// A more compressed KV cache would fit better.
+    SmallerKvCache { kv: KvCacheQuant, on_gpu: bool },
+    /// Free-form note, e.g. from [`ModelFit::notes`].
+    Note(String),
+}
+
+impl fmt::Display for FitWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FitWarning::SpillsToRam { needed_gb, vram_gb } => write!(
+                f,
+                "Needs {:.1} GB but only {:.1} GB VRAM; layers will spill to system RAM",
+                needed_gb, vram_gb
+            ),
+            FitWarning::VramUnknown => {
+                write!(f, "GPU detected but VRAM unknown; assuming CPU only")
+            }
+            FitWarning::FitsWithSwap {
+                needed_gb,
+                ram_gb,
+                swap_gb,
+            } => write!(
+                f,
+                "Fits with swap (slow): needs {:.1} GB, {:.1} GB RAM + {:.1} GB swap free",
+                needed_gb, ram_gb, swap_gb
+            ),
+            FitWarning::InsufficientMemory {
+                needed_gb,
+                available_gb,
+            } => write!(
+                f,
+                "Needs {:.1} GB, only {:.1} GB available",
+                needed_gb, available_gb
+            ),
+            FitWarning::NearCapacity {
+                on_gpu,
+                needed_gb,
+                available_gb,
+            } => write!(
+                f,
+                "{} within 10% of requirement ({:.1} of {:.1} GB)",
+                if *on_gpu { "VRAM" } else { "RAM" },
+                needed_gb,
+                available_gb
+            ),
+            FitWarning::VramPartiallyOccupied { used_gb, free_gb } => write!(
+                f,
+                "{:.1} GB of VRAM is held by other processes; fit judged against the {:.1} GB free",
+                used_gb, free_gb
+            ),
+            FitWarning::ThermalThrottlingRisk { temperature_c } => write!(
+                f,
+                "GPU already at {:.0}°C; sustained inference may thermal-throttle below the estimated speed",
+                temperature_c
+            ),
+            FitWarning::MlockLimitTooLow {
+                weights_gb,
+                limit_gb,
+            } => write!(
+                f,
+                "Weights need {:.1} GB but mlock is limited to {:.2} GB, so --mlock will fail; \
+                 raise `memlock` in /etc/security/limits.conf (or LimitMEMLOCK=infinity for a \
+                 systemd service) and log in again",
+                weights_gb, limit_gb
+            ),
+            FitWarning::CpuOnly => write!(f, "No GPU -- inference will be slow"),
+            FitWarning::SmallerKvCache { kv, on_gpu } => write!(
+                f,
+                "Fits {} with a {} KV cache (llama.cpp --cache-type-k {} --cache-type-v {})",
+                if *on_gpu { "on the GPU" } else { "in RAM" },
+                kv.label(),
+                kv.label(),
+                kv.label()
+            ),
+            FitWarning::Note(note) => write!(f, "{}", note),
+        }
+    }
+}
+
+/// Warnings are written out as the sentence the user would see.
+impl serde::Serialize for FitWarning {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Verdict for a single model size/quantization on a given machine, as
+/// returned by [`SystemSpecs::check_model_fit`] and [`evaluate_fit`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ModelFitResult {
+    pub fit_level: FitLevel,
+    pub run_mode: RunMode,
+    /// Weights + KV cache + runtime overhead at the requested context.
+    pub estimated_vram_gb: f64,
+    /// The memory pool the model would load into: VRAM not held by other
+    /// processes on a GPU, the unified pool on Apple Silicon, or available
+    /// RAM otherwise.
+    pub available_vram_gb: f64,
+    pub recommended_backend: GpuBackend,
+    /// Bandwidth-bound decode speed for the run mode, as computed by
+    /// [`estimate_tokens_per_second`]. `None` when the model doesn't fit.
+    pub estimated_tokens_per_sec: Option<f64>,
+    /// Largest context (tokens) whose KV cache still fits alongside the
+    /// weights in the GPU pool, or in available RAM without a GPU. 0 when
+    /// the weights alone don't fit.
+    pub max_context: u32,
+    /// KV cache element type the estimate assumes.
+    pub kv_cache_quant: KvCacheQuant,
+    /// A more compressed KV cache that would fit better (on the GPU rather
+    /// than spilling, or at all), when that's what holds this one back.
+    pub kv_cache_suggestion: Option<KvCacheQuant>,
+    pub warnings: Vec<FitWarning>,
+}
+
+impl SystemSpecs {
+    /// Can a `params_billions` model at `quant` run here with `context_len`
+    /// tokens of context? Uses the same headroom rules as the model
+    /// database scoring in [`ModelFit::analyze`].
+    pub fn check_model_fit(
+        &self,
+        params_billions: f32,
+        quant: GgufQuantization,
+        context_len: u32,
+    ) -> ModelFitResult {
+        let estimated = models::model_memory_gb(params_billions, quant, context_len);
+        let weights = weights_gb(params_billions, quant);
+        let kv_per_token = models::approx_kv_cache_gb(params_billions as f64, 1);
+        self.check_model_fit_inner(estimated, weights, kv_per_token, false)
+    }
+
+    /// Like [`SystemSpecs::check_model_fit`], but sizes the KV cache from the
+    /// model's real layer / KV-head layout, which matters at long contexts.
+    pub fn check_arch_fit(
+        &self,
+        arch: &ModelArchConfig,
+        quant: GgufQuantization,
+        context_len: u32,
+    ) -> ModelFitResult {
+        self.check_arch_fit_with_kv(arch, quant, context_len, KvCacheQuant::F16)
+    }
+
+    /// Like [`SystemSpecs::check_arch_fit`], with the KV cache stored as
+    /// `kv_quant` (llama.cpp's `--cache-type-k/v`). When a more compressed
+    /// cache would move the model onto the GPU or make it fit at all, it is
+    /// reported in `kv_cache_suggestion` and the warnings.
+    pub fn check_arch_fit_with_kv(
+        &self,
+        arch: &ModelArchConfig,
+        quant: GgufQuantization,
+        context_len: u32,
+        kv_quant: KvCacheQuant,
+    ) -> ModelFitResult {
+        let mut result = self.arch_fit_at(arch, quant, context_len, kv_quant);
+        let better = KvCacheQuant::ALL
+            .iter()
+            .skip_while(|&&kv| kv != kv_quant)
+            .skip(1)
+            .map(|&kv| (kv, self.arch_fit_at(arch, quant, context_len, kv)))
+            .find(|(_, alt)| fits_better(alt, &result));
+        if let Some((kv, alt)) = better {
+            result.kv_cache_suggestion = Some(kv);
+            result.warnings.push(FitWarning::SmallerKvCache {
+                kv,
+                on_gpu: alt.run_mode == RunMode::Gpu,
+            });
+        }
+        result
+    }
+
+    /// Least compressed KV cache type that fits `context_len` tokens next to
+    /// the weights, per [`SystemSpecs::max_context_len`]; `None` if not
+    /// even a Q4_0 cache does.
+    pub fn kv_cache_for_context(
+        &self,
+        arch: &ModelArchConfig,
+        quant: GgufQuantization,
+        context_len: u32,
+    ) -> Option<KvCacheQuant> {
+        let weights = weights_gb(arch.params_billions, quant);
+        KvCacheQuant::ALL
+            .into_iter()
+            .find(|&kv| self.max_context_len(weights, arch.kv_cache_gb_with(1, kv)) >= context_len)
+    }
+
+    fn arch_fit_at(
+        &self,
+        arch: &ModelArchConfig,
+        quant: GgufQuantization,
+        context_len: u32,
+        kv_quant: KvCacheQuant,
+    ) -> ModelFitResult {
+        let weights = weights_gb(arch.params_billions, quant);
+        let estimated = arch.memory_gb_with(quant, context_len, kv_quant);
+        let kv_per_token = arch.kv_cache_gb_with(1, kv_quant);
+        let mut result = self.check_model_fit_inner(estimated, weights, kv_per_token, false);
+        result.kv_cache_quant = kv_quant;
+        result
+    }
+
+    /// Like [`SystemSpecs::check_model_fit`], but a model that only fits once
+    /// free swap is added to free RAM is reported as `Marginal` with a
+    /// "fits with swap (slow)" warning instead of `TooTight`.
+    pub fn check_model_fit_with_swap(
+        &self,
+        params_billions: f32,
+        quant: GgufQuantization,
+        context_len: u32,
+    ) -> ModelFitResult {
+        let estimated = models::model_memory_gb(params_billions, quant, context_len);
+        let weights = weights_gb(params_billions, quant);
+        let kv_per_token = models::approx_kv_cache_gb(params_billions as f64, 1);
+        self.check_model_fit_inner(estimated, weights, kv_per_token, true)
+    }
+
+    /// Largest context length whose KV cache, at `kv_gb_per_token`, fits
+    /// next to `weights_gb` of weights and the 0.5 GB runtime overhead in
+    /// the GPU pool (unified memory on Apple Silicon), or in available RAM
+    /// when there is no usable GPU. 0 if the weights alone don't fit.
+    pub fn max_context_len(&self, weights_gb: f64, kv_gb_per_token: f64) -> u32 {
+        let pool = self
+            .available_gpu_memory_gb()
+            .unwrap_or(self.available_ram_gb);
+        let budget = pool - weights_gb - 0.5;
+        if budget <= 0.0 {
+            return 0;
+        }
+        if kv_gb_per_token <= 0.0 {
+            return u32::MAX;
+        }
+        (budget / kv_gb_per_token).floor().min(u32::MAX as f64) as u32
+    }
+
+    fn check_model_fit_inner(
+        &self,
+        estimated: f64,
+        weights_gb: f64,
+        kv_gb_per_token: f64,
+        allow_swap: bool,
+    ) -> ModelFitResult {
+        // Aim for 20% headroom over the bare estimate, like the database entries
+        let recommended = estimated * 1.2;
+        let mut warnings = Vec::new();
+
+        let gpu_pool = self.available_gpu_memory_gb();
+
+        let (run_mode, available, backend) = match gpu_pool {
+            Some(vram) if estimated <= vram => (RunMode::Gpu, vram, self.backend),
+            Some(vram) if !self.unified_memory && estimated <= self.available_ram_gb => {
+                warnings.push(FitWarning::SpillsToRam {
+                    needed_gb: estimated,
+                    vram_gb: vram,
+                });
+                (RunMode::CpuOffload, self.available_ram_gb, self.backend)
+            }
+            Some(vram) => (RunMode::Gpu, vram, self.backend),
+            None => {
+                if self.has_gpu {
+                    warnings.push(FitWarning::VramUnknown);
+                }
+                (
+                    RunMode::CpuOnly,
+                    self.available_ram_gb,
+                    SystemSpecs::cpu_backend(&self.cpu_name),
+                )
+            }
+        };
+
+        let mut fit_level = score_fit(estimated, available, recommended, run_mode);
+        let mut available = available;
+        if allow_swap
+            && fit_level == FitLevel::TooTight
+            && self.swap_available_gb > 0.0
+            && estimated <= self.available_ram_gb + self.swap_available_gb
+        {
+            // Whatever doesn't fit in VRAM + RAM pages out to disk
+            fit_level = FitLevel::Marginal;
+            available = self.available_ram_gb + self.swap_available_gb;
+            warnings.push(FitWarning::FitsWithSwap {
+                needed_gb: estimated,
+                ram_gb: self.available_ram_gb,
+                swap_gb: self.swap_available_gb,
+            });
+        }
+        match fit_level {
+            FitLevel::TooTight => warnings.push(FitWarning::InsufficientMemory {
+                needed_gb: estimated,
+                available_gb: available,
+            }),
+            _ if estimated * 1.1 > available => warnings.push(FitWarning::NearCapacity {
+                on_gpu: run_mode == RunMode::Gpu,
+                needed_gb: estimated,
+                available_gb: available,
+            }),
+            _ => {}
+        }
+        if gpu_pool.is_some()
+            && !self.unified_memory
+            && self.vram_contended()
+            && let (Some(used), Some(free)) = (self.gpu_vram_used_gb, self.gpu_vram_available_gb)
+        {
+            warnings.push(FitWarning::VramPartiallyOccupied {
+                used_gb: used,
+                free_gb: free,
+            });
+        }
+        // Weights held in system RAM can only be pinned up to RLIMIT_MEMLOCK
+        if (run_mode != RunMode::Gpu || self.unified_memory)
+            && let Some(limit) = self.mlock_limit_gb
+            && weights_gb > limit
+        {
+            warnings.push(FitWarning::MlockLimitTooLow {
+                weights_gb,
+                limit_gb: limit,
+            });
+        }
+        if run_mode == RunMode::CpuOnly {
+            warnings.push(FitWarning::CpuOnly);
+        } else if let Some(warning) = thermal_warning(self) {
+            warnings.push(warning);
+        }
+
+        let estimated_tokens_per_sec = (fit_level != FitLevel::TooTight)
+            .then(|| bandwidth_tokens_per_sec(self, weights_gb, weights_gb, run_mode));
+
+        ModelFitResult {
+            fit_level,
+            run_mode,
+            estimated_vram_gb: estimated,
+            available_vram_gb: available,
+            recommended_backend: backend,
+            estimated_tokens_per_sec,
+            max_context: self.max_context_len(weights_gb, kv_gb_per_token),
+            kv_cache_quant: KvCacheQuant::F16,
+            kv_cache_suggestion: None,
+            warnings,
+        }
+    }
+}
+
+/// Warning for a GPU that was already hot when detected.
+fn thermal_warning(system: &SystemSpecs) -> Option<FitWarning> {
+    let temperature_c = system.gpu_temperature_c?;
+    system
+        .thermal_throttle_risk()
+        .then_some(FitWarning::ThermalThrottlingRisk { temperature_c })
+}
+
+/// Does `alt` run meaningfully better than `base`: a higher fit level, or
+/// fully on the GPU where `base` spills to RAM?
+fn fits_better(alt: &ModelFitResult, base: &ModelFitResult) -> bool {
+    if alt.fit_level == FitLevel::TooTight {
+        return false;
+    }
+    (alt.fit_level != base.fit_level && alt.fit_level.at_least(base.fit_level))
+        || (alt.run_mode == RunMode::Gpu && base.run_mode == RunMode::CpuOffload)
+}
+
+/// Verdict for a database model on `specs`, as a serializable value. Runs
+/// the same analysis as [`ModelFit::analyze`] (best quantization, MoE
+/// offload, context cap), so it agrees with the TUI and `fit` output.
+pub fn evaluate_fit(specs: &SystemSpecs, model: &LlmModel) -> ModelFitResult {
+    let fit = ModelFit::analyze(model, specs);
+    let bpp = models::quant_bpp(&fit.best_quant);
+    let weights = model.params_b() * bpp;
+    let recommended_backend = if fit.run_mode == RunMode::CpuOnly {
+        SystemSpecs::cpu_backend(&specs.cpu_name)
+    } else {
+        specs.backend
+    };
+
+    ModelFitResult {
+        fit_level: fit.fit_level,
+        run_mode: fit.run_mode,
+        estimated_vram_gb: fit.memory_required_gb,
+        available_vram_gb: fit.memory_available_gb,
+        recommended_backend,
+        estimated_tokens_per_sec: (fit.fit_level != FitLevel::TooTight).then(|| {
+            bandwidth_tokens_per_sec(specs, model.active_params_b() * bpp, weights, fit.run_mode)
+        }),
+        max_context: specs.max_context_len(weights, model.kv_cache_gb(1)),
+        kv_cache_quant: KvCacheQuant::F16,
+        kv_cache_suggestion: None,
+        warnings: fit.notes.into_iter().map(FitWarning::Note).collect(),
+    }
+}
+
+/// Layers of `model` at `quant` that fit in `vram_gb` for a partial offload.
+/// The embeddings and output head are counted as one extra block's worth
+/// and, with the KV cache and runtime overhead, stay in VRAM.
+fn offload_gpu_layers(model: &LlmModel, quant: &str, ctx: u32, vram_gb: f64) -> u32 {
+    let layers = model.estimated_layer_count();
+    let weights = model.params_b() * models::quant_bpp(quant);
+    let per_layer = weights / (layers + 1) as f64;
+    let non_layer = per_layer + model.kv_cache_gb(ctx) + 0.5;
+    gpu_specs::gpu_layers_that_fit(layers, per_layer, vram_gb, non_layer)
+}
+
+/// Upper bound on tokens/sec for `weights_gb` of dense weights when the
+/// part that doesn't fit in free RAM (plus discrete VRAM) is re-read from
+/// the NVMe drive for every token, as happens with llama.cpp's mmap
+/// loading. `None` without a known drive speed or when nothing spills.
+pub fn mmap_tokens_per_sec(system: &SystemSpecs, weights_gb: f64) -> Option<f64> {
+    let read_gb_s = system.nvme_read_bandwidth_gb_s.filter(|&bw| bw > 0.0)?;
+    let vram = if system.unified_memory {
+        0.0
+    } else {
+        system.available_gpu_memory_gb().unwrap_or(0.0)
+    };
+    let streamed_gb = weights_gb - system.available_ram_gb - vram;
+    (streamed_gb > 0.0).then(|| read_gb_s / streamed_gb)
+}
+
+/// Size of the quantized weights alone, in GB.
+fn weights_gb(params_billions: f32, quant: GgufQuantization) -> f64 {
+    params_billions as f64 * quant.bits_per_weight() as f64 / 8.0
+}
+
 /// Pure memory headroom scoring.
 /// - GPU (including Apple Silicon unified memory): can reach Perfect.
 /// - CpuOffload: caps at Good.
@@ -639,6 +1282,115 @@ pub fn rank_models_by_fit_opts_col(
     ranked
 }
 
+// ────────────────────────────────────────────────────────────────────
+// Multi-GPU sharding
+// ────────────────────────────────────────────────────────────────────
+
+/// How a model would be sharded across several identical GPUs, as returned
+/// by [`check_multi_gpu_fit`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct MultiGpuFitResult {
+    /// Cards each layer is split across (1 = no tensor parallelism).
+    pub tensor_parallel_degree: u32,
+    /// Pipeline stages the layers are divided into (1 = no pipelining).
+    pub pipeline_parallel_degree: u32,
+    pub fits: bool,
+    /// Estimated decode slowdown from inter-GPU traffic, in percent.
+    pub communication_overhead_pct: f64,
+    pub warnings: Vec<String>,
+}
+
+/// Can a `params_billions` model at `quant` be sharded over `gpu_count`
+/// cards of `vram_per_gpu_gb` each? NVLink favours tensor parallelism;
+/// over PCIe (or between unrelated cards) layers are pipelined instead,
+/// since all-reduce traffic on every layer would saturate the bus.
+pub fn check_multi_gpu_fit(
+    params_billions: f64,
+    quant: GgufQuantization,
+    gpu_count: u32,
+    vram_per_gpu_gb: f64,
+    link_topology: GpuLinkTopology,
+) -> MultiGpuFitResult {
+    let needed = models::model_vram_gb(params_billions as f32, quant);
+    multi_gpu_fit(needed, None, gpu_count, vram_per_gpu_gb, link_topology)
+}
+
+/// Like [`check_multi_gpu_fit`], sized from the model's architecture and
+/// warning when its attention heads can't be split evenly across the
+/// tensor-parallel degree.
+pub fn check_arch_multi_gpu_fit(
+    arch: &ModelArchConfig,
+    quant: GgufQuantization,
+    gpu_count: u32,
+    vram_per_gpu_gb: f64,
+    link_topology: GpuLinkTopology,
+) -> MultiGpuFitResult {
+    let needed = arch.memory_gb(quant, models::DEFAULT_CONTEXT_LENGTH);
+    multi_gpu_fit(
+        needed,
+        Some(arch.num_attention_heads),
+        gpu_count,
+        vram_per_gpu_gb,
+        link_topology,
+    )
+}
+
+fn multi_gpu_fit(
+    needed_gb: f64,
+    num_attention_heads: Option<u32>,
+    gpu_count: u32,
+    vram_per_gpu_gb: f64,
+    link_topology: GpuLinkTopology,
+) -> MultiGpuFitResult {
+    let gpu_count = gpu_count.max(1);
+    let nvlink = link_topology.has_nvlink();
+    // Each extra card over PCIe keeps its own context and staging buffers
+    let reserve = if nvlink { 0.0 } else { PCIE_SPLIT_RESERVE_GB };
+    let shard_fits =
+        |n: u32| needed_gb / n as f64 + (n - 1) as f64 * reserve / n as f64 <= vram_per_gpu_gb;
+    let degree = (1..=gpu_count).find(|&n| shard_fits(n));
+    let fits = degree.is_some();
+    let degree = degree.unwrap_or(gpu_count);
+
+    let (tensor_parallel_degree, pipeline_parallel_degree) =
+        if nvlink { (degree, 1) } else { (1, degree) };
+    // Per extra card: TP all-reduces every layer, PP only hands off
+    // activations at stage boundaries.
+    let per_card_pct = match link_topology {
+        GpuLinkTopology::NvlinkPod => 3.0,
+        GpuLinkTopology::PcieSli | GpuLinkTopology::SingleGpu => 8.0,
+        GpuLinkTopology::Independent => 12.0,
+    };
+    let communication_overhead_pct = (degree - 1) as f64 * per_card_pct;
+
+    let mut warnings = Vec::new();
+    if !fits {
+        warnings.push(format!(
+            "Needs {:.1} GB, only {:.1} GB across {} GPUs",
+            needed_gb,
+            vram_per_gpu_gb * gpu_count as f64,
+            gpu_count
+        ));
+    }
+    if let Some(heads) = num_attention_heads
+        && tensor_parallel_degree > 1
+        && heads % tensor_parallel_degree != 0
+    {
+        warnings.push(format!(
+            "{} attention heads don't divide evenly across TP={}; most runtimes will refuse this split",
+            heads, tensor_parallel_degree
+        ));
+    }
+
+    MultiGpuFitResult {
+        tensor_parallel_degree,
+        pipeline_parallel_degree,
+        fits,
+        communication_overhead_pct,
+        warnings,
+    }
+}
+
 // ────────────────────────────────────────────────────────────────────
 // Speed estimation
 // ────────────────────────────────────────────────────────────────────
@@ -653,19 +1405,34 @@ fn estimate_tps(
     runtime: InferenceRuntime,
 ) -> f64 {
     // Backend speed constant K (higher = faster)
-    let k: f64 = match (system.backend, runtime) {
+    let mut k: f64 = match (system.backend, runtime) {
         (GpuBackend::Metal, InferenceRuntime::Mlx) => 250.0,
         (GpuBackend::Metal, InferenceRuntime::LlamaCpp) => 160.0,
         (GpuBackend::Cuda, _) => 220.0,
         (GpuBackend::Rocm, _) => 180.0,
         (GpuBackend::Vulkan, _) => 150.0,
         (GpuBackend::Sycl, _) => 100.0,
+        (GpuBackend::OpenCL, _) => 80.0,
+        (GpuBackend::DirectML, _) => 120.0,
         (GpuBackend::CpuArm, _) => 90.0,
         (GpuBackend::CpuX86, _) => 70.0,
         (GpuBackend::Ascend, _) => 390.0,
     };
 
-    let params = model.params_b().max(0.1);
+    // Apple Silicon tiers differ mainly in memory bus width; within a tier,
+    // binned chips with fewer GPU cores also get a narrower bus.
+    if system.backend == GpuBackend::Metal
+        && let Some(variant) = system.apple_chip_variant
+    {
+        k *= variant.bandwidth_multiplier();
+        if let Some(cores) = system.gpu_core_count {
+            let ratio = cores as f64 / variant.typical_gpu_cores() as f64;
+            k *= ratio.sqrt().clamp(0.7, 1.3);
+        }
+    }
+
+    // Decode streams only the active experts of an MoE model per token
+    let params = model.active_params_b().max(0.1);
     let mut base = k / params;
 
     // Quantization speed multiplier
@@ -678,9 +1445,9 @@ fn estimate_tps(
 
     // Run mode penalties
     match run_mode {
-        RunMode::Gpu => {}                  // full speed
+        RunMode::Gpu => {}                                                    // full speed
         RunMode::MoeOffload => base *= 0.8, // expert switching latency
-        RunMode::CpuOffload => base *= 0.5, // significant penalty
+        RunMode::CpuOffload => base *= 0.5 * pcie_offload_multiplier(system), // significant penalty
         RunMode::CpuOnly => base *= 0.3,    // worst case—override K to CPU
     }
 
@@ -691,7 +1458,9 @@ fn estimate_tps(
         } else {
             70.0
         };
-        base = (cpu_k / params) * models::quant_speed_multiplier(quant);
+        base = (cpu_k / params)
+            * models::quant_speed_multiplier(quant)
+            * cpu_simd_multiplier(&system.cpu_features);
         if system.total_cpu_cores >= 8 {
             base *= 1.1;
         }
@@ -700,6 +1469,114 @@ fn estimate_tps(
     base.max(0.1)
 }
 
+/// Host link the CpuOffload penalty is calibrated against (PCIe 4.0 x16).
+const PCIE_REFERENCE_GBPS: f64 = 31.5;
+
+/// Scale offload speed by the GPU's PCIe bandwidth relative to 4.0 x16:
+/// spilled layers cross the link every token, so a 3.0 x4 riser slot is
+/// much slower. Unknown links leave the estimate unchanged.
+fn pcie_offload_multiplier(system: &SystemSpecs) -> f64 {
+    system
+        .pcie_bandwidth_gbps()
+        .map_or(1.0, |bw| (bw / PCIE_REFERENCE_GBPS).sqrt().clamp(0.5, 1.2))
+}
+
+/// Scale CPU throughput by the widest SIMD extension available.
+/// The CPU speed constants assume AVX2 (x86) or NEON (ARM); an empty
+/// feature list (unknown) leaves the estimate unchanged. AMX tiles mostly
+/// speed up prompt processing, so decode gains less than the raw FLOPs.
+fn cpu_simd_multiplier(features: &[String]) -> f64 {
+    if features.is_empty() {
+        return 1.0;
+    }
+    let has = |f: &str| features.iter().any(|x| x == f);
+    if has("amx") {
+        1.4
+    } else if has("avx512f") || has("sve") {
+        1.2
+    } else if has("avx2") || has("neon") {
+        1.0
+    } else if has("avx") {
+        0.8
+    } else {
+        // SSE-only
+        0.6
+    }
+}
+
+/// Decode speed on an AVX2/NEON core, expressed as GB of weights streamed
+/// per second. An 8-core AVX2 desktop manages ~10 tok/s on a 4 GB model.
+const CPU_GBPS_PER_CORE: f64 = 5.0;
+
+/// Tokens/sec for a model with `params_gb` of quantized weights running
+/// purely on the CPU. Cores beyond the first 8 add only a quarter each, as
+/// decoding runs into the memory bus long before it runs out of cores.
+pub fn estimate_cpu_tokens_per_sec(params_gb: f64, features: &[String], core_count: usize) -> f64 {
+    if params_gb <= 0.0 || core_count == 0 {
+        return 0.0;
+    }
+    let cores = core_count as f64;
+    let effective_cores = cores.min(8.0) + (cores - 8.0).max(0.0) * 0.25;
+    CPU_GBPS_PER_CORE * effective_cores * cpu_simd_multiplier(features) / params_gb
+}
+
+/// Rough decode speed of `model` (at its listed quantization) in
+/// `run_mode`, from memory bandwidth: each token streams the active
+/// weights once, so tok/s is bandwidth over bytes read. Coarser than the
+/// calibrated [`ModelFit::estimated_tps`], but it only needs the hardware
+/// and works for any run mode. Expect it to be within about 30%.
+pub fn estimate_tokens_per_second(specs: &SystemSpecs, model: &LlmModel, run_mode: RunMode) -> f64 {
+    let bpp = models::quant_bpp(&model.quantization);
+    bandwidth_tokens_per_sec(
+        specs,
+        model.active_params_b() * bpp,
+        model.params_b() * bpp,
+        run_mode,
+    )
+}
+
+/// Bandwidth-bound tok/s when `active_gb` of weights is read per token
+/// out of `total_gb` loaded. For a CPU offload, the share of the weights
+/// that fits the GPU pool is read at GPU speed and the rest at CPU speed.
+fn bandwidth_tokens_per_sec(
+    specs: &SystemSpecs,
+    active_gb: f64,
+    total_gb: f64,
+    run_mode: RunMode,
+) -> f64 {
+    let gpu_tps = |gb: f64| {
+        gpu_specs::estimate_gpu_tokens_per_sec(
+            gb,
+            specs.backend,
+            specs.gpu_name.as_deref().unwrap_or(""),
+        )
+    };
+    let cpu_tps =
+        |gb: f64| estimate_cpu_tokens_per_sec(gb, &specs.cpu_features, specs.total_cpu_cores);
+
+    match run_mode {
+        RunMode::Gpu => gpu_tps(active_gb),
+        // Active experts are paged in from RAM as routing changes
+        RunMode::MoeOffload => gpu_tps(active_gb) * 0.8,
+        RunMode::CpuOffload => {
+            let pool = specs.available_gpu_memory_gb().unwrap_or(0.0);
+            let gpu_share = if total_gb > 0.0 {
+                (pool / total_gb).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let gpu_gb = active_gb * gpu_share;
+            let cpu_gb = active_gb - gpu_gb;
+            let seconds = gpu_gb / gpu_tps(1.0).max(0.1) + cpu_gb / cpu_tps(1.0).max(0.1);
+            if seconds <= 0.0 {
+                return 0.0;
+            }
+            gpu_specs::HYBRID_SYNC_PENALTY / seconds
+        }
+        RunMode::CpuOnly => cpu_tps(active_gb),
+    }
+}
+
 // ────────────────────────────────────────────────────────────────────
 // Multi-dimensional scoring (Quality, Speed, Fit, Context)
 // ────────────────────────────────────────────────────────────────────
@@ -767,29 +1644,19 @@ fn quality_score(model: &LlmModel, quant: &str, use_case: UseCase) -> f64 {
 
     // Task alignment bump
     let task_bump = match use_case {
-        UseCase::Coding => {
+        UseCase::Coding
             if name_lower.contains("code")
                 || name_lower.contains("starcoder")
-                || name_lower.contains("wizard")
-            {
-                6.0
-            } else {
-                0.0
-            }
-        }
-        UseCase::Reasoning => {
-            if params >= 13.0 {
-                5.0
-            } else {
-                0.0
-            }
+                || name_lower.contains("wizard") =>
+        {
+            6.0
         }
-        UseCase::Multimodal => {
-            if name_lower.contains("vision") || model.use_case.to_lowercase().contains("vision") {
-                6.0
-            } else {
-                0.0
-            }
+        UseCase::Reasoning if params >= 13.0 => 5.0,
+        UseCase::Multimodal
+            if name_lower.contains("vision")
+                || model.use_case.to_lowercase().contains("vision") =>
+        {
+            6.0
         }
         _ => 0.0,
     };
@@ -865,6 +1732,380 @@ mod tests {
     use super::*;
     use crate::hardware::{GpuBackend, SystemSpecs};
 
+    // ────────────────────────────────────────────────────────────────────
+    // SystemSpecs::check_model_fit
+    // ────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_check_model_fit_gpu() {
+        let system = test_system(64.0, true, Some(24.0));
+        let result = system.check_model_fit(8.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(result.fit_level, FitLevel::Perfect);
+        // 24 GB less the 1 GB CUDA runtime overhead
+        assert_eq!(result.available_vram_gb, 23.0);
+        assert_eq!(result.recommended_backend, GpuBackend::Cuda);
+        assert!(result.warnings.is_empty());
+        assert!(result.estimated_tokens_per_sec.is_some_and(|tps| tps > 0.0));
+
+        // 70B at Q4_K_M (~41 GB) won't fit 24 GB VRAM but will spill into RAM
+        let result = system.check_model_fit(70.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(result.fit_level, FitLevel::Marginal);
+        assert_eq!(result.run_mode, RunMode::CpuOffload);
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| matches!(w, FitWarning::SpillsToRam { .. }))
+        );
+        // Slower than the 8B on the GPU, but still an estimate
+        let offload_tps = result.estimated_tokens_per_sec.unwrap();
+        assert!(offload_tps > 0.0);
+        assert!(offload_tps < 5.0);
+
+        // 70B at F16 fits nowhere
+        let result = system.check_model_fit(70.0, GgufQuantization::F16, 4096);
+        assert_eq!(result.fit_level, FitLevel::TooTight);
+    }
+
+    #[test]
+    fn test_hot_gpu_warns_of_throttling() {
+        let mut system = test_system(64.0, true, Some(24.0));
+        system.gpu_temperature_c = Some(86.0);
+        let result = system.check_model_fit(8.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(result.fit_level, FitLevel::Perfect);
+        assert_eq!(
+            result.warnings,
+            vec![FitWarning::ThermalThrottlingRisk {
+                temperature_c: 86.0
+            }]
+        );
+        assert!(result.warnings[0].to_string().contains("86°C"));
+
+        system.gpu_temperature_c = Some(65.0);
+        let result = system.check_model_fit(8.0, GgufQuantization::Q4_K_M, 4096);
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_fit_level_at_least_and_from_str() {
+        assert!(FitLevel::Perfect.at_least(FitLevel::Good));
+        assert!(FitLevel::Marginal.at_least(FitLevel::Marginal));
+        assert!(!FitLevel::Marginal.at_least(FitLevel::Good));
+        assert!(!FitLevel::TooTight.at_least(FitLevel::Marginal));
+
+        assert_eq!("Good".parse::<FitLevel>(), Ok(FitLevel::Good));
+        assert_eq!("too-tight".parse::<FitLevel>(), Ok(FitLevel::TooTight));
+        assert_eq!("Too Tight".parse::<FitLevel>(), Ok(FitLevel::TooTight));
+        assert!("great".parse::<FitLevel>().is_err());
+    }
+
+    #[test]
+    fn test_evaluate_fit_matches_analyze() {
+        let system = test_system(64.0, true, Some(24.0));
+        let model = test_model("7B", 4.0, Some(4.0));
+        let fit = ModelFit::analyze(&model, &system);
+
+        let result = evaluate_fit(&system, &model);
+        assert_eq!(result.fit_level, fit.fit_level);
+        assert_eq!(result.run_mode, RunMode::Gpu);
+        assert_eq!(result.estimated_vram_gb, fit.memory_required_gb);
+        let weights = model.params_b() * models::quant_bpp(&fit.best_quant);
+        assert_eq!(
+            result.estimated_tokens_per_sec,
+            Some(gpu_specs::estimate_gpu_tokens_per_sec(
+                weights,
+                GpuBackend::Cuda,
+                "Test GPU"
+            ))
+        );
+        assert!(result.max_context > 4096);
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["run_mode"], "Gpu");
+        assert_eq!(json["recommended_backend"], "Cuda");
+
+        // Nowhere near enough memory: no speed estimate
+        let result = evaluate_fit(
+            &test_system(8.0, false, None),
+            &test_model("405B", 230.0, None),
+        );
+        assert_eq!(result.fit_level, FitLevel::TooTight);
+        assert_eq!(result.run_mode, RunMode::CpuOnly);
+        assert_eq!(result.estimated_tokens_per_sec, None);
+    }
+
+    #[test]
+    fn test_check_model_fit_warns_when_tight() {
+        // 8B Q8_0 at 4K needs ~9.26 GB; 10 GB usable after CUDA's overhead
+        let system = test_system(64.0, true, Some(11.0));
+        let result = system.check_model_fit(8.0, GgufQuantization::Q8_0, 4096);
+        assert_eq!(result.fit_level, FitLevel::Marginal);
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| matches!(w, FitWarning::NearCapacity { .. }))
+        );
+    }
+
+    #[test]
+    fn test_check_arch_fit_counts_kv_cache() {
+        let system = test_system(64.0, true, Some(12.0));
+        let arch = ModelArchConfig::LLAMA_3_8B;
+        let short = system.check_arch_fit(&arch, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(short.fit_level, FitLevel::Perfect);
+        // 128K of F16 KV cache alone is ~17 GB
+        let long = system.check_arch_fit(&arch, GgufQuantization::Q4_K_M, 131_072);
+        assert!(long.estimated_vram_gb > short.estimated_vram_gb + 16.0);
+        assert_ne!(long.fit_level, FitLevel::Perfect);
+    }
+
+    #[test]
+    fn test_check_arch_fit_depends_on_context_and_kv_type() {
+        let system = test_system(64.0, true, Some(8.0));
+        let arch = ModelArchConfig::LLAMA_3_8B;
+        let at_4k = system.check_arch_fit(&arch, GgufQuantization::Q4_K_M, 4096);
+        let spills = |r: &ModelFitResult| {
+            r.warnings
+                .iter()
+                .any(|w| matches!(w, FitWarning::SpillsToRam { .. }))
+        };
+        assert!(!spills(&at_4k));
+        // 32K of F16 cache (~4.3 GB) pushes it past 8 GB of VRAM...
+        let at_32k = system.check_arch_fit(&arch, GgufQuantization::Q4_K_M, 32_768);
+        assert!(spills(&at_32k));
+        // ...while a Q4_0 cache brings it back on the GPU
+        let q4_kv = system.check_arch_fit_with_kv(
+            &arch,
+            GgufQuantization::Q4_K_M,
+            32_768,
+            KvCacheQuant::Q4_0,
+        );
+        assert!(!spills(&q4_kv));
+        assert!(q4_kv.estimated_vram_gb < at_32k.estimated_vram_gb - 2.5);
+        assert_eq!(q4_kv.kv_cache_quant, KvCacheQuant::Q4_0);
+    }
+
+    #[test]
+    fn test_quantized_kv_cache_suggested_when_it_makes_the_fit() {
+        let system = test_system(64.0, true, Some(8.0));
+        let arch = ModelArchConfig::LLAMA_3_8B;
+
+        // Fits at 4K as is: nothing to suggest
+        let at_4k = system.check_arch_fit(&arch, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(at_4k.kv_cache_suggestion, None);
+
+        // 32K spills with F16; Q8_0 (~2.3 GB) doesn't quite make it in the
+        // 7 GB pool, Q4_0 does
+        let at_32k = system.check_arch_fit(&arch, GgufQuantization::Q4_K_M, 32_768);
+        assert_eq!(at_32k.run_mode, RunMode::CpuOffload);
+        assert_eq!(at_32k.kv_cache_suggestion, Some(KvCacheQuant::Q4_0));
+        assert!(at_32k.warnings.contains(&FitWarning::SmallerKvCache {
+            kv: KvCacheQuant::Q4_0,
+            on_gpu: true
+        }));
+        // Already at the most compressed type: nowhere left to go
+        let q4 = system.check_arch_fit_with_kv(
+            &arch,
+            GgufQuantization::Q4_K_M,
+            32_768,
+            KvCacheQuant::Q4_0,
+        );
+        assert_eq!(q4.kv_cache_suggestion, None);
+
+        assert_eq!(
+            system.kv_cache_for_context(&arch, GgufQuantization::Q4_K_M, 4096),
+            Some(KvCacheQuant::F16)
+        );
+        assert_eq!(
+            system.kv_cache_for_context(&arch, GgufQuantization::Q4_K_M, 32_768),
+            Some(KvCacheQuant::Q4_0)
+        );
+        assert_eq!(
+            system.kv_cache_for_context(&arch, GgufQuantization::Q4_K_M, 1_000_000),
+            None
+        );
+    }
+
+    #[test]
+    fn test_max_context_fills_remaining_vram() {
+        let system = test_system(64.0, true, Some(12.0));
+        let arch = ModelArchConfig::LLAMA_3_8B;
+        let result = system.check_arch_fit(&arch, GgufQuantization::Q4_K_M, 4096);
+        // The reported maximum fits exactly; one more token doesn't
+        let at_max = arch.memory_gb(GgufQuantization::Q4_K_M, result.max_context);
+        let past_max = arch.memory_gb(GgufQuantization::Q4_K_M, result.max_context + 1);
+        // 12 GB card less the 1 GB CUDA runtime overhead
+        assert!(at_max <= 11.0 && past_max > 11.0);
+        // A Q8_0 cache roughly doubles it
+        let q8 = system.check_arch_fit_with_kv(
+            &arch,
+            GgufQuantization::Q4_K_M,
+            4096,
+            KvCacheQuant::Q8_0,
+        );
+        assert!(q8.max_context > result.max_context * 18 / 10);
+        // Weights that don't fit leave no room for context
+        assert_eq!(system.max_context_len(12.0, 0.0001), 0);
+    }
+
+    #[test]
+    fn test_check_model_fit_with_swap() {
+        let mut system = test_system(16.0, false, None);
+        system.swap_available_gb = 16.0;
+        // 70B Q4_K_M (~41 GB) is far beyond 12.8 GB of free RAM...
+        let plain = system.check_model_fit(70.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(plain.fit_level, FitLevel::TooTight);
+        // ...and still too big with 16 GB swap, while 30B (~18.7 GB) squeezes in
+        let with_swap = system.check_model_fit_with_swap(70.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(with_swap.fit_level, FitLevel::TooTight);
+        let with_swap = system.check_model_fit_with_swap(30.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(with_swap.fit_level, FitLevel::Marginal);
+        assert!(
+            with_swap
+                .warnings
+                .iter()
+                .any(|w| matches!(w, FitWarning::FitsWithSwap { .. }))
+        );
+        assert_eq!(
+            system
+                .check_model_fit(30.0, GgufQuantization::Q4_K_M, 4096)
+                .fit_level,
+            FitLevel::TooTight
+        );
+    }
+
+    #[test]
+    fn test_check_model_fit_cpu_only() {
+        let system = test_system(32.0, false, None);
+        let result = system.check_model_fit(8.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(result.fit_level, FitLevel::Marginal);
+        assert!(matches!(
+            result.recommended_backend,
+            GpuBackend::CpuArm | GpuBackend::CpuX86
+        ));
+        assert!(result.warnings.contains(&FitWarning::CpuOnly));
+        assert!(result.estimated_tokens_per_sec.is_some_and(|tps| tps > 0.0));
+    }
+
+    #[test]
+    fn test_check_model_fit_cpu_speed_uses_cpu_features() {
+        let mut system = test_system(32.0, false, None);
+        let baseline = system
+            .check_model_fit(8.0, GgufQuantization::Q4_K_M, 4096)
+            .estimated_tokens_per_sec
+            .unwrap();
+        system.cpu_features = vec!["avx2".to_string(), "avx512f".to_string()];
+        let avx512 = system
+            .check_model_fit(8.0, GgufQuantization::Q4_K_M, 4096)
+            .estimated_tokens_per_sec
+            .unwrap();
+        assert!(avx512 > baseline);
+    }
+
+    #[test]
+    fn test_estimate_cpu_tokens_per_sec() {
+        let feats = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let avx2 = feats(&["avx2"]);
+        let avx512 = feats(&["avx2", "avx512f"]);
+        let amx = feats(&["avx2", "avx512f", "amx"]);
+
+        let base = estimate_cpu_tokens_per_sec(4.0, &avx2, 8);
+        assert!((base - 10.0).abs() < 1e-9);
+        let with_avx512 = estimate_cpu_tokens_per_sec(4.0, &avx512, 8);
+        assert!(with_avx512 > base);
+        assert!(estimate_cpu_tokens_per_sec(4.0, &amx, 8) > with_avx512);
+
+        // Doubling cores past 8 gives far less than double the speed
+        let many = estimate_cpu_tokens_per_sec(4.0, &avx2, 16);
+        assert!(many > base && many < base * 1.5);
+        assert_eq!(estimate_cpu_tokens_per_sec(4.0, &avx2, 0), 0.0);
+    }
+
+    #[test]
+    fn test_check_multi_gpu_fit_nvlink_uses_tensor_parallel() {
+        // 70B Q4_K_M (~41 GB) across 2x 24 GB linked by NVLink
+        let result = check_multi_gpu_fit(
+            70.0,
+            GgufQuantization::Q4_K_M,
+            2,
+            24.0,
+            GpuLinkTopology::NvlinkPod,
+        );
+        assert!(result.fits);
+        assert_eq!(result.tensor_parallel_degree, 2);
+        assert_eq!(result.pipeline_parallel_degree, 1);
+        assert!(result.warnings.is_empty());
+
+        let pcie = check_multi_gpu_fit(
+            70.0,
+            GgufQuantization::Q4_K_M,
+            2,
+            24.0,
+            GpuLinkTopology::PcieSli,
+        );
+        assert!(pcie.fits);
+        assert_eq!(pcie.tensor_parallel_degree, 1);
+        assert_eq!(pcie.pipeline_parallel_degree, 2);
+        assert!(pcie.communication_overhead_pct > result.communication_overhead_pct);
+    }
+
+    #[test]
+    fn test_check_multi_gpu_fit_single_card_and_too_big() {
+        let small = check_multi_gpu_fit(
+            8.0,
+            GgufQuantization::Q4_K_M,
+            4,
+            24.0,
+            GpuLinkTopology::NvlinkPod,
+        );
+        assert!(small.fits);
+        assert_eq!(small.tensor_parallel_degree, 1);
+        assert_eq!(small.communication_overhead_pct, 0.0);
+
+        let huge = check_multi_gpu_fit(
+            405.0,
+            GgufQuantization::Q4_K_M,
+            2,
+            24.0,
+            GpuLinkTopology::PcieSli,
+        );
+        assert!(!huge.fits);
+        assert_eq!(huge.pipeline_parallel_degree, 2);
+        assert!(huge.warnings.iter().any(|w| w.contains("across 2 GPUs")));
+    }
+
+    #[test]
+    fn test_check_arch_multi_gpu_fit_warns_on_uneven_heads() {
+        // Mixtral (~27 GB at Q4_K_M) needs 3x 12 GB cards; 32 heads / 3 is uneven
+        let arch = ModelArchConfig::MIXTRAL_8X7B;
+        let result = check_arch_multi_gpu_fit(
+            &arch,
+            GgufQuantization::Q4_K_M,
+            4,
+            12.0,
+            GpuLinkTopology::NvlinkPod,
+        );
+        assert!(result.fits);
+        assert_eq!(result.tensor_parallel_degree, 3);
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.contains("attention heads"))
+        );
+
+        let even = check_arch_multi_gpu_fit(
+            &arch,
+            GgufQuantization::Q4_K_M,
+            4,
+            16.0,
+            GpuLinkTopology::NvlinkPod,
+        );
+        assert_eq!(even.tensor_parallel_degree, 2);
+        assert!(even.warnings.is_empty());
+    }
+
     // ────────────────────────────────────────────────────────────────────
     // Helper to create test model
     // ────────────────────────────────────────────────────────────────────
@@ -890,28 +2131,14 @@ mod tests {
     }
 
     fn test_system(ram: f64, has_gpu: bool, vram: Option<f64>) -> SystemSpecs {
-        SystemSpecs {
-            total_ram_gb: ram,
-            available_ram_gb: ram * 0.8, // simulate some usage
-            total_cpu_cores: 8,
-            cpu_name: "Test CPU".to_string(),
-            has_gpu,
-            gpu_vram_gb: vram,
-            total_gpu_vram_gb: vram, // same as gpu_vram_gb for single-GPU tests
-            gpu_name: if has_gpu {
-                Some("Test GPU".to_string())
-            } else {
-                None
-            },
-            gpu_count: if has_gpu { 1 } else { 0 },
-            unified_memory: false,
-            backend: if has_gpu {
-                GpuBackend::Cuda
-            } else {
-                GpuBackend::CpuX86
-            },
-            gpus: vec![],
-        }
+        let backend = if has_gpu {
+            GpuBackend::Cuda
+        } else {
+            GpuBackend::CpuX86
+        };
+        let mut system = SystemSpecs::mock(vram.unwrap_or(0.0), ram, backend);
+        system.available_ram_gb = ram * 0.8; // simulate some usage
+        system
     }
 
     // ────────────────────────────────────────────────────────────────────
@@ -978,7 +2205,8 @@ mod tests {
     #[test]
     fn test_model_fit_gpu_path() {
         let model = test_model("7B", 4.0, Some(4.0));
-        let system = test_system(16.0, true, Some(8.0));
+        // 8 GB usable after the 1 GB CUDA runtime overhead
+        let system = test_system(16.0, true, Some(9.0));
 
         let fit = ModelFit::analyze(&model, &system);
 
@@ -988,6 +2216,205 @@ mod tests {
         assert_eq!(fit.memory_available_gb, 8.0);
     }
 
+    #[test]
+    fn test_model_fit_notes_multi_gpu_split() {
+        let model = test_model("20B", 12.0, Some(12.0));
+        let mut system = test_system(64.0, true, Some(12.0));
+        system.total_gpu_vram_gb = Some(24.0);
+        system.gpu_vram_gb_per_device = vec![12.0, 12.0];
+        system.gpu_count = 2;
+
+        let fit = ModelFit::analyze(&model, &system);
+
+        assert_eq!(fit.run_mode, RunMode::Gpu);
+        assert!(fit.memory_required_gb > 12.0);
+        assert!(fit.notes.iter().any(|n| n.starts_with("Multi-GPU")));
+        assert!(!fit.notes.iter().any(|n| n.starts_with("NVLink")));
+
+        system.gpu_link_topology = GpuLinkTopology::NvlinkPod;
+        let fit = ModelFit::analyze(&model, &system);
+        assert!(fit.notes.iter().any(|n| n.starts_with("NVLink")));
+    }
+
+    #[test]
+    fn test_check_model_fit_nvlink_pools_full_vram() {
+        let mut system = test_system(128.0, true, Some(24.0));
+        system.total_gpu_vram_gb = Some(48.0);
+        system.gpu_vram_gb_per_device = vec![24.0, 24.0];
+        system.gpu_count = 2;
+
+        // Both lose the 1 GB CUDA runtime overhead; PCIe also the split reserve
+        let pcie = system.check_model_fit(70.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(pcie.available_vram_gb, 46.5);
+        system.gpu_link_topology = GpuLinkTopology::NvlinkPod;
+        let nvlink = system.check_model_fit(70.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(nvlink.available_vram_gb, 47.0);
+    }
+
+    #[test]
+    fn test_runtime_overhead_is_reserved_before_fitting() {
+        // 8B Q8_0 at 4K needs ~9.26 GB: fits 9.5 GB on paper, not once
+        // CUDA's context and compute buffers are accounted for
+        let mut system = test_system(8.0, true, Some(9.5));
+        system.overhead_gb = Some(0.0);
+        let bare = system.check_model_fit(8.0, GgufQuantization::Q8_0, 4096);
+        assert_eq!(bare.run_mode, RunMode::Gpu);
+        assert_ne!(bare.fit_level, FitLevel::TooTight);
+
+        system.overhead_gb = None;
+        let real = system.check_model_fit(8.0, GgufQuantization::Q8_0, 4096);
+        assert_eq!(real.available_vram_gb, 8.5);
+        assert_eq!(real.fit_level, FitLevel::TooTight);
+
+        // ModelFit::analyze reports the same reduced pool
+        let model = test_model("7B", 4.0, Some(4.0));
+        let fit = ModelFit::analyze(&model, &system.with_overhead_override(2.0));
+        assert_eq!(fit.memory_available_gb, 7.5);
+    }
+
+    #[test]
+    fn test_model_fit_judges_against_free_vram() {
+        let model = test_model("7B", 4.0, Some(4.0));
+        let mut system = test_system(32.0, true, Some(8.0));
+        assert_eq!(ModelFit::analyze(&model, &system).run_mode, RunMode::Gpu);
+
+        // Agrees with check_model_fit once 6 GB is held elsewhere
+        system.gpu_vram_available_gb = Some(2.0);
+        system.gpu_vram_used_gb = Some(6.0);
+        let fit = ModelFit::analyze(&model, &system);
+
+        assert_ne!(fit.run_mode, RunMode::Gpu);
+        assert!(
+            fit.notes
+                .iter()
+                .any(|n| n.contains("held by other processes"))
+        );
+    }
+
+    #[test]
+    fn test_check_model_fit_warns_when_vram_is_occupied() {
+        let mut system = test_system(32.0, true, Some(24.0));
+        system.gpu_vram_available_gb = Some(16.0);
+        system.gpu_vram_used_gb = Some(8.0);
+
+        // 7B Q4_K_M fits in the 16 GB left over
+        let result = system.check_model_fit(7.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(result.run_mode, RunMode::Gpu);
+        assert!(
+            result
+                .warnings
+                .contains(&FitWarning::VramPartiallyOccupied {
+                    used_gb: 8.0,
+                    free_gb: 16.0
+                })
+        );
+        assert_eq!(result.available_vram_gb, 15.0);
+
+        // An idle card gets no warning
+        system.gpu_vram_available_gb = Some(23.5);
+        system.gpu_vram_used_gb = Some(0.5);
+        let result = system.check_model_fit(7.0, GgufQuantization::Q4_K_M, 4096);
+        assert!(
+            !result
+                .warnings
+                .iter()
+                .any(|w| matches!(w, FitWarning::VramPartiallyOccupied { .. }))
+        );
+    }
+
+    #[test]
+    fn test_check_model_fit_judges_against_free_vram() {
+        let mut system = test_system(32.0, true, Some(24.0));
+        let idle = system.check_model_fit(14.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(idle.run_mode, RunMode::Gpu);
+
+        // 18 GB held elsewhere: the 14B no longer fits next to it
+        system.gpu_vram_available_gb = Some(6.0);
+        system.gpu_vram_used_gb = Some(18.0);
+        let busy = system.check_model_fit(14.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(busy.run_mode, RunMode::CpuOffload);
+        assert!(
+            busy.warnings
+                .iter()
+                .any(|w| matches!(w, FitWarning::SpillsToRam { .. }))
+        );
+        assert!(busy.warnings.contains(&FitWarning::VramPartiallyOccupied {
+            used_gb: 18.0,
+            free_gb: 6.0
+        }));
+    }
+
+    #[test]
+    fn test_mmap_tokens_per_sec_streams_the_overflow() {
+        // 12.8 GB of RAM free, no GPU
+        let mut system = test_system(16.0, false, None);
+        assert_eq!(mmap_tokens_per_sec(&system, 20.8), None);
+
+        system.nvme_read_bandwidth_gb_s = Some(4.0);
+        let tps = mmap_tokens_per_sec(&system, 20.8).unwrap();
+        assert!((tps - 0.5).abs() < 1e-9);
+        assert_eq!(mmap_tokens_per_sec(&system, 10.0), None);
+
+        let model = test_model("70B", 40.0, Some(40.0));
+        let fit = ModelFit::analyze(&model, &system);
+        assert_eq!(fit.fit_level, FitLevel::TooTight);
+        assert!(
+            fit.mmap_tokens_per_sec
+                .is_some_and(|tps| tps > 0.0 && tps < 1.0)
+        );
+        assert!(fit.notes.iter().any(|n| n.starts_with("mmap from NVMe")));
+    }
+
+    #[test]
+    fn test_fit_warning_serializes_as_its_message() {
+        let warning = FitWarning::NearCapacity {
+            on_gpu: true,
+            needed_gb: 7.25,
+            available_gb: 7.5,
+        };
+        assert_eq!(
+            serde_json::to_value(&warning).unwrap(),
+            "VRAM within 10% of requirement (7.2 of 7.5 GB)"
+        );
+    }
+
+    #[test]
+    fn test_check_model_fit_warns_when_mlock_limit_is_low() {
+        let mut system = test_system(64.0, false, None);
+        // The usual 8 MB default
+        system.mlock_limit_gb = Some(8.0 / 1024.0);
+        let result = system.check_model_fit(7.0, GgufQuantization::Q4_K_M, 4096);
+        assert_eq!(result.run_mode, RunMode::CpuOnly);
+        let warning = result
+            .warnings
+            .iter()
+            .find(|w| matches!(w, FitWarning::MlockLimitTooLow { .. }))
+            .unwrap()
+            .to_string();
+        assert!(warning.contains("mlock is limited to 0.01 GB"));
+        assert!(warning.contains("limits.conf") && warning.contains("LimitMEMLOCK"));
+
+        system.mlock_limit_gb = None;
+        let result = system.check_model_fit(7.0, GgufQuantization::Q4_K_M, 4096);
+        assert!(
+            !result
+                .warnings
+                .iter()
+                .any(|w| matches!(w, FitWarning::MlockLimitTooLow { .. }))
+        );
+
+        // Weights in discrete VRAM aren't pinned in RAM
+        let mut gpu = test_system(64.0, true, Some(24.0));
+        gpu.mlock_limit_gb = Some(0.0);
+        let result = gpu.check_model_fit(7.0, GgufQuantization::Q4_K_M, 4096);
+        assert!(
+            !result
+                .warnings
+                .iter()
+                .any(|w| matches!(w, FitWarning::MlockLimitTooLow { .. }))
+        );
+    }
+
     #[test]
     fn test_model_fit_cpu_only() {
         let model = test_model("7B", 4.0, Some(4.0));
@@ -1001,6 +2428,27 @@ mod tests {
         assert_eq!(fit.fit_level, FitLevel::Marginal);
     }
 
+    #[test]
+    fn test_model_fit_cpu_only_notes_numa() {
+        let model = test_model("7B", 4.0, Some(4.0));
+        let mut system = test_system(16.0, false, None);
+        let has_numa_note = |fit: &ModelFit| fit.notes.iter().any(|n| n.contains("NUMA"));
+
+        assert!(!has_numa_note(&ModelFit::analyze(&model, &system)));
+        system.numa_nodes = 2;
+        assert!(has_numa_note(&ModelFit::analyze(&model, &system)));
+
+        // Fully on the GPU, NUMA placement doesn't matter
+        let gpu_system = SystemSpecs {
+            numa_nodes: 2,
+            overhead_gb: None,
+            ..test_system(16.0, true, Some(24.0))
+        };
+        let fit = ModelFit::analyze(&model, &gpu_system);
+        assert_eq!(fit.run_mode, RunMode::Gpu);
+        assert!(!has_numa_note(&fit));
+    }
+
     #[test]
     fn test_model_fit_cpu_offload() {
         let model = test_model("13B", 8.0, Some(8.0));
@@ -1015,6 +2463,10 @@ mod tests {
                 .iter()
                 .any(|n| n.contains("spilling to system RAM"))
         );
+        // Only part of the 40 layers fit in 4 GB
+        let layers = fit.gpu_layers.unwrap();
+        assert!(layers > 0 && layers < model.estimated_layer_count());
+        assert!(fit.notes.iter().any(|n| n.contains("--n-gpu-layers")));
     }
 
     #[test]
@@ -1318,6 +2770,31 @@ mod tests {
         assert!(tps_mlx / tps_llamacpp > 1.4);
     }
 
+    #[test]
+    fn test_estimate_tps_scales_with_apple_gpu_cores() {
+        let model = test_model("7B", 4.0, Some(4.0));
+        let mut system = test_system(64.0, true, Some(64.0));
+        system.backend = GpuBackend::Metal;
+        system.unified_memory = true;
+        system.apple_chip_variant = Some(crate::hardware::AppleChipVariant::Max);
+        let tps = |system: &SystemSpecs| {
+            estimate_tps(
+                &model,
+                "Q4_K_M",
+                system,
+                RunMode::Gpu,
+                InferenceRuntime::Mlx,
+            )
+        };
+
+        let unknown = tps(&system);
+        system.gpu_core_count = Some(30); // binned M3 Max
+        let binned = tps(&system);
+        system.gpu_core_count = Some(40);
+        let full = tps(&system);
+        assert!(binned < unknown && unknown < full);
+    }
+
     #[test]
     fn test_analyze_selects_mlx_on_apple_silicon() {
         let model = test_model("7B", 4.0, Some(4.0));
@@ -1358,6 +2835,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cpu_simd_multiplier() {
+        let feats = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(cpu_simd_multiplier(&[]), 1.0);
+        assert_eq!(cpu_simd_multiplier(&feats(&["sse4.2", "avx", "avx2"])), 1.0);
+        assert!(cpu_simd_multiplier(&feats(&["avx2", "avx512f"])) > 1.0);
+        assert!(cpu_simd_multiplier(&feats(&["sse4.2"])) < 1.0);
+    }
+
     #[test]
     fn test_estimate_tps_run_mode_penalties() {
         let model = test_model("7B", 4.0, Some(4.0));
@@ -1402,6 +2888,86 @@ mod tests {
         assert!(tps_cpu > 0.0);
     }
 
+    #[test]
+    fn test_estimate_tokens_per_second_by_run_mode() {
+        let model = test_model("8B", 5.0, Some(5.0));
+        // 2 GB usable: under half the weights fit for a CPU offload
+        let system = test_system(64.0, true, Some(3.0));
+        let tps = |mode| estimate_tokens_per_second(&system, &model, mode);
+
+        // 8B at Q4_K_M is ~4.6 GB read per token at the 450 GB/s CUDA default
+        let weights = 8.0 * models::quant_bpp("Q4_K_M");
+        assert!((tps(RunMode::Gpu) - 450.0 * 0.7 / weights).abs() < 1e-9);
+        assert!(tps(RunMode::Gpu) > tps(RunMode::MoeOffload));
+        assert!(tps(RunMode::MoeOffload) > tps(RunMode::CpuOffload));
+        assert!(tps(RunMode::CpuOffload) > tps(RunMode::CpuOnly));
+        assert!(tps(RunMode::CpuOnly) > 0.0);
+
+        // Only the active experts are read per token
+        let moe = LlmModel {
+            is_moe: true,
+            num_experts: Some(8),
+            active_experts: Some(2),
+            active_parameters: Some(2_000_000_000),
+            ..model.clone()
+        };
+        let moe_tps = estimate_tokens_per_second(&system, &moe, RunMode::Gpu);
+        assert!(moe_tps > tps(RunMode::Gpu) * 3.0);
+    }
+
+    #[test]
+    fn test_moe_speed_uses_active_params() {
+        let dense = test_model("30B", 20.0, Some(20.0));
+        let moe = LlmModel {
+            is_moe: true,
+            num_experts: Some(128),
+            active_experts: Some(8),
+            active_parameters: Some(3_000_000_000),
+            ..dense.clone()
+        };
+        let system = test_system(64.0, true, Some(48.0));
+        let tps = |m: &LlmModel| {
+            estimate_tps(
+                m,
+                "Q4_K_M",
+                &system,
+                RunMode::Gpu,
+                InferenceRuntime::LlamaCpp,
+            )
+        };
+        // ~10x fewer weights streamed per token
+        assert!(tps(&moe) > tps(&dense) * 8.0);
+
+        let fit = ModelFit::analyze(&moe, &system);
+        assert!(fit.notes.iter().any(|n| n.contains("3.0B active")));
+    }
+
+    #[test]
+    fn test_offload_tps_penalised_on_narrow_pcie_link() {
+        let model = test_model("7B", 4.0, Some(4.0));
+        let mut system = test_system(16.0, true, Some(10.0));
+        let offload_tps = |system: &SystemSpecs| {
+            estimate_tps(
+                &model,
+                "Q4_K_M",
+                system,
+                RunMode::CpuOffload,
+                InferenceRuntime::LlamaCpp,
+            )
+        };
+
+        let unknown = offload_tps(&system);
+        system.pcie_gen = Some(4);
+        system.pcie_width = Some(16);
+        let gen4_x16 = offload_tps(&system);
+        system.pcie_gen = Some(3);
+        system.pcie_width = Some(4);
+        let gen3_x4 = offload_tps(&system);
+
+        assert!((gen4_x16 - unknown).abs() < 0.5);
+        assert!(gen3_x4 < gen4_x16 * 0.6);
+    }
+
     // ────────────────────────────────────────────────────────────────────
     // Release date sorting tests
     // ────────────────────────────────────────────────────────────────────
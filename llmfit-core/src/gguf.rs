@@ -0,0 +1,484 @@
+//! GGUF header parsing.
+//!
+//! Reads the metadata key/values and tensor descriptors at the start of a
+//! GGUF file, without touching tensor data, so the exact weight size of a
+//! local model can be used instead of the parameter-count estimate.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use crate::models::{KvCacheQuant, kv_cache_bytes};
+
+/// "GGUF" read as a little-endian u32.
+const GGUF_MAGIC: u32 = 0x4655_4747;
+
+/// Longest string the parser will buffer (keys, architecture names, ...).
+/// Larger strings are skipped, or rejected when they are keys.
+const MAX_STRING_LEN: u64 = 1 << 20;
+
+/// GGML supports at most 4 dimensions; allow some slack for future formats.
+const MAX_TENSOR_DIMS: u32 = 8;
+
+/// Header fields of a GGUF model file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GgufMetadata {
+    pub version: u32,
+    pub tensor_count: u64,
+    /// `general.architecture`, e.g. "llama", "qwen2".
+    pub architecture: Option<String>,
+    /// `general.name`, when present.
+    pub name: Option<String>,
+    /// `{arch}.context_length`: maximum context the model was trained for.
+    pub context_length: Option<u64>,
+    /// `{arch}.block_count`: number of transformer layers.
+    pub block_count: Option<u64>,
+    /// `{arch}.embedding_length`: hidden size (n_embd).
+    pub embedding_length: Option<u64>,
+    /// `{arch}.attention.head_count`.
+    pub head_count: Option<u64>,
+    /// `{arch}.attention.head_count_kv`; equals `head_count` without GQA.
+    pub head_count_kv: Option<u64>,
+    /// Total elements across all tensors.
+    pub parameter_count: u64,
+    /// Exact size of the tensor data on disk, in bytes.
+    pub weight_bytes: u64,
+}
+
+impl GgufMetadata {
+    /// Weight size in GB (1e9 bytes, like the rest of the estimates).
+    pub fn weight_gb(&self) -> f64 {
+        self.weight_bytes as f64 / 1e9
+    }
+
+    /// F16 KV cache (GB) for `context_len` tokens, when the header carries
+    /// the layer count, hidden size and head counts.
+    pub fn kv_cache_gb(&self, context_len: u32) -> Option<f64> {
+        let layers = self.block_count?;
+        let heads = self.head_count.filter(|&h| h > 0)?;
+        let kv_heads = self.head_count_kv.unwrap_or(heads);
+        let head_dim = self.embedding_length? / heads;
+        let kv_width = u32::try_from(kv_heads * head_dim).ok()?;
+        let bytes = kv_cache_bytes(
+            u32::try_from(layers).ok()?,
+            kv_width,
+            context_len,
+            KvCacheQuant::F16,
+        );
+        Some(bytes as f64 / 1e9)
+    }
+
+    /// Weights + F16 KV cache + runtime overhead, in GB. Same shape as
+    /// [`crate::models::ModelArchConfig::memory_gb`], but with real weight
+    /// bytes. The KV term is left out when the header lacks the shape keys.
+    pub fn memory_gb(&self, context_len: u32) -> f64 {
+        let overhead = 0.5;
+        self.weight_gb() + self.kv_cache_gb(context_len).unwrap_or(0.0) + overhead
+    }
+}
+
+/// Why a GGUF header couldn't be read.
+#[derive(Debug)]
+pub enum GgufError {
+    Io(io::Error),
+    NotGguf,                 // magic bytes don't match
+    UnsupportedVersion(u32), // only v2 and v3 are supported
+    Malformed(String),
+}
+
+impl fmt::Display for GgufError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GgufError::Io(e) => write!(f, "I/O error: {}", e),
+            GgufError::NotGguf => write!(f, "not a GGUF file"),
+            GgufError::UnsupportedVersion(v) => write!(f, "unsupported GGUF version {}", v),
+            GgufError::Malformed(msg) => write!(f, "malformed GGUF header: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for GgufError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GgufError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GgufError {
+    fn from(e: io::Error) -> Self {
+        GgufError::Io(e)
+    }
+}
+
+/// Parse the header of the GGUF file at `path`. Only the metadata and tensor
+/// descriptors are read; tensor data is never loaded.
+pub fn parse_gguf_header(path: impl AsRef<Path>) -> Result<GgufMetadata, GgufError> {
+    let file = File::open(path)?;
+    parse_gguf_reader(BufReader::new(file))
+}
+
+/// Like [`parse_gguf_header`], for any reader positioned at the file start.
+pub fn parse_gguf_reader<R: Read>(reader: R) -> Result<GgufMetadata, GgufError> {
+    let mut r = GgufReader { inner: reader };
+
+    let magic = r.u32().map_err(|e| match e {
+        GgufError::Io(ref io) if io.kind() == io::ErrorKind::UnexpectedEof => GgufError::NotGguf,
+        other => other,
+    })?;
+    if magic != GGUF_MAGIC {
+        return Err(GgufError::NotGguf);
+    }
+    let version = r.u32()?;
+    if !(2..=3).contains(&version) {
+        return Err(GgufError::UnsupportedVersion(version));
+    }
+    let tensor_count = r.u64()?;
+    let kv_count = r.u64()?;
+
+    let mut strings: HashMap<String, String> = HashMap::new();
+    let mut integers: HashMap<String, u64> = HashMap::new();
+    for _ in 0..kv_count {
+        let key = r.string()?;
+        let value_type = r.u32()?;
+        match value_type {
+            GGUF_TYPE_STRING => {
+                if let Some(value) = r.string_or_skip()? {
+                    strings.insert(key, value);
+                }
+            }
+            t => {
+                if let Some(value) = r.value(t, 0)? {
+                    integers.insert(key, value);
+                }
+            }
+        }
+    }
+
+    let mut parameter_count: u64 = 0;
+    let mut weight_bytes: u64 = 0;
+    for _ in 0..tensor_count {
+        r.skip_string()?; // tensor name
+        let n_dims = r.u32()?;
+        if n_dims > MAX_TENSOR_DIMS {
+            return Err(GgufError::Malformed(format!("tensor with {} dims", n_dims)));
+        }
+        let mut elements: u64 = 1;
+        for _ in 0..n_dims {
+            elements = elements
+                .checked_mul(r.u64()?)
+                .ok_or_else(|| GgufError::Malformed("tensor size overflows".to_string()))?;
+        }
+        let ggml_type = r.u32()?;
+        let _offset = r.u64()?;
+
+        let (block_size, type_size) = ggml_type_size(ggml_type)
+            .ok_or_else(|| GgufError::Malformed(format!("unknown tensor type {}", ggml_type)))?;
+        parameter_count = parameter_count.saturating_add(elements);
+        weight_bytes = weight_bytes.saturating_add(elements.div_ceil(block_size) * type_size);
+    }
+
+    let architecture = strings.get("general.architecture").cloned();
+    let arch_key = |suffix: &str| -> Option<u64> {
+        let arch = architecture.as_deref()?;
+        integers.get(&format!("{}.{}", arch, suffix)).copied()
+    };
+
+    Ok(GgufMetadata {
+        version,
+        tensor_count,
+        name: strings.get("general.name").cloned(),
+        context_length: arch_key("context_length"),
+        block_count: arch_key("block_count"),
+        embedding_length: arch_key("embedding_length"),
+        head_count: arch_key("attention.head_count"),
+        head_count_kv: arch_key("attention.head_count_kv"),
+        architecture,
+        parameter_count,
+        weight_bytes,
+    })
+}
+
+// GGUF metadata value types.
+const GGUF_TYPE_UINT8: u32 = 0;
+const GGUF_TYPE_INT8: u32 = 1;
+const GGUF_TYPE_UINT16: u32 = 2;
+const GGUF_TYPE_INT16: u32 = 3;
+const GGUF_TYPE_UINT32: u32 = 4;
+const GGUF_TYPE_INT32: u32 = 5;
+const GGUF_TYPE_FLOAT32: u32 = 6;
+const GGUF_TYPE_BOOL: u32 = 7;
+const GGUF_TYPE_STRING: u32 = 8;
+const GGUF_TYPE_ARRAY: u32 = 9;
+const GGUF_TYPE_UINT64: u32 = 10;
+const GGUF_TYPE_INT64: u32 = 11;
+const GGUF_TYPE_FLOAT64: u32 = 12;
+
+/// (elements per block, bytes per block) of a ggml tensor type.
+fn ggml_type_size(ggml_type: u32) -> Option<(u64, u64)> {
+    Some(match ggml_type {
+        0 => (1, 4),      // F32
+        1 => (1, 2),      // F16
+        2 => (32, 18),    // Q4_0
+        3 => (32, 20),    // Q4_1
+        6 => (32, 22),    // Q5_0
+        7 => (32, 24),    // Q5_1
+        8 => (32, 34),    // Q8_0
+        9 => (32, 36),    // Q8_1
+        10 => (256, 84),  // Q2_K
+        11 => (256, 110), // Q3_K
+        12 => (256, 144), // Q4_K
+        13 => (256, 176), // Q5_K
+        14 => (256, 210), // Q6_K
+        15 => (256, 292), // Q8_K
+        16 => (256, 66),  // IQ2_XXS
+        17 => (256, 74),  // IQ2_XS
+        18 => (256, 98),  // IQ3_XXS
+        19 => (256, 50),  // IQ1_S
+        20 => (32, 18),   // IQ4_NL
+        21 => (256, 110), // IQ3_S
+        22 => (256, 82),  // IQ2_S
+        23 => (256, 136), // IQ4_XS
+        24 => (1, 1),     // I8
+        25 => (1, 2),     // I16
+        26 => (1, 4),     // I32
+        27 => (1, 8),     // I64
+        28 => (1, 8),     // F64
+        29 => (256, 56),  // IQ1_M
+        30 => (1, 2),     // BF16
+        34 => (256, 54),  // TQ1_0
+        35 => (256, 66),  // TQ2_0
+        39 => (32, 17),   // MXFP4
+        _ => return None,
+    })
+}
+
+/// Little-endian primitive reads over the underlying reader.
+struct GgufReader<R> {
+    inner: R,
+}
+
+impl<R: Read> GgufReader<R> {
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], GgufError> {
+        let mut buf = [0u8; N];
+        self.inner.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn u32(&mut self) -> Result<u32, GgufError> {
+        Ok(u32::from_le_bytes(self.bytes()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, GgufError> {
+        Ok(u64::from_le_bytes(self.bytes()?))
+    }
+
+    fn skip(&mut self, n: u64) -> Result<(), GgufError> {
+        let skipped = io::copy(&mut (&mut self.inner).take(n), &mut io::sink())?;
+        if skipped < n {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(())
+    }
+
+    fn string(&mut self) -> Result<String, GgufError> {
+        self.string_or_skip()?
+            .ok_or_else(|| GgufError::Malformed("oversized key".to_string()))
+    }
+
+    /// Read a string, or skip it and return `None` if it is too long to keep.
+    fn string_or_skip(&mut self) -> Result<Option<String>, GgufError> {
+        let len = self.u64()?;
+        if len > MAX_STRING_LEN {
+            self.skip(len)?;
+            return Ok(None);
+        }
+        let mut buf = vec![0u8; len as usize];
+        self.inner.read_exact(&mut buf)?;
+        Ok(Some(String::from_utf8_lossy(&buf).into_owned()))
+    }
+
+    fn skip_string(&mut self) -> Result<(), GgufError> {
+        let len = self.u64()?;
+        self.skip(len)
+    }
+
+    /// Read a metadata value of type `value_type`, returning it when it is a
+    /// non-negative integer. Everything else (floats, bools, strings nested
+    /// in arrays, tokenizer vocabularies) is skipped.
+    fn value(&mut self, value_type: u32, depth: u32) -> Result<Option<u64>, GgufError> {
+        Ok(match value_type {
+            GGUF_TYPE_UINT8 => Some(self.bytes::<1>()?[0] as u64),
+            GGUF_TYPE_INT8 => u64::try_from(self.bytes::<1>()?[0] as i8).ok(),
+            GGUF_TYPE_UINT16 => Some(u16::from_le_bytes(self.bytes()?) as u64),
+            GGUF_TYPE_INT16 => u64::try_from(i16::from_le_bytes(self.bytes()?)).ok(),
+            GGUF_TYPE_UINT32 => Some(self.u32()? as u64),
+            GGUF_TYPE_INT32 => u64::try_from(i32::from_le_bytes(self.bytes()?)).ok(),
+            GGUF_TYPE_UINT64 => Some(self.u64()?),
+            GGUF_TYPE_INT64 => u64::try_from(i64::from_le_bytes(self.bytes()?)).ok(),
+            GGUF_TYPE_BOOL => {
+                self.skip(1)?;
+                None
+            }
+            GGUF_TYPE_FLOAT32 => {
+                self.skip(4)?;
+                None
+            }
+            GGUF_TYPE_FLOAT64 => {
+                self.skip(8)?;
+                None
+            }
+            GGUF_TYPE_STRING => {
+                self.skip_string()?;
+                None
+            }
+            GGUF_TYPE_ARRAY => {
+                if depth > 2 {
+                    return Err(GgufError::Malformed("arrays nested too deeply".to_string()));
+                }
+                let item_type = self.u32()?;
+                let len = self.u64()?;
+                let fixed_width = match item_type {
+                    GGUF_TYPE_UINT8 | GGUF_TYPE_INT8 | GGUF_TYPE_BOOL => Some(1),
+                    GGUF_TYPE_UINT16 | GGUF_TYPE_INT16 => Some(2),
+                    GGUF_TYPE_UINT32 | GGUF_TYPE_INT32 | GGUF_TYPE_FLOAT32 => Some(4),
+                    GGUF_TYPE_UINT64 | GGUF_TYPE_INT64 | GGUF_TYPE_FLOAT64 => Some(8),
+                    _ => None,
+                };
+                match fixed_width {
+                    Some(width) => self.skip(len.saturating_mul(width))?,
+                    None => {
+                        for _ in 0..len {
+                            self.value(item_type, depth + 1)?;
+                        }
+                    }
+                }
+                None
+            }
+            t => return Err(GgufError::Malformed(format!("unknown value type {}", t))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a GGUF header in memory.
+    struct Builder {
+        buf: Vec<u8>,
+    }
+
+    impl Builder {
+        fn new(version: u32, tensors: u64, kvs: u64) -> Self {
+            let mut b = Builder { buf: Vec::new() };
+            b.buf.extend_from_slice(b"GGUF");
+            b.u32(version).u64(tensors).u64(kvs);
+            b
+        }
+        fn u32(&mut self, v: u32) -> &mut Self {
+            self.buf.extend_from_slice(&v.to_le_bytes());
+            self
+        }
+        fn u64(&mut self, v: u64) -> &mut Self {
+            self.buf.extend_from_slice(&v.to_le_bytes());
+            self
+        }
+        fn str(&mut self, s: &str) -> &mut Self {
+            self.u64(s.len() as u64);
+            self.buf.extend_from_slice(s.as_bytes());
+            self
+        }
+        fn kv_str(&mut self, key: &str, value: &str) -> &mut Self {
+            self.str(key).u32(GGUF_TYPE_STRING).str(value)
+        }
+        fn kv_u32(&mut self, key: &str, value: u32) -> &mut Self {
+            self.str(key).u32(GGUF_TYPE_UINT32).u32(value)
+        }
+        fn tensor(&mut self, name: &str, dims: &[u64], ggml_type: u32) -> &mut Self {
+            self.str(name).u32(dims.len() as u32);
+            for &d in dims {
+                self.u64(d);
+            }
+            self.u32(ggml_type).u64(0)
+        }
+    }
+
+    fn llama_header(version: u32) -> Vec<u8> {
+        let mut b = Builder::new(version, 2, 8);
+        b.kv_str("general.architecture", "llama")
+            .kv_str("general.name", "Tiny Llama")
+            .kv_u32("llama.context_length", 8192)
+            .kv_u32("llama.block_count", 32)
+            .kv_u32("llama.embedding_length", 4096)
+            .kv_u32("llama.attention.head_count", 32)
+            .kv_u32("llama.attention.head_count_kv", 8);
+        // Tokenizer vocabulary: an array of strings that must be skipped
+        b.str("tokenizer.ggml.tokens")
+            .u32(GGUF_TYPE_ARRAY)
+            .u32(GGUF_TYPE_STRING)
+            .u64(3)
+            .str("<s>")
+            .str("</s>")
+            .str("hello");
+        b.tensor("token_embd.weight", &[4096, 32000], 12) // Q4_K
+            .tensor("output_norm.weight", &[4096], 0); // F32
+        b.buf
+    }
+
+    #[test]
+    fn test_parse_v3_header() {
+        let meta = parse_gguf_reader(llama_header(3).as_slice()).unwrap();
+        assert_eq!(meta.version, 3);
+        assert_eq!(meta.tensor_count, 2);
+        assert_eq!(meta.architecture.as_deref(), Some("llama"));
+        assert_eq!(meta.name.as_deref(), Some("Tiny Llama"));
+        assert_eq!(meta.context_length, Some(8192));
+        assert_eq!(meta.block_count, Some(32));
+        assert_eq!(meta.embedding_length, Some(4096));
+        assert_eq!(meta.head_count_kv, Some(8));
+        assert_eq!(meta.parameter_count, 4096 * 32000 + 4096);
+        // Q4_K packs 256 weights into 144 bytes; F32 is 4 bytes each
+        assert_eq!(meta.weight_bytes, 4096 * 32000 / 256 * 144 + 4096 * 4);
+    }
+
+    #[test]
+    fn test_parse_v2_header_and_memory() {
+        let meta = parse_gguf_reader(llama_header(2).as_slice()).unwrap();
+        assert_eq!(meta.version, 2);
+        // 8 KV heads x 128 head dim x 32 layers, F16: 0.131 MB per token
+        let kv = meta.kv_cache_gb(8192).unwrap();
+        assert!((kv - 1.074).abs() < 0.01, "kv = {kv}");
+        assert!((meta.memory_gb(8192) - (meta.weight_gb() + kv + 0.5)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rejects_non_gguf() {
+        assert!(matches!(
+            parse_gguf_reader(b"PK\x03\x04 not a model".as_slice()),
+            Err(GgufError::NotGguf)
+        ));
+        assert!(matches!(
+            parse_gguf_reader(b"GG".as_slice()),
+            Err(GgufError::NotGguf)
+        ));
+        let v1 = Builder::new(1, 0, 0).buf;
+        assert!(matches!(
+            parse_gguf_reader(v1.as_slice()),
+            Err(GgufError::UnsupportedVersion(1))
+        ));
+    }
+
+    #[test]
+    fn test_truncated_header_is_io_error() {
+        let header = llama_header(3);
+        let truncated = &header[..header.len() - 10];
+        assert!(matches!(
+            parse_gguf_reader(truncated),
+            Err(GgufError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,293 @@
+//! Memory bandwidth of known GPUs and the bandwidth-bound ("roofline")
+//! token generation estimates built on it, including partial CPU+GPU
+//! layer offload.
+
+use crate::hardware::GpuBackend;
+
+/// Fraction of peak bandwidth real decoders sustain (kernel overheads,
+/// imperfect overlap, KV cache reads).
+pub const ROOFLINE_EFFICIENCY: f64 = 0.7;
+
+/// Decode speed if every generated token has to stream all weights from
+/// memory once: `bandwidth * efficiency / model_params_gb`. Unknown GPUs fall
+/// back to [`backend_default_bandwidth_gbps`].
+///
+/// `model_params_gb` is the size of the weights actually read per token
+/// (for MoE models, only the active experts).
+pub fn estimate_gpu_tokens_per_sec(
+    model_params_gb: f64,
+    backend: GpuBackend,
+    gpu_name: &str,
+) -> f64 {
+    let bandwidth =
+        memory_bandwidth_gbps(gpu_name).unwrap_or_else(|| backend_default_bandwidth_gbps(backend));
+    bandwidth * ROOFLINE_EFFICIENCY / model_params_gb.max(0.1)
+}
+
+/// Slowdown of a split CPU+GPU run beyond the bandwidth math: per-token
+/// synchronisation and activations crossing PCIe at each boundary.
+pub(crate) const HYBRID_SYNC_PENALTY: f64 = 0.85;
+
+/// How many of `total_layers` fit in `available_vram_gb` once
+/// `non_layer_gb` is set aside for what stays on the GPU regardless of the
+/// split: embeddings and output head, KV cache and runtime buffers. The
+/// remaining layers run on the CPU.
+pub fn gpu_layers_that_fit(
+    total_layers: u32,
+    per_layer_gb: f64,
+    available_vram_gb: f64,
+    non_layer_gb: f64,
+) -> u32 {
+    let budget = available_vram_gb - non_layer_gb;
+    if budget <= 0.0 || per_layer_gb <= 0.0 {
+        return 0;
+    }
+    ((budget / per_layer_gb).floor() as u32).min(total_layers)
+}
+
+/// Typical bandwidth (GB/s) of a mid-range part for each backend, used when
+/// the exact GPU isn't in the table.
+pub fn backend_default_bandwidth_gbps(backend: GpuBackend) -> f64 {
+    match backend {
+        GpuBackend::Cuda => 450.0,
+        GpuBackend::Rocm => 500.0,
+        GpuBackend::Metal => 200.0,
+        GpuBackend::Vulkan => 300.0,
+        GpuBackend::Sycl => 450.0,
+        GpuBackend::OpenCL => 100.0,
+        GpuBackend::DirectML => 300.0,
+        GpuBackend::CpuArm => 100.0, // LPDDR5 laptops / Graviton
+        GpuBackend::CpuX86 => 60.0,  // dual-channel DDR5
+        GpuBackend::Ascend => 1600.0,
+    }
+}
+
+/// Approximate peak memory bandwidth (GB/s) from a GPU or Apple chip name.
+/// Mirrors `estimate_vram_from_name` in hardware.rs; returns `None` for unknown parts rather
+/// than guessing, since bandwidth varies far more than VRAM across SKUs.
+pub fn memory_bandwidth_gbps(name: &str) -> Option<f64> {
+    let lower = name.to_lowercase();
+    // Apple Silicon (unified memory bus)
+    if lower.contains("apple m1 ultra") {
+        return Some(800.0);
+    }
+    if lower.contains("apple m1 max") {
+        return Some(400.0);
+    }
+    if lower.contains("apple m1 pro") {
+        return Some(200.0);
+    }
+    if lower.contains("apple m1") {
+        return Some(68.0);
+    }
+    if lower.contains("apple m2 ultra") {
+        return Some(800.0);
+    }
+    if lower.contains("apple m2 max") {
+        return Some(400.0);
+    }
+    if lower.contains("apple m2 pro") {
+        return Some(200.0);
+    }
+    if lower.contains("apple m2") {
+        return Some(100.0);
+    }
+    if lower.contains("apple m3 ultra") {
+        return Some(819.0);
+    }
+    if lower.contains("apple m3 max") {
+        return Some(400.0);
+    }
+    if lower.contains("apple m3 pro") {
+        return Some(150.0);
+    }
+    if lower.contains("apple m3") {
+        return Some(100.0);
+    }
+    if lower.contains("apple m4 max") {
+        return Some(546.0);
+    }
+    if lower.contains("apple m4 pro") {
+        return Some(273.0);
+    }
+    if lower.contains("apple m4") {
+        return Some(120.0);
+    }
+    // NVIDIA RTX 50 series
+    if lower.contains("5090") {
+        return Some(1792.0);
+    }
+    if lower.contains("5080") {
+        return Some(960.0);
+    }
+    if lower.contains("5070 ti") {
+        return Some(896.0);
+    }
+    if lower.contains("5070") {
+        return Some(672.0);
+    }
+    if lower.contains("5060 ti") {
+        return Some(448.0);
+    }
+    if lower.contains("5060") {
+        return Some(448.0);
+    }
+    // NVIDIA RTX 40 series
+    if lower.contains("4090") {
+        return Some(1008.0);
+    }
+    if lower.contains("4080") {
+        return Some(717.0);
+    }
+    if lower.contains("4070 ti") {
+        return Some(504.0);
+    }
+    if lower.contains("4070") {
+        return Some(504.0);
+    }
+    if lower.contains("4060 ti") {
+        return Some(288.0);
+    }
+    if lower.contains("4060") {
+        return Some(272.0);
+    }
+    // NVIDIA RTX 30 series
+    if lower.contains("3090") {
+        return Some(936.0);
+    }
+    if lower.contains("3080 ti") {
+        return Some(912.0);
+    }
+    if lower.contains("3080") {
+        return Some(760.0);
+    }
+    if lower.contains("3070") {
+        return Some(448.0);
+    }
+    if lower.contains("3060 ti") {
+        return Some(448.0);
+    }
+    if lower.contains("3060") {
+        return Some(360.0);
+    }
+    // Data center
+    if lower.contains("h100") {
+        return Some(3350.0);
+    }
+    if lower.contains("a100") {
+        return Some(2039.0);
+    }
+    if lower.contains("l40") {
+        return Some(864.0);
+    }
+    if lower.contains("a10") {
+        return Some(600.0);
+    }
+    if lower.contains("t4") {
+        return Some(320.0);
+    }
+    // NVIDIA Grace / DGX Spark unified memory SoCs
+    if lower.contains("gb10") {
+        return Some(273.0);
+    }
+    // AMD RX 9000 / 7000 / 6000 series
+    if lower.contains("9070 xt") {
+        return Some(640.0);
+    }
+    if lower.contains("9070") {
+        return Some(640.0);
+    }
+    if lower.contains("7900 xtx") {
+        return Some(960.0);
+    }
+    if lower.contains("7900") {
+        return Some(800.0);
+    }
+    if lower.contains("7800") {
+        return Some(624.0);
+    }
+    if lower.contains("7700") {
+        return Some(432.0);
+    }
+    if lower.contains("7600") {
+        return Some(288.0);
+    }
+    if lower.contains("6950") {
+        return Some(576.0);
+    }
+    if lower.contains("6900") {
+        return Some(512.0);
+    }
+    if lower.contains("6800") {
+        return Some(512.0);
+    }
+    if lower.contains("6700") {
+        return Some(384.0);
+    }
+    if lower.contains("6600") {
+        return Some(224.0);
+    }
+    // AMD Ryzen AI MAX (Strix Halo)
+    if lower.contains("8060s") {
+        return Some(256.0);
+    }
+    if lower.contains("8050s") {
+        return Some(256.0);
+    }
+    // Intel Arc
+    if lower.contains("b580") {
+        return Some(456.0);
+    }
+    if lower.contains("a770") {
+        return Some(560.0);
+    }
+    if lower.contains("a750") {
+        return Some(512.0);
+    }
+    if lower.contains("a380") {
+        return Some(186.0);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_bandwidth_from_name() {
+        use super::memory_bandwidth_gbps as bw;
+        assert_eq!(bw("NVIDIA GeForce RTX 4090"), Some(1008.0));
+        assert_eq!(bw("Apple M3 Max"), Some(400.0));
+        assert_eq!(bw("Apple M1"), Some(68.0));
+        assert_eq!(bw("Apple M2 Ultra"), Some(800.0));
+        assert_eq!(bw("AMD Radeon RX 7900 XTX"), Some(960.0));
+        assert_eq!(bw("Some Unknown GPU"), None);
+    }
+
+    #[test]
+    fn test_estimate_gpu_tokens_per_sec() {
+        // 4090 with a ~4.6 GB 8B Q4_K_M: 1008 * 0.7 / 4.6 ~ 153 tok/s
+        let tps = estimate_gpu_tokens_per_sec(4.6, GpuBackend::Cuda, "NVIDIA GeForce RTX 4090");
+        assert!((tps - 153.4).abs() < 0.5, "got {}", tps);
+        // A100 has roughly twice the bandwidth of a 3090
+        let a100 = estimate_gpu_tokens_per_sec(10.0, GpuBackend::Cuda, "NVIDIA A100-SXM4-80GB");
+        let rtx3090 =
+            estimate_gpu_tokens_per_sec(10.0, GpuBackend::Cuda, "NVIDIA GeForce RTX 3090");
+        assert!(a100 > rtx3090 * 2.0);
+        // Unknown parts use the backend default
+        assert_eq!(
+            estimate_gpu_tokens_per_sec(10.0, GpuBackend::Cuda, "Mystery GPU"),
+            450.0 * ROOFLINE_EFFICIENCY / 10.0
+        );
+    }
+
+    #[test]
+    fn test_gpu_layers_that_fit_reserves_non_layer_memory() {
+        // 12 GB card, 2 GB reserved: 10 GB holds 20 half-GB layers
+        assert_eq!(gpu_layers_that_fit(80, 0.5, 12.0, 2.0), 20);
+        assert_eq!(gpu_layers_that_fit(32, 0.25, 24.0, 2.0), 32);
+        // KV cache and buffers alone exhaust VRAM
+        assert_eq!(gpu_layers_that_fit(32, 0.25, 2.0, 2.5), 0);
+    }
+}
@@ -1,14 +1,18 @@
 use std::collections::BTreeMap;
-use sysinfo::System;
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
+
+use crate::gpu_specs::memory_bandwidth_gbps;
 
 /// The acceleration backend for inference speed estimation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum GpuBackend {
     Cuda,
     Metal,
     Rocm,
-    Vulkan, // AMD/other GPUs without ROCm (e.g. Windows AMD, older AMD)
-    Sycl,   // Intel oneAPI
+    Vulkan,   // AMD/other GPUs without ROCm (e.g. Linux AMD without ROCm, older AMD)
+    Sycl,     // Intel oneAPI
+    OpenCL,   // Older AMD / Mali / PowerVR GPUs via OpenCL (e.g. llama.cpp CLBlast)
+    DirectML, // AMD/Intel GPUs on Windows (ONNX Runtime, DirectML llama.cpp builds)
     CpuArm,
     CpuX86,
     Ascend,
@@ -22,24 +26,357 @@ impl GpuBackend {
             GpuBackend::Rocm => "ROCm",
             GpuBackend::Vulkan => "Vulkan",
             GpuBackend::Sycl => "SYCL",
+            GpuBackend::OpenCL => "OpenCL",
+            GpuBackend::DirectML => "DirectML",
             GpuBackend::CpuArm => "CPU (ARM)",
             GpuBackend::CpuX86 => "CPU (x86)",
             GpuBackend::Ascend => "NPU (Ascend)",
         }
     }
+
+    /// Every backend, in declaration order.
+    pub const ALL: [GpuBackend; 10] = [
+        GpuBackend::Cuda,
+        GpuBackend::Metal,
+        GpuBackend::Rocm,
+        GpuBackend::Vulkan,
+        GpuBackend::Sycl,
+        GpuBackend::OpenCL,
+        GpuBackend::DirectML,
+        GpuBackend::CpuArm,
+        GpuBackend::CpuX86,
+        GpuBackend::Ascend,
+    ];
+
+    /// GPU memory (GB) a runtime on this backend typically holds beyond the
+    /// model: CUDA and ROCm contexts plus compute buffers run 1 GB or more,
+    /// Metal's are small, and CPU inference reserves nothing extra.
+    pub fn default_overhead_gb(&self) -> f64 {
+        match self {
+            GpuBackend::Cuda | GpuBackend::Rocm => 1.0,
+            GpuBackend::Sycl | GpuBackend::Ascend => 0.75,
+            GpuBackend::Vulkan | GpuBackend::OpenCL | GpuBackend::DirectML => 0.5,
+            GpuBackend::Metal => 0.25,
+            GpuBackend::CpuArm | GpuBackend::CpuX86 => 0.0,
+        }
+    }
+
+    /// Parse a backend from its [`GpuBackend::label`] (e.g. "CUDA", "cpu (x86)")
+    /// or variant name (e.g. "CpuX86"), case-insensitively.
+    pub fn from_label(s: &str) -> Option<GpuBackend> {
+        let s = s.trim();
+        Self::ALL.into_iter().find(|b| {
+            b.label().eq_ignore_ascii_case(s) || format!("{:?}", b).eq_ignore_ascii_case(s)
+        })
+    }
+}
+
+/// Apple Silicon chip tier within an M-series generation (M1, M2, M3, M4, ...).
+/// The tier determines the width of the unified memory bus, which is the main
+/// driver of token generation speed on Apple hardware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum AppleChipVariant {
+    Base,  // e.g. "Apple M3"
+    Pro,   // e.g. "Apple M3 Pro"
+    Max,   // e.g. "Apple M3 Max"
+    Ultra, // e.g. "Apple M2 Ultra" (two fused Max dies)
+}
+
+impl AppleChipVariant {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AppleChipVariant::Base => "Base",
+            AppleChipVariant::Pro => "Pro",
+            AppleChipVariant::Max => "Max",
+            AppleChipVariant::Ultra => "Ultra",
+        }
+    }
+
+    /// Parse the tier from a chip name such as "Apple M3 Max".
+    /// Returns `None` if the name is not an Apple M-series chip.
+    pub fn from_chip_name(name: &str) -> Option<Self> {
+        let lower = name.to_lowercase();
+        if !lower.contains("apple m") {
+            return None;
+        }
+        let variant = if lower.contains("ultra") {
+            AppleChipVariant::Ultra
+        } else if lower.contains("max") {
+            AppleChipVariant::Max
+        } else if lower.contains("pro") {
+            AppleChipVariant::Pro
+        } else {
+            AppleChipVariant::Base
+        };
+        Some(variant)
+    }
+
+    /// Memory bandwidth relative to the Pro tier, which the Metal speed
+    /// constants are calibrated against. Base chips have roughly half the
+    /// bandwidth of Pro; Max doubles it and Ultra doubles it again, but
+    /// compute limits keep real-world gains sub-linear.
+    pub fn bandwidth_multiplier(&self) -> f64 {
+        match self {
+            AppleChipVariant::Base => 0.6,
+            AppleChipVariant::Pro => 1.0,
+            AppleChipVariant::Max => 1.6,
+            AppleChipVariant::Ultra => 2.2,
+        }
+    }
+
+    /// GPU core count of a typical (unbinned) chip in this tier, across
+    /// M1-M4 generations. Binned parts (e.g. a 30-core M3 Max) fall below it.
+    pub fn typical_gpu_cores(&self) -> u32 {
+        match self {
+            AppleChipVariant::Base => 10,
+            AppleChipVariant::Pro => 18,
+            AppleChipVariant::Max => 38,
+            AppleChipVariant::Ultra => 76,
+        }
+    }
+}
+
+/// NVIDIA Jetson module. The GPU shares the module's LPDDR with the CPU, so
+/// the memory bus, not VRAM, sets how fast it generates tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum JetsonVariant {
+    Nano,     // original Jetson Nano (Maxwell)
+    Nx,       // Jetson Xavier NX
+    Agx,      // Jetson AGX Xavier
+    Orin,     // Jetson AGX Orin
+    OrinNx,   // Jetson Orin NX
+    OrinNano, // Jetson Orin Nano
+}
+
+impl JetsonVariant {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JetsonVariant::Nano => "Nano",
+            JetsonVariant::Nx => "Xavier NX",
+            JetsonVariant::Agx => "AGX Xavier",
+            JetsonVariant::Orin => "AGX Orin",
+            JetsonVariant::OrinNx => "Orin NX",
+            JetsonVariant::OrinNano => "Orin Nano",
+        }
+    }
+
+    /// Parse the module from a board name such as "NVIDIA Jetson AGX Orin
+    /// Developer Kit". Returns `None` for other boards, or a Jetson whose
+    /// module can't be told apart.
+    pub fn from_board_name(name: &str) -> Option<Self> {
+        let lower = name.to_lowercase();
+        if !lower.contains("jetson") {
+            return None;
+        }
+        let variant = if lower.contains("orin") {
+            if lower.contains("nano") {
+                JetsonVariant::OrinNano
+            } else if lower.contains("nx") {
+                JetsonVariant::OrinNx
+            } else {
+                JetsonVariant::Orin
+            }
+        } else if lower.contains("xavier") && lower.contains("nx") {
+            JetsonVariant::Nx
+        } else if lower.contains("xavier") || lower.contains("agx") {
+            // AGX Xavier's device tree calls it just "Jetson-AGX"
+            JetsonVariant::Agx
+        } else if lower.contains("nano") {
+            JetsonVariant::Nano
+        } else {
+            return None;
+        };
+        Some(variant)
+    }
+
+    /// Peak LPDDR bandwidth (GB/s) of the module; for modules sold with two
+    /// memory sizes, that of the larger one.
+    pub fn memory_bandwidth_gbps(&self) -> f64 {
+        match self {
+            JetsonVariant::Nano => 25.6,
+            JetsonVariant::Nx => 59.7,
+            JetsonVariant::Agx => 136.5,
+            JetsonVariant::Orin => 204.8,
+            JetsonVariant::OrinNx => 102.4,
+            JetsonVariant::OrinNano => 68.0,
+        }
+    }
+}
+
+/// Interconnect between the detected GPUs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum GpuLinkTopology {
+    SingleGpu,   // zero or one GPU
+    NvlinkPod,   // every GPU pair linked via NVLink / SXM
+    PcieSli,     // same-model GPUs talking over PCIe
+    Independent, // different GPU models/vendors; no practical pooling
+}
+
+impl GpuLinkTopology {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GpuLinkTopology::SingleGpu => "Single GPU",
+            GpuLinkTopology::NvlinkPod => "NVLink",
+            GpuLinkTopology::PcieSli => "PCIe",
+            GpuLinkTopology::Independent => "Independent",
+        }
+    }
+
+    /// Whether the cards exchange data over NVLink rather than PCIe.
+    pub fn has_nvlink(&self) -> bool {
+        *self == GpuLinkTopology::NvlinkPod
+    }
+}
+
+/// Container or virtualization layer the process is running under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ContainerEnv {
+    None,
+    Docker,
+    Kubernetes,
+    Wsl,
+    Nspawn,  // systemd-nspawn
+    Unknown, // podman, LXC, or another OCI runtime
+}
+
+impl ContainerEnv {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContainerEnv::None => "None",
+            ContainerEnv::Docker => "Docker",
+            ContainerEnv::Kubernetes => "Kubernetes",
+            ContainerEnv::Wsl => "WSL",
+            ContainerEnv::Nspawn => "systemd-nspawn",
+            ContainerEnv::Unknown => "Container",
+        }
+    }
 }
 
+/// Share of total VRAM that may be in use by other processes before
+/// [`SystemSpecs::vram_contended`] reports contention.
+pub const VRAM_CONTENTION_THRESHOLD: f64 = 0.2;
+
+/// GPU temperature (°C) at detection from which sustained inference is
+/// likely to thermal-throttle; most consumer cards start clocking down in
+/// the low-to-mid 80s.
+pub const THERMAL_THROTTLE_WARN_C: f32 = 80.0;
+
+/// Largest VRAM (GB) still treated as an APU's BIOS carveout rather than a
+/// discrete card's memory.
+const AMD_APU_CARVEOUT_MAX_GB: f64 = 2.0;
+
+/// Share of system RAM an AMD APU can map through GTT when the driver
+/// doesn't report it (amdgpu's default GTT size is half of RAM).
+const AMD_APU_SHARED_RAM_FRACTION: f64 = 0.5;
+
+/// Thunderbolt enclosure names (lowercase) that hold a desktop GPU: Razer
+/// Core X / Core V2, Sonnet Breakaway Box, Gigabyte AORUS Gaming Box,
+/// Mantiz Venus, Blackmagic eGPU. The Akitio Node is matched by word.
+const EGPU_ENCLOSURES: &[&str] = &[
+    "core x",
+    "core v2",
+    "breakaway box",
+    "gaming box",
+    "mantiz",
+    "egpu",
+];
+
+/// How long any single detection subprocess (nvidia-smi, rocm-smi,
+/// PowerShell, ...) may run before it is killed and treated as absent.
+pub const DETECTION_COMMAND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// VRAM each additional card gives up when a model is split over PCIe.
+pub const PCIE_SPLIT_RESERVE_GB: f64 = 0.5;
+
+/// Overrides also read without the `OVERRIDE_` infix, e.g. `LLMFIT_VRAM_GB`,
+/// as they were before the full set existed.
+const LEGACY_OVERRIDES: [&str; 5] = ["VRAM_GB", "RAM_GB", "BACKEND", "GPU_NAME", "OVERHEAD_GB"];
+
 /// Information about a single detected GPU.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct GpuInfo {
     pub name: String,
     pub vram_gb: Option<f64>,
     pub backend: GpuBackend,
     pub count: u32, // >1 for same-model multi-GPU (e.g. 2x RTX 4090)
     pub unified_memory: bool,
+    /// Dedicated card, iGPU borrowing part of RAM, or unified memory SoC.
+    /// Scans saved before this field existed read as Discrete.
+    #[serde(default)]
+    pub class: GpuClass,
+}
+
+/// How a GPU gets its memory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum GpuClass {
+    /// Own VRAM on the card (GeForce, Radeon RX, Arc A-series).
+    #[default]
+    Discrete,
+    /// iGPU with a small firmware carveout (typically 512 MB-2 GB) that
+    /// borrows a share of system RAM through the driver, e.g. Ryzen 5600G
+    /// Vega graphics, Radeon 780M or Intel Iris Xe.
+    Integrated,
+    /// CPU and GPU address the whole of system RAM as one pool (Apple
+    /// Silicon, Ryzen AI MAX, Jetson, GB10).
+    Unified,
+}
+
+impl GpuClass {
+    pub const ALL: [GpuClass; 3] = [GpuClass::Discrete, GpuClass::Integrated, GpuClass::Unified];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GpuClass::Discrete => "Discrete",
+            GpuClass::Integrated => "Integrated",
+            GpuClass::Unified => "Unified",
+        }
+    }
+
+    /// Guess the class from a marketing name, for probes (WMI, clinfo) that
+    /// report nothing about memory.
+    pub fn from_name(name: &str) -> Self {
+        let lower = name.to_lowercase();
+        // Strix Halo's Radeon 8060S/8050S sit on the full LPDDR5X pool
+        if lower.contains("8060s") || lower.contains("8050s") {
+            return GpuClass::Unified;
+        }
+        // Discrete Arc cards carry a model number (A770, B580); the Core
+        // Ultra iGPU is plain "Arc Graphics"
+        let arc_model = lower.split_whitespace().any(|word| {
+            let mut chars = word.chars();
+            matches!(chars.next(), Some('a' | 'b'))
+                && chars.take(3).filter(char::is_ascii_digit).count() == 3
+        });
+        let intel_igpu = lower.contains("intel")
+            && (lower.contains("uhd")
+                || lower.contains("iris")
+                || lower.contains("hd graphics")
+                || (lower.contains("arc") && lower.contains("graphics") && !arc_model));
+        // "Radeon(TM) Graphics", "Radeon RX Vega 10 Graphics" and the 7x0M /
+        // 8x0M mobile parts; discrete cards name a series (RX 6600, Pro W7900)
+        let amd_igpu = (lower.contains("radeon") || lower.contains("amd"))
+            && ([
+                "610m", "660m", "680m", "740m", "760m", "780m", "860m", "880m", "890m",
+            ]
+            .iter()
+            .any(|model| lower.contains(model))
+                || (lower.contains("graphics")
+                    && (lower.contains("vega")
+                        || !["rx ", " hd ", " r5 ", " r7 ", " r9 ", "pro "]
+                            .iter()
+                            .any(|series| lower.contains(series)))));
+        let mobile_igpu = lower.contains("mali") || lower.contains("adreno");
+        if intel_igpu || amd_igpu || mobile_igpu {
+            GpuClass::Integrated
+        } else {
+            GpuClass::Discrete
+        }
+    }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+/// Detected (or loaded) hardware profile. Round-trips through serde so a
+/// profile captured on one machine can be replayed on another.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct SystemSpecs {
     pub total_ram_gb: f64,
     pub available_ram_gb: f64,
@@ -51,38 +388,643 @@ pub struct SystemSpecs {
     /// For multi-GPU inference backends (llama.cpp, vLLM), models can be split
     /// across cards, so we use total VRAM for fit scoring.
     pub total_gpu_vram_gb: Option<f64>,
+    /// VRAM of each individual card, largest first (e.g. `[12.0, 12.0]` for
+    /// 2x RTX 3060). Lets fit logic tell 2x12 GB apart from one 24 GB card.
+    pub gpu_vram_gb_per_device: Vec<f64>,
+    /// VRAM currently free across the primary GPU model's cards, as reported
+    /// by nvidia-smi (`memory.free`) or rocm-smi (total - used). `None` when
+    /// the vendor tool doesn't expose usage.
+    pub gpu_vram_available_gb: Option<f64>,
+    /// VRAM held by other processes across the same cards, as reported by
+    /// nvidia-smi (`memory.used`) or rocm-smi. `None` alongside
+    /// `gpu_vram_available_gb`.
+    pub gpu_vram_used_gb: Option<f64>,
     pub gpu_name: Option<String>,
     pub gpu_count: u32,
     pub unified_memory: bool,
     pub backend: GpuBackend,
     /// All detected GPUs (may span different vendors/backends).
     pub gpus: Vec<GpuInfo>,
+    /// Apple Silicon tier (Base/Pro/Max/Ultra), when running on an M-series Mac.
+    pub apple_chip_variant: Option<AppleChipVariant>,
+    /// Estimated peak memory bandwidth (GB/s) of the primary GPU, or of the
+    /// unified memory bus on Apple Silicon. Looked up from the GPU name.
+    pub memory_bandwidth_gbps: Option<f64>,
+    /// SIMD extensions reported by the CPU (e.g. "avx2", "avx512f", "neon").
+    /// Used to scale CPU inference speed estimates.
+    pub cpu_features: Vec<String>,
+    /// How multiple GPUs are interconnected (NVLink, PCIe, or unrelated cards).
+    /// Determines whether tensor parallelism can run at full bandwidth.
+    pub gpu_link_topology: GpuLinkTopology,
+    /// CUDA compute capability of the first NVIDIA card as (major, minor),
+    /// e.g. (8, 9) for sm_89 (Ada). Gates features such as FlashAttention-2
+    /// and FP8 kernels. `None` on non-NVIDIA systems.
+    pub cuda_compute_capability: Option<(u8, u8)>,
+    /// Installed ROCm stack version (e.g. "6.1.2"). Only set when an AMD GPU
+    /// was found through ROCm; Vulkan-only AMD detections leave it `None`.
+    pub rocm_version: Option<String>,
+    /// Driver version behind the primary GPU: the NVIDIA driver reported by
+    /// nvidia-smi (e.g. "550.54.14"), the ROCm release, the macOS version for
+    /// Metal, or WMI's `DriverVersion` on Windows (e.g. "31.0.101.5382").
+    #[serde(default)]
+    pub gpu_driver_version: Option<String>,
+    /// Hottest card of the primary GPU model at detection time, in °C, from
+    /// nvidia-smi or rocm-smi. See [`SystemSpecs::thermal_throttle_risk`].
+    #[serde(default)]
+    pub gpu_temperature_c: Option<f32>,
+    /// Container or VM layer llmfit is running under, if any. Memory limits
+    /// and GPU passthrough behave differently inside these.
+    pub container_env: ContainerEnv,
+    /// Swap / pagefile size. Swap is never counted in the default fit
+    /// calculation; see [`SystemSpecs::check_model_fit_with_swap`].
+    pub swap_total_gb: f64,
+    pub swap_available_gb: f64,
+    /// Free disk space where models are stored (`$OLLAMA_MODELS`, else the
+    /// home directory, else the current directory).
+    pub disk_available_gb: Option<f64>,
+    /// GPU core count of an Apple Silicon chip (e.g. 40 for a full M3 Max),
+    /// from `system_profiler SPDisplaysDataType`. `None` on other systems.
+    pub gpu_core_count: Option<u32>,
+    /// PCIe generation of the primary GPU's current host link (3 = PCIe 3.0).
+    /// `None` when it couldn't be read.
+    pub pcie_gen: Option<u8>,
+    /// Lane count of the primary GPU's current host link (x16 = 16).
+    pub pcie_width: Option<u8>,
+    /// NUMA nodes on the host (2 on a typical dual-socket server); 1 when
+    /// it can't be read. CPU inference that spans nodes without pinning is
+    /// much slower.
+    pub numa_nodes: usize,
+    /// GPU memory the inference runtime holds beyond weights and KV cache:
+    /// driver context, compute buffers, allocator fragmentation. `None`
+    /// uses [`GpuBackend::default_overhead_gb`]; see
+    /// [`SystemSpecs::runtime_overhead_gb`].
+    #[serde(default)]
+    pub overhead_gb: Option<f64>,
+    /// Sequential read speed (GB/s) of the NVMe drive holding the models,
+    /// from its model name or PCIe link. Bounds how fast llama.cpp can page
+    /// in mmapped weights that don't fit in memory; see
+    /// [`crate::fit::mmap_tokens_per_sec`].
+    #[serde(default)]
+    pub nvme_read_bandwidth_gb_s: Option<f64>,
+    /// `RLIMIT_MEMLOCK` in GB: how much RAM llama.cpp's `--mlock` may pin.
+    /// `None` when unlimited, when running as root, or off Unix.
+    #[serde(default)]
+    pub mlock_limit_gb: Option<f64>,
+    /// NVIDIA Jetson module, when running on one.
+    #[serde(default)]
+    pub jetson_variant: Option<JetsonVariant>,
 }
 
 impl SystemSpecs {
-    pub fn detect() -> Self {
-        let mut sys = System::new_all();
-        sys.refresh_all();
+    /// Fast detection for scripts that only need RAM and CPU information.
+    /// Never spawns GPU probes (nvidia-smi, rocm-smi, lspci, PowerShell, ...);
+    /// GPU fields are left empty and `backend` is the CPU fallback.
+    pub fn detect_ram_only() -> Self {
+        let sys = System::new_with_specifics(
+            RefreshKind::nothing()
+                .with_memory(MemoryRefreshKind::everything())
+                .with_cpu(CpuRefreshKind::nothing()),
+        );
+
+        let (total_ram_gb, available_ram_gb) = Self::read_ram(&sys);
+        let (swap_total_gb, swap_available_gb) = Self::read_swap(&sys);
+        let cpu_name = Self::read_cpu_name(&sys);
+        let backend = Self::cpu_backend(&cpu_name);
+        let cpu_features = detect_cpu_features();
+
+        SystemSpecs {
+            total_ram_gb,
+            available_ram_gb,
+            total_cpu_cores: sys.cpus().len(),
+            cpu_name,
+            has_gpu: false,
+            gpu_vram_gb: None,
+            total_gpu_vram_gb: None,
+            gpu_vram_gb_per_device: Vec::new(),
+            gpu_vram_available_gb: None,
+            gpu_vram_used_gb: None,
+            gpu_name: None,
+            gpu_count: 0,
+            unified_memory: false,
+            backend,
+            gpus: Vec::new(),
+            apple_chip_variant: None,
+            memory_bandwidth_gbps: None,
+            cpu_features,
+            gpu_link_topology: GpuLinkTopology::SingleGpu,
+            cuda_compute_capability: None,
+            rocm_version: None,
+            gpu_driver_version: None,
+            gpu_temperature_c: None,
+            container_env: container_env(),
+            swap_total_gb,
+            swap_available_gb,
+            disk_available_gb: None,
+            gpu_core_count: None,
+            pcie_gen: None,
+            pcie_width: None,
+            numa_nodes: detect_numa_nodes(),
+            overhead_gb: None,
+            nvme_read_bandwidth_gb_s: None,
+            mlock_limit_gb: detect_mlock_limit_gb(),
+            jetson_variant: None,
+        }
+    }
+
+    fn read_cpu_name(sys: &System) -> String {
+        sys.cpus()
+            .first()
+            .map(|cpu| cpu.brand().to_string())
+            .unwrap_or_else(|| "Unknown CPU".to_string())
+    }
+
+    /// CPU inference backend used when no GPU is available.
+    pub(crate) fn cpu_backend(cpu_name: &str) -> GpuBackend {
+        if cfg!(target_arch = "aarch64") || cpu_name.to_lowercase().contains("apple") {
+            GpuBackend::CpuArm
+        } else {
+            GpuBackend::CpuX86
+        }
+    }
+
+    /// Like [`SystemSpecs::detect`], but the expensive probes (nvidia-smi,
+    /// rocm-smi, lspci, system_profiler, PowerShell) run only once per process.
+    ///
+    /// Cached: CPU name/cores, every GPU field, backend, chip variant.
+    /// Not cached: `total_ram_gb` / `available_ram_gb` and `swap_total_gb` /
+    /// `swap_available_gb`, which are re-read from sysinfo on every call so
+    /// long-running callers never show stale RAM or swap.
+    /// Use [`SystemSpecs::refresh`] to force a full re-detection.
+    pub fn detect_cached() -> Self {
+        let cache = Self::detection_cache();
+        let mut specs = cache.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        specs.refresh_ram();
+        specs
+    }
+
+    /// Re-run full hardware detection and replace the cached result used by
+    /// [`SystemSpecs::detect_cached`].
+    pub fn refresh() -> Self {
+        let specs = Self::detect();
+        let cache = Self::detection_cache();
+        *cache.lock().unwrap_or_else(|e| e.into_inner()) = specs.clone();
+        specs
+    }
+
+    fn detection_cache() -> &'static std::sync::Mutex<SystemSpecs> {
+        static DETECTED: std::sync::OnceLock<std::sync::Mutex<SystemSpecs>> =
+            std::sync::OnceLock::new();
+        DETECTED.get_or_init(|| std::sync::Mutex::new(Self::detect()))
+    }
+
+    /// Re-read RAM and swap via sysinfo without probing GPUs.
+    pub fn refresh_ram(&mut self) {
+        let mut sys = System::new();
+        sys.refresh_memory();
+        let (total_ram_gb, available_ram_gb) = Self::read_ram(&sys);
+        self.total_ram_gb = total_ram_gb;
+        self.available_ram_gb = available_ram_gb;
+        let (swap_total_gb, swap_available_gb) = Self::read_swap(&sys);
+        self.swap_total_gb = swap_total_gb;
+        self.swap_available_gb = swap_available_gb;
+    }
+
+    /// Re-query free and used VRAM on the primary GPU without a full
+    /// detection. Both become `None` on backends whose tools don't report
+    /// usage.
+    pub fn refresh_vram_available(&mut self) {
+        let usage = self.gpus.first().and_then(Self::detect_gpu_vram_usage);
+        self.gpu_vram_available_gb = usage.map(|(free, _)| free);
+        self.gpu_vram_used_gb = usage.map(|(_, used)| used);
+    }
+
+    /// Returns (total, free) swap in GB from an already-refreshed `System`.
+    fn read_swap(sys: &System) -> (f64, f64) {
+        let gb = |bytes: u64| bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+        (gb(sys.total_swap()), gb(sys.free_swap()))
+    }
+
+    /// Returns (total, available) RAM in GB from an already-refreshed `System`.
+    /// Inside a container with a memory limit (Docker `--memory`, Kubernetes
+    /// `resources.limits.memory`) sysinfo still reports the host's RAM, so both
+    /// figures are clamped to the cgroup limit and its remaining headroom.
+    fn read_ram(sys: &System) -> (f64, f64) {
+        let cgroup_root = resolve_cgroup_dir(
+            std::path::Path::new("/sys/fs/cgroup"),
+            &std::fs::read_to_string("/proc/self/cgroup").unwrap_or_default(),
+        );
+        let cgroup_limit = if cfg!(target_os = "linux") {
+            read_cgroup_memory_limit(&cgroup_root)
+        } else {
+            None
+        };
+        // Headroom left before the container is OOM-killed
+        let cgroup_available = cgroup_limit.and_then(|limit| {
+            read_cgroup_memory_usage(&cgroup_root).map(|used| limit.saturating_sub(used))
+        });
 
-        let total_ram_bytes = sys.total_memory();
+        let total_ram_bytes = match cgroup_limit {
+            Some(limit) if limit < sys.total_memory() => limit,
+            _ => sys.total_memory(),
+        };
         let available_ram_bytes = sys.available_memory();
         let total_ram_gb = total_ram_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
         let available_ram_gb = if available_ram_bytes == 0 && total_ram_bytes > 0 {
             // sysinfo may fail to report available memory on some platforms
             // (e.g. macOS Tahoe / newer macOS versions). Try fallbacks.
-            Self::available_ram_fallback(&sys, total_ram_bytes, total_ram_gb)
+            Self::available_ram_fallback(sys, total_ram_bytes, total_ram_gb, cgroup_available)
         } else {
-            available_ram_bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+            let available = match cgroup_available {
+                Some(headroom) => available_ram_bytes.min(headroom),
+                None => available_ram_bytes,
+            };
+            available as f64 / (1024.0 * 1024.0 * 1024.0)
         };
+        (total_ram_gb, available_ram_gb)
+    }
 
-        let total_cpu_cores = sys.cpus().len();
-        let cpu_name = sys
-            .cpus()
-            .first()
-            .map(|cpu| cpu.brand().to_string())
-            .unwrap_or_else(|| "Unknown CPU".to_string());
+    /// Compute capability as nvidia-smi prints it, e.g. "8.9".
+    pub fn compute_capability_label(&self) -> Option<String> {
+        self.cuda_compute_capability
+            .map(|(major, minor)| format!("{}.{}", major, minor))
+    }
+
+    /// FlashAttention-2 needs an Ampere-or-newer NVIDIA GPU (sm_80+).
+    pub fn supports_flash_attention_2(&self) -> bool {
+        self.cuda_compute_capability.is_some_and(|cc| cc >= (8, 0))
+    }
+
+    /// VRAM free right now minus `reserve_gb` kept back for the desktop,
+    /// CUDA context, etc. `None` when free VRAM wasn't reported.
+    pub fn vram_headroom_gb(&self, reserve_gb: f64) -> Option<f64> {
+        self.gpu_vram_available_gb
+            .map(|free| (free - reserve_gb).max(0.0))
+    }
+
+    /// Whether the GPU was already at [`THERMAL_THROTTLE_WARN_C`] or hotter
+    /// when detected, so sustained inference may throttle below the
+    /// estimated speed.
+    pub fn thermal_throttle_risk(&self) -> bool {
+        self.gpu_temperature_c
+            .is_some_and(|temp| temp >= THERMAL_THROTTLE_WARN_C)
+    }
+
+    /// Whether other processes hold more than [`VRAM_CONTENTION_THRESHOLD`]
+    /// of the GPU's memory, e.g. a browser sitting on a few GB.
+    pub fn vram_contended(&self) -> bool {
+        match (self.gpu_vram_used_gb, self.total_gpu_vram_gb) {
+            (Some(used), Some(total)) if total > 0.0 => used > total * VRAM_CONTENTION_THRESHOLD,
+            _ => false,
+        }
+    }
+
+    /// Widest SIMD extension in `cpu_features` as a short label (e.g.
+    /// "AVX-512"), or `None` when only scalar/SSE code paths are available.
+    pub fn cpu_inference_path(&self) -> Option<&'static str> {
+        let has = |f: &str| self.cpu_features.iter().any(|x| x == f);
+        if has("amx") {
+            Some("AMX")
+        } else if has("avx512f") {
+            Some("AVX-512")
+        } else if has("sve") || has("sve2") {
+            Some("SVE")
+        } else if has("avx2") {
+            Some("AVX2")
+        } else if has("neon") {
+            Some("NEON")
+        } else {
+            None
+        }
+    }
+
+    /// Class of the primary GPU, or `None` without one.
+    pub fn gpu_class(&self) -> Option<GpuClass> {
+        self.gpus.first().map(|gpu| gpu.class)
+    }
+
+    /// Host link bandwidth of the primary GPU in GB/s, when both the PCIe
+    /// generation and width are known.
+    pub fn pcie_bandwidth_gbps(&self) -> Option<f64> {
+        Some(pcie_lane_gbps(self.pcie_gen?) * self.pcie_width? as f64)
+    }
+
+    /// Discrete VRAM a model can be split across. Over PCIe each extra card
+    /// loses [`PCIE_SPLIT_RESERVE_GB`] to its own CUDA context and staging
+    /// buffers; with NVLink the cards exchange activations peer-to-peer and
+    /// the full sum is usable.
+    pub fn pooled_vram_gb(&self) -> Option<f64> {
+        let total = self.total_gpu_vram_gb?;
+        let extra_cards = self.gpu_vram_gb_per_device.len().saturating_sub(1);
+        if self.gpu_link_topology.has_nvlink() || extra_cards == 0 {
+            return Some(total);
+        }
+        Some((total - extra_cards as f64 * PCIE_SPLIT_RESERVE_GB).max(0.0))
+    }
+
+    /// Runtime overhead kept out of the GPU pool: `overhead_gb` if set,
+    /// otherwise the backend's default.
+    pub fn runtime_overhead_gb(&self) -> f64 {
+        self.overhead_gb
+            .unwrap_or_else(|| self.backend.default_overhead_gb())
+            .max(0.0)
+    }
+
+    /// GPU memory a model can actually load into: the unified pool on Apple
+    /// Silicon or the pooled VRAM across cards, less
+    /// [`SystemSpecs::runtime_overhead_gb`]. `None` without a usable GPU.
+    pub fn usable_gpu_memory_gb(&self) -> Option<f64> {
+        if !self.has_gpu {
+            return None;
+        }
+        let pool = if self.unified_memory {
+            self.gpu_vram_gb
+        } else {
+            self.pooled_vram_gb()
+        }?;
+        Some((pool - self.runtime_overhead_gb()).max(0.0))
+    }
+
+    /// [`SystemSpecs::usable_gpu_memory_gb`] less the VRAM other processes
+    /// held at detection, which a model can't load into. Unified memory is
+    /// left as is, since its free figure is shared with system RAM.
+    pub fn available_gpu_memory_gb(&self) -> Option<f64> {
+        let usable = self.usable_gpu_memory_gb()?;
+        if self.unified_memory {
+            return Some(usable);
+        }
+        Some((usable - self.gpu_vram_used_gb.unwrap_or(0.0)).max(0.0))
+    }
+
+    /// Set the runtime overhead instead of using the backend default.
+    pub fn with_overhead_override(mut self, overhead_gb: f64) -> Self {
+        self.overhead_gb = Some(overhead_gb);
+        self
+    }
+
+    /// Describe a machine by hand, without probing any hardware. Useful for
+    /// tests and for libraries modelling a machine they aren't running on.
+    ///
+    /// `vram_gb` is per card; `gpu_count` identical cards are assumed. With
+    /// `vram_gb: None` (or a CPU backend) the profile is CPU-only. A Metal
+    /// backend is treated as unified memory. Everything else gets neutral
+    /// defaults (all RAM free, 8 cores, no optional hardware details); wrap
+    /// the result in [`SystemSpecsBuilder::from_specs`] to adjust any field.
+    pub fn new_manual(
+        ram_gb: f64,
+        vram_gb: Option<f64>,
+        backend: GpuBackend,
+        gpu_count: u32,
+    ) -> Self {
+        let is_cpu = matches!(backend, GpuBackend::CpuArm | GpuBackend::CpuX86);
+        let unified_memory = backend == GpuBackend::Metal;
+        let gpus: Vec<GpuInfo> = match vram_gb {
+            Some(vram) if !is_cpu && gpu_count > 0 => vec![GpuInfo {
+                name: "Manual GPU".to_string(),
+                vram_gb: Some(vram),
+                backend,
+                count: gpu_count,
+                unified_memory,
+                class: if unified_memory {
+                    GpuClass::Unified
+                } else {
+                    GpuClass::Discrete
+                },
+            }],
+            _ => Vec::new(),
+        };
+        let primary = gpus.first();
+        let backend = if gpus.is_empty() && !is_cpu {
+            Self::cpu_backend("")
+        } else {
+            backend
+        };
+
+        SystemSpecs {
+            total_ram_gb: ram_gb,
+            available_ram_gb: ram_gb,
+            total_cpu_cores: 8,
+            cpu_name: "Unknown CPU".to_string(),
+            has_gpu: primary.is_some(),
+            gpu_vram_gb: primary.and_then(|g| g.vram_gb),
+            total_gpu_vram_gb: primary.and_then(|g| g.vram_gb.map(|v| v * g.count as f64)),
+            gpu_vram_gb_per_device: Self::per_device_vram(&gpus),
+            gpu_vram_available_gb: None,
+            gpu_vram_used_gb: None,
+            gpu_name: primary.map(|g| g.name.clone()),
+            gpu_count: primary.map(|g| g.count).unwrap_or(0),
+            unified_memory: primary.is_some() && unified_memory,
+            backend,
+            gpus,
+            apple_chip_variant: None,
+            memory_bandwidth_gbps: None,
+            cpu_features: Vec::new(),
+            gpu_link_topology: if gpu_count > 1 && !is_cpu && vram_gb.is_some() {
+                GpuLinkTopology::PcieSli
+            } else {
+                GpuLinkTopology::SingleGpu
+            },
+            cuda_compute_capability: None,
+            rocm_version: None,
+            gpu_driver_version: None,
+            gpu_temperature_c: None,
+            container_env: ContainerEnv::None,
+            swap_total_gb: 0.0,
+            swap_available_gb: 0.0,
+            disk_available_gb: None,
+            gpu_core_count: None,
+            pcie_gen: None,
+            pcie_width: None,
+            numa_nodes: 1,
+            overhead_gb: None,
+            nvme_read_bandwidth_gb_s: None,
+            mlock_limit_gb: None,
+            jetson_variant: None,
+        }
+    }
+
+    /// A fixed single-GPU machine for unit tests: [`SystemSpecs::new_manual`]
+    /// with one card of `vram_gb`. Nothing is probed, so results don't
+    /// depend on the machine running the tests. A CPU backend gives a
+    /// CPU-only machine. Available with the `testing` feature.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn mock(vram_gb: f64, ram_gb: f64, backend: GpuBackend) -> Self {
+        Self::new_manual(ram_gb, Some(vram_gb), backend, 1)
+    }
+
+    /// Start a [`SystemSpecsBuilder`] that falls back to detected hardware.
+    pub fn builder() -> SystemSpecsBuilder {
+        SystemSpecsBuilder::new()
+    }
+
+    /// Detect the hardware, then apply any `LLMFIT_OVERRIDE_*` environment
+    /// variables (see [`SystemSpecs::apply_env_overrides`]). Values that
+    /// don't parse are ignored; call [`SystemSpecs::detect_hardware`] and
+    /// apply the overrides yourself to report them.
+    pub fn detect() -> Self {
+        let mut specs = Self::detect_hardware();
+        specs.apply_env_overrides();
+        specs
+    }
+
+    /// Probe the hardware without applying environment overrides.
+    pub fn detect_hardware() -> Self {
+        let host = HostInfo::read();
+        let probes = GpuProbes::run();
+        let apple_gpu_cores = probes.apple_gpu_cores;
+        let jetson_variant = probes
+            .jetson
+            .as_deref()
+            .and_then(JetsonVariant::from_board_name);
+        let nvidia_usage = probes.nvidia_usage.clone();
+        let gpus = Self::merge_gpu_probes(probes, host.total_ram_gb, &host.cpu_name);
+        let follow_ups = GpuFollowUps {
+            gpu_core_count: apple_gpu_cores,
+            jetson_variant,
+            ..GpuFollowUps::run(&gpus, &host.cpu_name, nvidia_usage.as_deref())
+        };
+        Self::assemble(host, gpus, follow_ups)
+    }
+
+    /// Like [`SystemSpecs::detect`], but every vendor probe (nvidia-smi,
+    /// rocm-smi, lspci, system_profiler, PowerShell, npu-smi, clinfo) and the
+    /// sysinfo refresh run concurrently on tokio's blocking pool, so detection
+    /// takes roughly as long as the slowest tool instead of the sum of all.
+    #[cfg(feature = "async")]
+    pub async fn detect_async() -> Self {
+        use tokio::task::spawn_blocking;
+
+        // Spawn everything up front; awaiting in sequence afterwards is fine
+        // because the tasks are already running.
+        let host = spawn_blocking(HostInfo::read);
+        let nvidia = spawn_blocking(GpuProbes::probe_nvidia);
+        let amd = spawn_blocking(GpuProbes::probe_amd);
+        let windows = spawn_blocking(Self::detect_gpu_windows_info);
+        let intel = spawn_blocking(Self::detect_intel_gpu);
+        let apple = spawn_blocking(Self::detect_apple_gpu);
+        let egpu = spawn_blocking(Self::detect_egpu_macos);
+        let ascend = spawn_blocking(Self::detect_ascend_npus);
+        let jetson = spawn_blocking(Self::detect_jetson);
+        let opencl = spawn_blocking(Self::detect_opencl_gpu);
+
+        // A panicking probe is treated the same as a tool that isn't installed.
+        let host = match host.await {
+            Ok(host) => host,
+            Err(_) => HostInfo::read(),
+        };
+        let (apple, apple_gpu_cores) = apple.await.unwrap_or_default();
+        let (nvidia, nvidia_usage) = nvidia.await.unwrap_or_default();
+        let probes = GpuProbes {
+            nvidia,
+            nvidia_usage: nvidia_usage.clone(),
+            amd: amd.await.unwrap_or_default(),
+            windows: windows.await.unwrap_or_default(),
+            intel: intel.await.unwrap_or_default(),
+            apple,
+            apple_gpu_cores,
+            egpu: egpu.await.unwrap_or_default(),
+            ascend: ascend.await.unwrap_or_default(),
+            jetson: jetson.await.unwrap_or_default(),
+            opencl: opencl.await.unwrap_or_default(),
+        };
+        let jetson_variant = probes
+            .jetson
+            .as_deref()
+            .and_then(JetsonVariant::from_board_name);
+        let gpus = Self::merge_gpu_probes(probes, host.total_ram_gb, &host.cpu_name);
+
+        // Second round: probes that depend on which GPUs were found.
+        let primary = gpus.first().cloned();
+        let backend = Self::primary_backend(&gpus, &host.cpu_name);
+        let has_cuda = gpus.iter().any(|g| g.backend == GpuBackend::Cuda);
+        let has_rocm = gpus.iter().any(|g| g.backend == GpuBackend::Rocm);
+        let vram_usage = spawn_blocking(move || {
+            primary
+                .as_ref()
+                .and_then(|g| Self::initial_vram_usage(g, nvidia_usage.as_deref()))
+        });
+        let cpu_name = host.cpu_name.clone();
+        let apple_chip_variant = spawn_blocking(move || {
+            (backend == GpuBackend::Metal)
+                .then(|| Self::detect_apple_chip_variant(&cpu_name))
+                .flatten()
+        });
+        let topology_gpus = gpus.clone();
+        let link_topology = spawn_blocking(move || Self::detect_link_topology(&topology_gpus));
+        let cuda_info = spawn_blocking(move || {
+            if has_cuda {
+                Self::detect_nvidia_cuda_info()
+            } else {
+                (None, None)
+            }
+        });
+
+        let rocm_version =
+            spawn_blocking(move || has_rocm.then(Self::detect_rocm_version).flatten());
+        let pcie_gpus = gpus.clone();
+        let pcie_link = spawn_blocking(move || Self::detect_pcie_link(&pcie_gpus));
+        let driver_gpus = gpus.clone();
+        let driver_cpu = host.cpu_name.clone();
+        let os_driver_version =
+            spawn_blocking(move || Self::detect_os_driver_version(&driver_gpus, &driver_cpu));
+        let temperature_gpu = gpus.first().cloned();
+        let gpu_temperature = spawn_blocking(move || {
+            temperature_gpu
+                .as_ref()
+                .and_then(Self::detect_gpu_temperature)
+        });
+
+        let follow_ups = GpuFollowUps {
+            vram_usage: vram_usage.await.unwrap_or_default(),
+            apple_chip_variant: apple_chip_variant.await.unwrap_or_default(),
+            link_topology: link_topology.await.unwrap_or(GpuLinkTopology::SingleGpu),
+            cuda_info: cuda_info.await.unwrap_or_default(),
+            rocm_version: rocm_version.await.unwrap_or_default(),
+            pcie_link: pcie_link.await.unwrap_or_default(),
+            gpu_core_count: apple_gpu_cores,
+            jetson_variant,
+            os_driver_version: os_driver_version.await.unwrap_or_default(),
+            gpu_temperature_c: gpu_temperature.await.unwrap_or_default(),
+        };
+        let mut specs = Self::assemble(host, gpus, follow_ups);
+        specs.apply_env_overrides();
+        specs
+    }
+
+    /// Backend of the primary GPU, or the CPU fallback when there is none.
+    fn primary_backend(gpus: &[GpuInfo], cpu_name: &str) -> GpuBackend {
+        gpus.first()
+            .map(|g| g.backend)
+            .unwrap_or_else(|| Self::cpu_backend(cpu_name))
+    }
 
-        let gpus = Self::detect_all_gpus(total_ram_gb, &cpu_name);
+    /// Build the final specs from the host info, merged GPU list and
+    /// follow-up probe results. Shared by the sync and async detectors.
+    fn assemble(host: HostInfo, gpus: Vec<GpuInfo>, follow_ups: GpuFollowUps) -> Self {
+        let HostInfo {
+            total_ram_gb,
+            available_ram_gb,
+            total_cpu_cores,
+            cpu_name,
+            cpu_features,
+            swap_total_gb,
+            swap_available_gb,
+            disk_available_gb,
+            nvme_read_bandwidth_gb_s,
+            mlock_limit_gb,
+            numa_nodes,
+        } = host;
+        let GpuFollowUps {
+            vram_usage,
+            apple_chip_variant,
+            link_topology: gpu_link_topology,
+            cuda_info: (compute_capability, nvidia_driver_version),
+            rocm_version,
+            pcie_link: (pcie_gen, pcie_width),
+            gpu_core_count,
+            jetson_variant,
+            os_driver_version,
+            gpu_temperature_c,
+        } = follow_ups;
 
         // Primary GPU = the one with the most VRAM (best for inference).
         // For fit scoring, we use the primary GPU's VRAM pool.
@@ -91,17 +1033,23 @@ impl SystemSpecs {
         let gpu_vram_gb = primary.and_then(|g| g.vram_gb);
         // Total VRAM = per-card VRAM * count (for multi-GPU tensor splitting)
         let total_gpu_vram_gb = primary.and_then(|g| g.vram_gb.map(|vram| vram * g.count as f64));
+        let gpu_vram_gb_per_device = Self::per_device_vram(&gpus);
         let gpu_name = primary.map(|g| g.name.clone());
         let gpu_count = primary.map(|g| g.count).unwrap_or(0);
         let unified_memory = primary.map(|g| g.unified_memory).unwrap_or(false);
-
-        let cpu_backend =
-            if cfg!(target_arch = "aarch64") || cpu_name.to_lowercase().contains("apple") {
-                GpuBackend::CpuArm
-            } else {
-                GpuBackend::CpuX86
-            };
-        let backend = primary.map(|g| g.backend).unwrap_or(cpu_backend);
+        let backend = Self::primary_backend(&gpus, &cpu_name);
+        let memory_bandwidth_gbps = primary
+            .and_then(|g| memory_bandwidth_gbps(&g.name))
+            .or_else(|| jetson_variant.map(|v| v.memory_bandwidth_gbps()));
+        let cuda_compute_capability = compute_capability
+            .as_deref()
+            .and_then(parse_compute_capability);
+        let container_env = container_env();
+        let gpu_driver_version = match backend {
+            GpuBackend::Cuda => nvidia_driver_version,
+            GpuBackend::Rocm => rocm_version.clone(),
+            _ => os_driver_version,
+        };
 
         SystemSpecs {
             total_ram_gb,
@@ -111,39 +1059,63 @@ impl SystemSpecs {
             has_gpu,
             gpu_vram_gb,
             total_gpu_vram_gb,
+            gpu_vram_gb_per_device,
+            gpu_vram_available_gb: vram_usage.map(|(free, _)| free),
+            gpu_vram_used_gb: vram_usage.map(|(_, used)| used),
             gpu_name,
             gpu_count,
             unified_memory,
             backend,
             gpus,
+            apple_chip_variant,
+            memory_bandwidth_gbps,
+            cpu_features,
+            gpu_link_topology,
+            cuda_compute_capability,
+            rocm_version,
+            gpu_driver_version,
+            gpu_temperature_c,
+            container_env,
+            swap_total_gb,
+            swap_available_gb,
+            disk_available_gb,
+            gpu_core_count,
+            pcie_gen,
+            pcie_width,
+            numa_nodes,
+            overhead_gb: None,
+            nvme_read_bandwidth_gb_s,
+            mlock_limit_gb,
+            jetson_variant,
         }
     }
 
-    /// Detect all GPUs across all vendors. Returns a Vec sorted by VRAM descending
-    /// (best GPU first). Unlike the old cascade, this does NOT short-circuit:
-    /// a system with both NVIDIA and AMD GPUs will report both.
-    fn detect_all_gpus(total_ram_gb: f64, cpu_name: &str) -> Vec<GpuInfo> {
+    /// Expand grouped GPU entries into one VRAM figure per physical card,
+    /// largest first. GPUs with unknown VRAM are skipped.
+    fn per_device_vram(gpus: &[GpuInfo]) -> Vec<f64> {
+        let mut per_device: Vec<f64> = gpus
+            .iter()
+            .filter_map(|g| g.vram_gb.map(|vram| (vram, g.count)))
+            .flat_map(|(vram, count)| std::iter::repeat_n(vram, count as usize))
+            .collect();
+        per_device.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        per_device
+    }
+
+    /// Combine the raw vendor probe results into one GPU list, sorted by VRAM
+    /// descending (best GPU first). Unlike the old cascade, this does NOT
+    /// short-circuit: a system with both NVIDIA and AMD GPUs will report both.
+    fn merge_gpu_probes(probes: GpuProbes, total_ram_gb: f64, cpu_name: &str) -> Vec<GpuInfo> {
         let mut gpus = Vec::new();
 
         // NVIDIA GPUs via nvidia-smi, with sysfs fallback for Linux/toolbox setups
-        let nvidia = Self::detect_nvidia_gpus();
-        if nvidia.is_empty() {
-            if let Some(nvidia_sysfs) = Self::detect_nvidia_gpu_sysfs_info() {
-                gpus.push(nvidia_sysfs);
-            }
-        } else {
-            gpus.extend(nvidia);
-        }
+        gpus.extend(probes.nvidia);
 
         // AMD GPUs via rocm-smi or sysfs
-        if let Some(amd) = Self::detect_amd_gpu_rocm_info() {
-            gpus.push(amd);
-        } else if let Some(amd) = Self::detect_amd_gpu_sysfs_info() {
-            gpus.push(amd);
-        }
+        gpus.extend(probes.amd);
 
         // Windows WMI (catches GPUs not found by vendor-specific tools)
-        for wmi_gpu in Self::detect_gpu_windows_info() {
+        for wmi_gpu in probes.windows {
             // Skip if we already found a GPU with the same name from a vendor tool
             let dominated = gpus.iter().any(|existing| {
                 let existing_lower = existing.name.to_lowercase();
@@ -155,6 +1127,14 @@ impl SystemSpecs {
             }
         }
 
+        // Other AMD APUs found through sysfs with only a small carveout and
+        // no GTT size: assume the driver's default share of system RAM.
+        for gpu in &mut gpus {
+            if gpu.unified_memory && gpu.vram_gb.is_none() && gpu.backend == GpuBackend::Vulkan {
+                gpu.vram_gb = Some(total_ram_gb * AMD_APU_SHARED_RAM_FRACTION);
+            }
+        }
+
         // AMD unified memory APUs (e.g. Ryzen AI MAX series).
         // These share the full system RAM between CPU and GPU, like Apple Silicon.
         // WMI AdapterRAM is a 32-bit field capped at ~4 GB, so we override with
@@ -166,6 +1146,7 @@ impl SystemSpecs {
             });
             if let Some(idx) = amd_idx {
                 gpus[idx].unified_memory = true;
+                gpus[idx].class = GpuClass::Unified;
                 gpus[idx].vram_gb = Some(total_ram_gb);
             } else {
                 // No AMD GPU found via other methods; create one.
@@ -175,6 +1156,7 @@ impl SystemSpecs {
                     backend: GpuBackend::Vulkan,
                     count: 1,
                     unified_memory: true,
+                    class: GpuClass::Unified,
                 });
             }
         }
@@ -192,27 +1174,62 @@ impl SystemSpecs {
                 let lower = gpu.name.to_lowercase();
                 if lower.contains("gb10") || lower.contains("gb20") {
                     gpu.unified_memory = true;
+                    gpu.class = GpuClass::Unified;
                     gpu.vram_gb = Some(total_ram_gb);
                 }
             }
         }
 
+        // NVIDIA Jetson / Tegra boards. The integrated GPU shares LPDDR with the
+        // CPU, and nvidia-smi is often missing (JetPack < 6), so add the board
+        // ourselves or, if nvidia-smi did see it, fix up its memory pool.
+        if let Some(board) = probes.jetson {
+            let mut found = false;
+            for gpu in gpus.iter_mut().filter(|g| g.backend == GpuBackend::Cuda) {
+                gpu.unified_memory = true;
+                gpu.class = GpuClass::Unified;
+                gpu.vram_gb = Some(total_ram_gb);
+                found = true;
+            }
+            if !found {
+                gpus.push(GpuInfo {
+                    name: board,
+                    vram_gb: Some(total_ram_gb),
+                    backend: GpuBackend::Cuda,
+                    count: 1,
+                    unified_memory: true,
+                    class: GpuClass::Unified,
+                });
+            }
+        }
+
         // Intel Arc via sysfs
-        if let Some(vram) = Self::detect_intel_gpu() {
+        if let Some((name, vram)) = probes.intel {
             let already_found = gpus.iter().any(|g| g.name.to_lowercase().contains("intel"));
             if !already_found {
                 gpus.push(GpuInfo {
-                    name: "Intel Arc".to_string(),
+                    name,
                     vram_gb: Some(vram),
                     backend: GpuBackend::Sycl,
                     count: 1,
                     unified_memory: false,
+                    class: if vram > 0.0 {
+                        GpuClass::Discrete
+                    } else {
+                        GpuClass::Integrated
+                    },
                 });
             }
         }
 
-        // Apple Silicon (unified memory)
-        if let Some(vram) = Self::detect_apple_gpu(total_ram_gb) {
+        // A Thunderbolt eGPU on an Intel Mac has its own VRAM. It takes the
+        // place of the Apple path, which would otherwise report the host's
+        // RAM as a unified pool and sort above the card.
+        if let Some(egpu) = probes.egpu {
+            gpus.push(egpu);
+        } else if probes.apple {
+            // Apple Silicon (unified memory): GPU and CPU share the same RAM
+            // pool, so total RAM is reported as the VRAM capacity.
             let name = if cpu_name.to_lowercase().contains("apple") {
                 cpu_name.to_string()
             } else {
@@ -220,17 +1237,23 @@ impl SystemSpecs {
             };
             gpus.push(GpuInfo {
                 name,
-                vram_gb: Some(vram),
+                vram_gb: Some(total_ram_gb),
                 backend: GpuBackend::Metal,
                 count: 1,
                 unified_memory: true,
+                class: GpuClass::Unified,
             });
         }
 
         // Ascend NPUs via npu-smi
-        let ascend = Self::detect_ascend_npus();
-        if !ascend.is_empty() {
-            gpus.extend(ascend);
+        gpus.extend(probes.ascend);
+
+        // OpenCL-only GPUs (older Intel HD, Mali, older AMD without ROCm).
+        // Only used as a last resort when nothing more specific was found.
+        if gpus.is_empty()
+            && let Some(opencl) = probes.opencl
+        {
+            gpus.push(opencl);
         }
 
         // Sort by VRAM descending so the best GPU is primary
@@ -244,53 +1267,58 @@ impl SystemSpecs {
     }
 
     /// Detect NVIDIA GPUs via nvidia-smi. Returns one GpuInfo per unique model,
-    /// with count and per-card VRAM for same-model multi-GPU setups.
+    /// with count and per-card VRAM for same-model multi-GPU setups, and the
+    /// `memory.free, memory.used, name` lines read by the same query.
     ///
     /// First tries querying `addressing_mode` to detect unified memory (Tegra/Grace
     /// Blackwell platforms). Falls back to the standard 2-column query if the field
     /// is unavailable on older nvidia-smi versions.
-    fn detect_nvidia_gpus() -> Vec<GpuInfo> {
+    fn detect_nvidia_gpus() -> (Vec<GpuInfo>, Option<String>) {
         // Try the extended query first (addressing_mode,memory.total,name).
         // On NVIDIA Tegra / Grace Blackwell, addressing_mode returns "ATS"
         // (Address Translation Services) which signals unified CPU+GPU memory.
-        if let Some(gpus) = Self::try_nvidia_smi_with_addressing_mode() {
-            return gpus;
+        if let Some(found) = Self::try_nvidia_smi_with_addressing_mode() {
+            return found;
         }
 
-        // Fallback: standard 2-column query for older nvidia-smi versions
-        let output = match std::process::Command::new("nvidia-smi")
-            .arg("--query-gpu=memory.total,name")
-            .arg("--format=csv,noheader,nounits")
-            .output()
-        {
-            Ok(o) if o.status.success() => o,
-            _ => return Vec::new(),
+        // Fallback: standard query for nvidia-smi versions without addressing_mode
+        let output = match run_with_timeout(
+            std::process::Command::new(nvidia_smi())
+                .arg("--query-gpu=memory.free,memory.used,memory.total,name")
+                .arg("--format=csv,noheader,nounits"),
+            DETECTION_COMMAND_TIMEOUT,
+        ) {
+            Some(o) if o.status.success() => o,
+            _ => return (Vec::new(), None),
         };
 
         let text = match String::from_utf8(output.stdout) {
             Ok(t) => t,
-            Err(_) => return Vec::new(),
+            Err(_) => return (Vec::new(), None),
         };
 
-        Self::parse_nvidia_smi_list(&text)
+        let (rest, usage) = split_nvidia_usage_columns(&text, 1);
+        (Self::parse_nvidia_smi_list(&rest), Some(usage))
     }
 
     /// Try nvidia-smi with `addressing_mode` column. Returns `None` if the
     /// query fails (e.g. older driver that doesn't support the field), so the
     /// caller can fall back to the standard query.
-    fn try_nvidia_smi_with_addressing_mode() -> Option<Vec<GpuInfo>> {
-        let output = std::process::Command::new("nvidia-smi")
-            .arg("--query-gpu=addressing_mode,memory.total,name")
-            .arg("--format=csv,noheader,nounits")
-            .output()
-            .ok()?;
+    fn try_nvidia_smi_with_addressing_mode() -> Option<(Vec<GpuInfo>, Option<String>)> {
+        let output = run_with_timeout(
+            std::process::Command::new(nvidia_smi())
+                .arg("--query-gpu=memory.free,memory.used,addressing_mode,memory.total,name")
+                .arg("--format=csv,noheader,nounits"),
+            DETECTION_COMMAND_TIMEOUT,
+        )?;
 
         if !output.status.success() {
             return None;
         }
 
         let text = String::from_utf8(output.stdout).ok()?;
-        Some(Self::parse_nvidia_smi_extended(&text))
+        let (rest, usage) = split_nvidia_usage_columns(&text, 2);
+        Some((Self::parse_nvidia_smi_extended(&rest), Some(usage)))
     }
 
     /// Parse `nvidia-smi --query-gpu=addressing_mode,memory.total,name`.
@@ -359,6 +1387,11 @@ impl SystemSpecs {
                 backend: GpuBackend::Cuda,
                 count,
                 unified_memory: is_unified,
+                class: if is_unified {
+                    GpuClass::Unified
+                } else {
+                    GpuClass::Discrete
+                },
             })
             .collect()
     }
@@ -415,17 +1448,360 @@ impl SystemSpecs {
                 backend: GpuBackend::Cuda,
                 count,
                 unified_memory: false,
+                class: GpuClass::Discrete,
             })
             .collect()
     }
 
-    /// Detect NVIDIA GPUs via Linux sysfs when nvidia-smi is unavailable.
-    /// This is common in containerized environments (e.g. Toolbx) and
-    /// Nouveau-based systems.
-    fn detect_nvidia_gpu_sysfs_info() -> Option<GpuInfo> {
-        if !cfg!(target_os = "linux") {
-            return None;
-        }
+    /// Query compute capability and driver version in a single nvidia-smi call.
+    fn detect_nvidia_cuda_info() -> (Option<String>, Option<String>) {
+        run_with_timeout(
+            std::process::Command::new(nvidia_smi())
+                .arg("--query-gpu=compute_cap,driver_version")
+                .arg("--format=csv,noheader,nounits"),
+            DETECTION_COMMAND_TIMEOUT,
+        )
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|text| Self::parse_nvidia_cuda_info(&text))
+        .unwrap_or((None, None))
+    }
+
+    /// Parse "compute_cap, driver_version" lines (e.g. "8.9, 550.54.14").
+    /// Each column takes the first valid value across cards; entries like
+    /// "[N/A]" are skipped.
+    fn parse_nvidia_cuda_info(text: &str) -> (Option<String>, Option<String>) {
+        let is_version = |s: &str| {
+            !s.is_empty()
+                && s.split('.')
+                    .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        };
+        let mut compute_cap = None;
+        let mut driver = None;
+        for line in text.lines() {
+            let mut cols = line.split(',').map(str::trim);
+            let cc = cols.next().unwrap_or("");
+            let drv = cols.next().unwrap_or("");
+            if compute_cap.is_none() && cc.contains('.') && is_version(cc) {
+                compute_cap = Some(cc.to_string());
+            }
+            if driver.is_none() && is_version(drv) {
+                driver = Some(drv.to_string());
+            }
+        }
+        (compute_cap, driver)
+    }
+
+    /// Classify how the detected GPUs are connected to each other.
+    fn detect_link_topology(gpus: &[GpuInfo]) -> GpuLinkTopology {
+        let physical: u32 = gpus.iter().map(|g| g.count).sum();
+        if physical <= 1 {
+            return GpuLinkTopology::SingleGpu;
+        }
+        if gpus.len() > 1 {
+            return GpuLinkTopology::Independent;
+        }
+        if gpus[0].backend == GpuBackend::Cuda {
+            let all_pairs_linked = run_with_timeout(
+                std::process::Command::new(nvidia_smi()).args(["topo", "--matrix"]),
+                DETECTION_COMMAND_TIMEOUT,
+            )
+            .filter(|o| o.status.success())
+            .and_then(|o| String::from_utf8(o.stdout).ok())
+            .is_some_and(|text| Self::parse_nvidia_topo_all_nvlink(&text) == Some(true));
+            // Active NVLink bridges count even when the topology matrix
+            // doesn't show every pair linked.
+            if all_pairs_linked || Self::detect_nvlink_present() {
+                return GpuLinkTopology::NvlinkPod;
+            }
+        }
+        GpuLinkTopology::PcieSli
+    }
+
+    /// Parse `nvidia-smi topo --matrix` and report whether every GPU pair is
+    /// connected via NVLink ("NV#" cells). Returns `None` with fewer than 2 GPUs.
+    ///
+    /// ```text
+    ///         GPU0    GPU1    CPU Affinity    NUMA Affinity
+    /// GPU0     X      NV12    0-63            0
+    /// GPU1    NV12     X      0-63            0
+    /// ```
+    fn parse_nvidia_topo_all_nvlink(text: &str) -> Option<bool> {
+        // Data rows start with "GPUn" in column 0; the header row is indented.
+        let rows: Vec<Vec<&str>> = text
+            .lines()
+            .filter(|line| !line.starts_with(char::is_whitespace))
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .filter(|tokens| {
+                tokens.first().is_some_and(|t| {
+                    t.strip_prefix("GPU")
+                        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+                })
+            })
+            .collect();
+
+        let n = rows.len();
+        if n < 2 {
+            return None;
+        }
+        let all_nvlink = rows.iter().enumerate().all(|(i, row)| {
+            (0..n)
+                .filter(|&j| j != i)
+                .all(|j| row.get(j + 1).is_some_and(|cell| cell.starts_with("NV")))
+        });
+        Some(all_nvlink)
+    }
+
+    /// Run `nvidia-smi nvlink --status`; see [`Self::parse_nvlink_status`].
+    fn detect_nvlink_present() -> bool {
+        run_with_timeout(
+            std::process::Command::new(nvidia_smi()).args(["nvlink", "--status"]),
+            DETECTION_COMMAND_TIMEOUT,
+        )
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .is_some_and(|text| Self::parse_nvlink_status(&text))
+    }
+
+    /// PCIe generation and lane width of the primary GPU's host link, from
+    /// nvidia-smi on CUDA systems and /sys/class/drm elsewhere on Linux.
+    /// These are the *current* link values, which some cards lower at idle.
+    fn detect_pcie_link(gpus: &[GpuInfo]) -> (Option<u8>, Option<u8>) {
+        let Some(primary) = gpus.first() else {
+            return (None, None);
+        };
+        if primary.unified_memory {
+            return (None, None);
+        }
+        if primary.backend == GpuBackend::Cuda
+            && let Some((generation, width)) = run_with_timeout(
+                std::process::Command::new(nvidia_smi())
+                    .arg("--query-gpu=pcie.link.gen.current,pcie.link.width.current")
+                    .arg("--format=csv,noheader,nounits"),
+                DETECTION_COMMAND_TIMEOUT,
+            )
+            .filter(|o| o.status.success())
+            .and_then(|o| String::from_utf8(o.stdout).ok())
+            .and_then(|text| Self::parse_nvidia_pcie_link(&text))
+        {
+            return (Some(generation), Some(width));
+        }
+        if cfg!(target_os = "linux") {
+            read_drm_pcie_link(std::path::Path::new("/sys/class/drm"))
+                .map_or((None, None), |(g, w)| (Some(g), Some(w)))
+        } else {
+            (None, None)
+        }
+    }
+
+    /// Parse the first line of
+    /// `nvidia-smi --query-gpu=pcie.link.gen.current,pcie.link.width.current`,
+    /// e.g. "4, 16".
+    fn parse_nvidia_pcie_link(text: &str) -> Option<(u8, u8)> {
+        let (generation, width) = text.lines().next()?.split_once(',')?;
+        Some((generation.trim().parse().ok()?, width.trim().parse().ok()?))
+    }
+
+    /// Map a sysfs `current_link_speed` ("16.0 GT/s PCIe") to a PCIe generation.
+    fn parse_pcie_link_speed(s: &str) -> Option<u8> {
+        let gts: f64 = s.split_whitespace().next()?.parse().ok()?;
+        match gts {
+            x if x >= 64.0 => Some(6),
+            x if x >= 32.0 => Some(5),
+            x if x >= 16.0 => Some(4),
+            x if x >= 8.0 => Some(3),
+            x if x >= 5.0 => Some(2),
+            x if x >= 2.5 => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Parse `nvidia-smi nvlink --status` and report whether at least two GPUs
+    /// are listed and every one of them has an active link. Cards without an
+    /// NVLink bridge still print links, just marked `<inactive>`.
+    ///
+    /// ```text
+    /// GPU 0: NVIDIA A100-SXM4-80GB (UUID: GPU-...)
+    ///          Link 0: 25 GB/s
+    ///          Link 1: <inactive>
+    /// ```
+    fn parse_nvlink_status(text: &str) -> bool {
+        let mut gpus: Vec<bool> = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.starts_with("GPU ") {
+                gpus.push(false);
+            } else if line.starts_with("Link ")
+                && let Some((_, status)) = line.split_once(':')
+                && let Some(active) = gpus.last_mut()
+            {
+                let status = status.trim();
+                if !status.is_empty() && !status.to_lowercase().contains("inactive") {
+                    *active = true;
+                }
+            }
+        }
+        gpus.len() > 1 && gpus.iter().all(|&active| active)
+    }
+
+    /// (free, used) VRAM of the primary GPU at detection time. NVIDIA figures
+    /// come from the `memory.free` and `memory.used` columns of the detection
+    /// query (`nvidia_usage`) rather than a second nvidia-smi run.
+    fn initial_vram_usage(primary: &GpuInfo, nvidia_usage: Option<&str>) -> Option<(f64, f64)> {
+        if primary.backend != GpuBackend::Cuda {
+            return Self::detect_gpu_vram_usage(primary);
+        }
+        if primary.unified_memory {
+            return None;
+        }
+        nvidia_usage.and_then(|text| Self::parse_nvidia_smi_usage(text, &primary.name))
+    }
+
+    /// Query how much VRAM is free and used right now on the primary GPU
+    /// model, as (free, used) GB. Only NVIDIA and ROCm tools report live
+    /// usage; other backends return `None`.
+    fn detect_gpu_vram_usage(primary: &GpuInfo) -> Option<(f64, f64)> {
+        if primary.unified_memory {
+            return None;
+        }
+        match primary.backend {
+            GpuBackend::Cuda => {
+                let output = run_with_timeout(
+                    std::process::Command::new(nvidia_smi())
+                        .arg("--query-gpu=memory.free,memory.used,name")
+                        .arg("--format=csv,noheader,nounits"),
+                    DETECTION_COMMAND_TIMEOUT,
+                )
+                .filter(|o| o.status.success())?;
+                let text = String::from_utf8(output.stdout).ok()?;
+                Self::parse_nvidia_smi_usage(&text, &primary.name)
+            }
+            GpuBackend::Rocm => {
+                let output = run_with_timeout(
+                    std::process::Command::new("rocm-smi")
+                        .arg("--showmeminfo")
+                        .arg("vram"),
+                    DETECTION_COMMAND_TIMEOUT,
+                )
+                .filter(|o| o.status.success())?;
+                let text = String::from_utf8(output.stdout).ok()?;
+                Self::parse_rocm_smi_usage(&text)
+            }
+            _ => None,
+        }
+    }
+
+    /// Current temperature of the primary GPU via nvidia-smi or rocm-smi.
+    /// Other backends have no portable way to read it.
+    fn detect_gpu_temperature(primary: &GpuInfo) -> Option<f32> {
+        let (cmd, args): (&str, &[&str]) = match primary.backend {
+            GpuBackend::Cuda => (
+                nvidia_smi(),
+                &[
+                    "--query-gpu=temperature.gpu,name",
+                    "--format=csv,noheader,nounits",
+                ],
+            ),
+            GpuBackend::Rocm => ("rocm-smi", &["--showtemp"]),
+            _ => return None,
+        };
+        let output = run_with_timeout(
+            std::process::Command::new(cmd).args(args),
+            DETECTION_COMMAND_TIMEOUT,
+        )
+        .filter(|o| o.status.success())?;
+        let text = String::from_utf8(output.stdout).ok()?;
+        if primary.backend == GpuBackend::Cuda {
+            Self::parse_nvidia_smi_temperature(&text, &primary.name)
+        } else {
+            Self::parse_rocm_smi_temperature(&text)
+        }
+    }
+
+    /// Hottest card matching `name` in `nvidia-smi
+    /// --query-gpu=temperature.gpu,name` output.
+    fn parse_nvidia_smi_temperature(text: &str, name: &str) -> Option<f32> {
+        text.lines()
+            .filter_map(|line| line.trim().split_once(','))
+            .filter(|(_, gpu)| gpu.trim() == name)
+            .filter_map(|(temp, _)| temp.trim().parse::<f32>().ok())
+            .reduce(f32::max)
+    }
+
+    /// Hottest edge sensor in `rocm-smi --showtemp` output, e.g.
+    /// "GPU[0]  : Temperature (Sensor edge) (C): 45.0". The edge sensor is
+    /// the one comparable to nvidia-smi's reading; junction runs hotter.
+    fn parse_rocm_smi_temperature(text: &str) -> Option<f32> {
+        text.lines()
+            .filter(|line| line.to_lowercase().contains("sensor edge"))
+            .filter_map(|line| line.rsplit(':').next()?.trim().parse::<f32>().ok())
+            .reduce(f32::max)
+    }
+
+    /// Parse `nvidia-smi --query-gpu=memory.free,memory.used,name` and sum
+    /// (free, used) VRAM in GB across all cards matching `name` (mirrors how
+    /// total VRAM is computed).
+    fn parse_nvidia_smi_usage(text: &str, name: &str) -> Option<(f64, f64)> {
+        let mut free_mb = 0.0;
+        let mut used_mb = 0.0;
+        let mut matched = false;
+        for line in text.lines() {
+            let parts: Vec<&str> = line.trim().splitn(3, ',').collect();
+            if parts.len() < 3 || parts[2].trim() != name {
+                continue;
+            }
+            if let (Ok(free), Ok(used)) = (
+                parts[0].trim().parse::<f64>(),
+                parts[1].trim().parse::<f64>(),
+            ) {
+                free_mb += free;
+                used_mb += used;
+                matched = true;
+            }
+        }
+        if matched {
+            Some((free_mb / 1024.0, used_mb / 1024.0))
+        } else {
+            None
+        }
+    }
+
+    /// Parse `rocm-smi --showmeminfo vram` and return (free, used) VRAM in GB
+    /// summed across all cards, free being total - used.
+    fn parse_rocm_smi_usage(text: &str) -> Option<(f64, f64)> {
+        let mut total_bytes: u64 = 0;
+        let mut used_bytes: u64 = 0;
+        for line in text.lines() {
+            let lower = line.to_lowercase();
+            if !lower.contains("total") {
+                continue;
+            }
+            let Some(val) = line
+                .split_whitespace()
+                .filter_map(|w| w.parse::<u64>().ok())
+                .next_back()
+            else {
+                continue;
+            };
+            if lower.contains("used") {
+                used_bytes += val;
+            } else {
+                total_bytes += val;
+            }
+        }
+        if total_bytes == 0 {
+            return None;
+        }
+        let gb = |bytes: u64| bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+        Some((gb(total_bytes.saturating_sub(used_bytes)), gb(used_bytes)))
+    }
+
+    /// Detect NVIDIA GPUs via Linux sysfs when nvidia-smi is unavailable.
+    /// This is common in containerized environments (e.g. Toolbx) and
+    /// Nouveau-based systems.
+    fn detect_nvidia_gpu_sysfs_info() -> Option<GpuInfo> {
+        if !cfg!(target_os = "linux") {
+            return None;
+        }
 
         let entries = std::fs::read_dir("/sys/class/drm").ok()?;
         let mut gpu_count: u32 = 0;
@@ -499,6 +1875,7 @@ impl SystemSpecs {
             backend,
             count: gpu_count,
             unified_memory: false,
+            class: GpuClass::Discrete,
         })
     }
 
@@ -506,11 +1883,12 @@ impl SystemSpecs {
     /// Parses per-card VRAM and GPU name from rocm-smi output.
     fn detect_amd_gpu_rocm_info() -> Option<GpuInfo> {
         // Try rocm-smi --showmeminfo vram for VRAM
-        let vram_output = std::process::Command::new("rocm-smi")
-            .arg("--showmeminfo")
-            .arg("vram")
-            .output()
-            .ok()?;
+        let vram_output = run_with_timeout(
+            std::process::Command::new("rocm-smi")
+                .arg("--showmeminfo")
+                .arg("vram"),
+            DETECTION_COMMAND_TIMEOUT,
+        )?;
 
         if !vram_output.status.success() {
             return None;
@@ -546,32 +1924,32 @@ impl SystemSpecs {
         }
 
         // Try to get GPU name from rocm-smi --showproductname
-        let gpu_name = std::process::Command::new("rocm-smi")
-            .arg("--showproductname")
-            .output()
-            .ok()
-            .and_then(|o| {
-                if o.status.success() {
-                    String::from_utf8(o.stdout).ok()
-                } else {
-                    None
-                }
-            })
-            .and_then(|text| {
-                // Look for "Card Series" or "Card Model" lines
-                for line in text.lines() {
-                    let lower = line.to_lowercase();
-                    if (lower.contains("card series") || lower.contains("card model"))
-                        && let Some(val) = line.split(':').nth(1)
-                    {
-                        let name = val.trim().to_string();
-                        if !name.is_empty() {
-                            return Some(name);
-                        }
+        let gpu_name = run_with_timeout(
+            std::process::Command::new("rocm-smi").arg("--showproductname"),
+            DETECTION_COMMAND_TIMEOUT,
+        )
+        .and_then(|o| {
+            if o.status.success() {
+                String::from_utf8(o.stdout).ok()
+            } else {
+                None
+            }
+        })
+        .and_then(|text| {
+            // Look for "Card Series" or "Card Model" lines
+            for line in text.lines() {
+                let lower = line.to_lowercase();
+                if (lower.contains("card series") || lower.contains("card model"))
+                    && let Some(val) = line.split(':').nth(1)
+                {
+                    let name = val.trim().to_string();
+                    if !name.is_empty() {
+                        return Some(name);
                     }
                 }
-                None
-            });
+            }
+            None
+        });
 
         let name = gpu_name.unwrap_or_else(|| "AMD GPU".to_string());
         let max_per_gpu_bytes = per_gpu_vram_bytes.into_iter().max().unwrap_or(0);
@@ -588,6 +1966,82 @@ impl SystemSpecs {
             backend: GpuBackend::Rocm,
             count: gpu_count,
             unified_memory: false,
+            class: GpuClass::Discrete,
+        })
+    }
+
+    /// Driver version for GPUs whose vendor tool doesn't report one: the
+    /// macOS version (`sw_vers -productVersion`) for Metal, which ships its
+    /// GPU drivers with the OS, or WMI's `DriverVersion` for the primary
+    /// GPU on Windows. NVIDIA and ROCm versions come from their own probes.
+    fn detect_os_driver_version(gpus: &[GpuInfo], cpu_name: &str) -> Option<String> {
+        let primary = gpus.first()?;
+        let run = |cmd: &mut std::process::Command| {
+            run_with_timeout(cmd, DETECTION_COMMAND_TIMEOUT)
+                .filter(|o| o.status.success())
+                .and_then(|o| String::from_utf8(o.stdout).ok())
+        };
+        match Self::primary_backend(gpus, cpu_name) {
+            GpuBackend::Cuda | GpuBackend::Rocm => None,
+            GpuBackend::Metal => run(std::process::Command::new("sw_vers").arg("-productVersion"))
+                .map(|text| text.trim().to_string())
+                .filter(|v| !v.is_empty()),
+            _ if cfg!(target_os = "windows") => run(std::process::Command::new("powershell")
+                .arg("-NoProfile")
+                .arg("-Command")
+                .arg("Get-CimInstance Win32_VideoController | ForEach-Object { $_.Name + '|' + $_.DriverVersion }"))
+            .and_then(|text| Self::parse_windows_driver_version(&text, &primary.name)),
+            _ => None,
+        }
+    }
+
+    /// The `DriverVersion` for `gpu_name` from PowerShell output
+    /// (Name|DriverVersion per line).
+    fn parse_windows_driver_version(text: &str, gpu_name: &str) -> Option<String> {
+        text.lines().find_map(|line| {
+            let (name, version) = line.trim().split_once('|')?;
+            let version = version.trim();
+            (name.trim().eq_ignore_ascii_case(gpu_name) && !version.is_empty())
+                .then(|| version.to_string())
+        })
+    }
+
+    /// Read the installed ROCm version from `$ROCM_PATH/.info/version`
+    /// (default `/opt/rocm`), falling back to `rocm-smi --version`.
+    fn detect_rocm_version() -> Option<String> {
+        let rocm_path = std::env::var("ROCM_PATH").unwrap_or_else(|_| "/opt/rocm".to_string());
+        let from_file = std::fs::read_to_string(format!("{}/.info/version", rocm_path))
+            .ok()
+            .and_then(|text| Self::parse_rocm_version_file(&text));
+        from_file.or_else(|| {
+            run_with_timeout(
+                std::process::Command::new("rocm-smi").arg("--version"),
+                DETECTION_COMMAND_TIMEOUT,
+            )
+            .filter(|o| o.status.success())
+            .and_then(|o| String::from_utf8(o.stdout).ok())
+            .and_then(|text| Self::parse_rocm_smi_version(&text))
+        })
+    }
+
+    /// `.info/version` holds e.g. "6.1.2-119"; drop the build suffix.
+    fn parse_rocm_version_file(text: &str) -> Option<String> {
+        let version = text.trim().split('-').next()?.trim();
+        (!version.is_empty() && version.chars().next()?.is_ascii_digit())
+            .then(|| version.to_string())
+    }
+
+    /// Newer `rocm-smi --version` builds print a "ROCm version: 6.2.0" line next
+    /// to the tool's own "ROCM-SMI version" line; only the former is useful.
+    fn parse_rocm_smi_version(text: &str) -> Option<String> {
+        text.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let key = key.trim().to_lowercase();
+            if key == "rocm version" {
+                Self::parse_rocm_version_file(value)
+            } else {
+                None
+            }
         })
     }
 
@@ -617,22 +2071,24 @@ impl SystemSpecs {
                 continue;
             }
 
-            // Found an AMD GPU. Try to read VRAM.
-            let mut vram_gb: Option<f64> = None;
-            let vram_path = device_path.join("mem_info_vram_total");
-            if let Ok(vram_str) = std::fs::read_to_string(&vram_path)
-                && let Ok(vram_bytes) = vram_str.trim().parse::<u64>()
-                && vram_bytes > 0
-            {
-                vram_gb = Some(vram_bytes as f64 / (1024.0 * 1024.0 * 1024.0));
-            }
+            // Found an AMD GPU. Try to read VRAM, plus the GTT window an APU
+            // uses to borrow system RAM.
+            let read_bytes = |file: &str| {
+                std::fs::read_to_string(device_path.join(file))
+                    .ok()
+                    .and_then(|s| s.trim().parse::<u64>().ok())
+            };
+            let (mut vram_gb, unified_memory) = Self::classify_amd_sysfs_memory(
+                read_bytes("mem_info_vram_total"),
+                read_bytes("mem_info_gtt_total"),
+            );
 
             // Try to get GPU name from lspci
             let gpu_name = Self::get_amd_gpu_name_lspci();
             let name = gpu_name.unwrap_or_else(|| "AMD GPU".to_string());
 
             // If we still don't have VRAM, try to estimate from name
-            if vram_gb.is_none() {
+            if vram_gb.is_none() && !unified_memory {
                 let estimated = estimate_vram_from_name(&name);
                 if estimated > 0.0 {
                     vram_gb = Some(estimated);
@@ -645,12 +2101,38 @@ impl SystemSpecs {
                 vram_gb,
                 backend: GpuBackend::Vulkan,
                 count: 1,
-                unified_memory: false,
+                unified_memory,
+                class: if unified_memory {
+                    GpuClass::Integrated
+                } else {
+                    GpuClass::Discrete
+                },
             });
         }
         None
     }
 
+    /// Turn amdgpu's `mem_info_vram_total` / `mem_info_gtt_total` (bytes) into
+    /// `(vram_gb, unified_memory)`. On APUs the "VRAM" is only the BIOS
+    /// carveout (often 512 MB); the iGPU mostly uses system RAM through GTT,
+    /// so a tiny carveout marks the device as unified and the usable pool is
+    /// carveout + GTT. Without a GTT figure the pool is left `None` and
+    /// filled in from system RAM later.
+    fn classify_amd_sysfs_memory(
+        vram_bytes: Option<u64>,
+        gtt_bytes: Option<u64>,
+    ) -> (Option<f64>, bool) {
+        const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+        let vram_gb = vram_bytes.filter(|&b| b > 0).map(|b| b as f64 / GB);
+        match vram_gb {
+            Some(vram) if vram < AMD_APU_CARVEOUT_MAX_GB => {
+                let gtt_gb = gtt_bytes.filter(|&b| b > 0).map(|b| b as f64 / GB);
+                (gtt_gb.map(|gtt| vram + gtt), true)
+            }
+            _ => (vram_gb, false),
+        }
+    }
+
     /// Extract AMD GPU name from lspci output.
     fn get_amd_gpu_name_lspci() -> Option<String> {
         let text = Self::lspci_output()?;
@@ -702,23 +2184,23 @@ impl SystemSpecs {
 
     /// Read lspci output, with host fallback for containerized environments.
     fn lspci_output() -> Option<String> {
-        let local = std::process::Command::new("lspci")
-            .arg("-nn")
-            .output()
-            .ok()
-            .filter(|o| o.status.success())
-            .and_then(|o| String::from_utf8(o.stdout).ok());
+        let local = run_with_timeout(
+            std::process::Command::new("lspci").arg("-nn"),
+            DETECTION_COMMAND_TIMEOUT,
+        )
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok());
 
         if local.is_some() {
             return local;
         }
 
-        std::process::Command::new("flatpak-spawn")
-            .args(["--host", "lspci", "-nn"])
-            .output()
-            .ok()
-            .filter(|o| o.status.success())
-            .and_then(|o| String::from_utf8(o.stdout).ok())
+        run_with_timeout(
+            std::process::Command::new("flatpak-spawn").args(["--host", "lspci", "-nn"]),
+            DETECTION_COMMAND_TIMEOUT,
+        )
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
     }
 
     /// Extract a likely model name from an lspci line.
@@ -765,11 +2247,13 @@ impl SystemSpecs {
         }
 
         // Use PowerShell to query WMI — more reliable than wmic (deprecated)
-        if let Ok(output) = std::process::Command::new("powershell")
-            .arg("-NoProfile")
-            .arg("-Command")
-            .arg("Get-CimInstance Win32_VideoController | Select-Object Name,AdapterRAM | ForEach-Object { $_.Name + '|' + $_.AdapterRAM }")
-            .output()
+        if let Some(output) = run_with_timeout(
+            std::process::Command::new("powershell")
+                .arg("-NoProfile")
+                .arg("-Command")
+                .arg("Get-CimInstance Win32_VideoController | Select-Object Name,AdapterRAM | ForEach-Object { $_.Name + '|' + $_.AdapterRAM }"),
+            DETECTION_COMMAND_TIMEOUT,
+        )
             && output.status.success()
                 && let Ok(text) = String::from_utf8(output.stdout) {
                     let gpus = Self::parse_windows_gpu_list(&text);
@@ -784,15 +2268,16 @@ impl SystemSpecs {
 
     /// Fallback Windows GPU detection via wmic (works on older systems).
     fn detect_gpu_windows_wmic_list() -> Vec<GpuInfo> {
-        let output = match std::process::Command::new("wmic")
-            .arg("path")
-            .arg("win32_VideoController")
-            .arg("get")
-            .arg("Name,AdapterRAM")
-            .arg("/format:csv")
-            .output()
-        {
-            Ok(o) if o.status.success() => o,
+        let output = match run_with_timeout(
+            std::process::Command::new("wmic")
+                .arg("path")
+                .arg("win32_VideoController")
+                .arg("get")
+                .arg("Name,AdapterRAM")
+                .arg("/format:csv"),
+            DETECTION_COMMAND_TIMEOUT,
+        ) {
+            Some(o) if o.status.success() => o,
             _ => return Vec::new(),
         };
 
@@ -821,12 +2306,14 @@ impl SystemSpecs {
                 }
                 let backend = Self::infer_gpu_backend(&name);
                 let vram_gb = Self::resolve_wmi_vram(raw_vram, &name);
+                let class = GpuClass::from_name(&name);
                 gpus.push(GpuInfo {
                     name,
                     vram_gb,
                     backend,
                     count: 1,
                     unified_memory: false,
+                    class,
                 });
             }
         }
@@ -859,12 +2346,14 @@ impl SystemSpecs {
 
             let backend = Self::infer_gpu_backend(&name);
             let vram_gb = Self::resolve_wmi_vram(raw_vram, &name);
+            let class = GpuClass::from_name(&name);
             gpus.push(GpuInfo {
                 name,
                 vram_gb,
                 backend,
                 count: 1,
                 unified_memory: false,
+                class,
             });
         }
         gpus
@@ -885,6 +2374,12 @@ impl SystemSpecs {
 
     /// Infer the most likely inference backend from a GPU name string.
     fn infer_gpu_backend(name: &str) -> GpuBackend {
+        Self::infer_gpu_backend_with(name, has_opencl_runtime(), cfg!(target_os = "windows"))
+    }
+
+    /// Backend heuristics by name. GPUs that match no vendor heuristic fall
+    /// back to OpenCL when an OpenCL runtime is installed, otherwise Vulkan.
+    fn infer_gpu_backend_with(name: &str, opencl_available: bool, windows: bool) -> GpuBackend {
         let lower = name.to_lowercase();
         if lower.contains("nvidia")
             || lower.contains("geforce")
@@ -894,21 +2389,35 @@ impl SystemSpecs {
         {
             GpuBackend::Cuda
         } else if lower.contains("amd") || lower.contains("radeon") || lower.contains("ati") {
-            // On Windows, Vulkan is the primary inference path for AMD GPUs
-            // (ROCm support on Windows is limited)
-            GpuBackend::Vulkan
+            // On Windows, DirectML is the most common inference path for AMD
+            // GPUs (ROCm support on Windows is limited); elsewhere without
+            // ROCm, Vulkan is.
+            if windows {
+                GpuBackend::DirectML
+            } else {
+                GpuBackend::Vulkan
+            }
         } else if lower.contains("intel") || lower.contains("arc") {
-            GpuBackend::Sycl
+            // oneAPI is rarely installed on Windows desktops; DirectML ships with the OS
+            if windows {
+                GpuBackend::DirectML
+            } else {
+                GpuBackend::Sycl
+            }
+        } else if opencl_available {
+            GpuBackend::OpenCL
         } else {
             GpuBackend::Vulkan
         }
     }
 
-    /// Detect Intel Arc / Intel integrated GPU via sysfs or lspci.
+    /// Detect Intel Arc / Intel integrated GPU via sysfs or lspci, returning
+    /// its name and VRAM (0 for a GPU sharing system RAM).
     /// Intel Arc GPUs (A370M, A770, etc.) have dedicated VRAM exposed via
-    /// the DRM subsystem at /sys/class/drm/card*/device/. Even integrated
+    /// the DRM subsystem at /sys/class/drm/card*/device/. Cards in
+    /// [`INTEL_ARC_DEVICES`] are named from their PCI device ID. Even integrated
     /// Intel GPUs that share system RAM are useful for inference via SYCL/oneAPI.
-    fn detect_intel_gpu() -> Option<f64> {
+    fn detect_intel_gpu() -> Option<(String, f64)> {
         // Try sysfs first: works for Intel discrete (Arc) GPUs on Linux.
         // Walk /sys/class/drm/card*/device/ looking for Intel vendor ID (0x8086).
         if let Ok(entries) = std::fs::read_dir("/sys/class/drm") {
@@ -927,12 +2436,24 @@ impl SystemSpecs {
                 // Look for total VRAM via DRM memory info
                 // Intel discrete GPUs expose this under drm/card*/device/mem_info_vram_total
                 let vram_path = card_path.join("device/mem_info_vram_total");
-                if let Ok(vram_str) = std::fs::read_to_string(&vram_path)
-                    && let Ok(vram_bytes) = vram_str.trim().parse::<u64>()
-                    && vram_bytes > 0
-                {
-                    let vram_gb = vram_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
-                    return Some(vram_gb);
+                let sysfs_vram = std::fs::read_to_string(&vram_path)
+                    .ok()
+                    .and_then(|s| s.trim().parse::<u64>().ok())
+                    .filter(|&bytes| bytes > 0)
+                    .map(|bytes| bytes as f64 / (1024.0 * 1024.0 * 1024.0));
+
+                // A known Arc device ID gives the model, and its VRAM when
+                // the driver doesn't expose it
+                let known = std::fs::read_to_string(device_path.join("device"))
+                    .ok()
+                    .and_then(|id| u16::from_str_radix(id.trim().trim_start_matches("0x"), 16).ok())
+                    .and_then(intel_arc_device);
+                match (known, sysfs_vram) {
+                    (Some((name, table_vram)), vram) => {
+                        return Some((name.to_string(), vram.unwrap_or(table_vram)));
+                    }
+                    (None, Some(vram)) => return Some(("Intel Arc".to_string(), vram)),
+                    (None, None) => {}
                 }
 
                 // For integrated Intel GPUs, check if it's an Arc-class device
@@ -941,10 +2462,10 @@ impl SystemSpecs {
                     for line in text.lines() {
                         let lower = line.to_lowercase();
                         if lower.contains("intel") && lower.contains("arc") {
-                            // Intel Arc integrated (e.g. Arc Graphics in Meteor Lake)
-                            // These share system RAM; report None for VRAM and
-                            // let the caller know a GPU exists.
-                            return Some(0.0);
+                            // No sysfs VRAM: either an Arc iGPU (e.g. Meteor Lake)
+                            // sharing system RAM, or a discrete card whose driver
+                            // doesn't expose mem_info_vram_total. Ask oneAPI.
+                            return Some(Self::intel_arc_from_lspci(line));
                         }
                     }
                 }
@@ -957,7 +2478,7 @@ impl SystemSpecs {
             for line in text.lines() {
                 let lower = line.to_lowercase();
                 if lower.contains("intel") && lower.contains("arc") {
-                    return Some(0.0);
+                    return Some(Self::intel_arc_from_lspci(line));
                 }
             }
         }
@@ -965,47 +2486,314 @@ impl SystemSpecs {
         None
     }
 
-    /// Detect Apple Silicon GPU via system_profiler.
-    /// Returns total system RAM as VRAM since memory is unified.
-    /// The unified memory pool capacity is the total RAM -- it doesn't
-    /// fluctuate with current usage the way available RAM does.
-    fn detect_apple_gpu(total_ram_gb: f64) -> Option<f64> {
-        // system_profiler only exists on macOS
-        let output = std::process::Command::new("system_profiler")
-            .arg("SPDisplaysDataType")
-            .output()
-            .ok()?;
+    /// Name and VRAM of the Arc GPU on an `lspci -nn` line: from
+    /// [`INTEL_ARC_DEVICES`] when its `[8086:xxxx]` ID is known, else
+    /// "Intel Arc" with whatever oneAPI reports.
+    fn intel_arc_from_lspci(line: &str) -> (String, f64) {
+        match parse_lspci_intel_device_id(line).and_then(intel_arc_device) {
+            Some((name, vram)) => (name.to_string(), vram),
+            None => (
+                "Intel Arc".to_string(),
+                Self::detect_intel_vram_oneapi().unwrap_or(0.0),
+            ),
+        }
+    }
 
-        if !output.status.success() {
+    /// Dedicated VRAM of an Intel Arc card as reported by oneAPI tooling:
+    /// `sycl-ls --verbose` first, then `xpu-smi discovery` (Level Zero).
+    fn detect_intel_vram_oneapi() -> Option<f64> {
+        [("sycl-ls", &["--verbose"]), ("xpu-smi", &["discovery"])]
+            .iter()
+            .find_map(|(cmd, args)| {
+                let output = run_with_timeout(
+                    std::process::Command::new(cmd).args(*args),
+                    DETECTION_COMMAND_TIMEOUT,
+                )
+                .filter(|o| o.status.success())?;
+                let text = String::from_utf8(output.stdout).ok()?;
+                Self::parse_intel_device_memory(&text)
+            })
+    }
+
+    /// Find the memory size of the first Arc device in `sycl-ls --verbose` or
+    /// `xpu-smi discovery` output. Both list a device name line before its
+    /// memory line; CPU devices (whose "memory" is system RAM) are skipped.
+    ///
+    /// ```text
+    ///         Name        : Intel(R) Arc(TM) A770 Graphics
+    ///         Global Memory Size : 17079205888
+    /// | 0 | Device Name: Intel(R) Arc(TM) A770 Graphics |
+    /// |   | Memory Physical Size: 16288.00 MiB          |
+    /// ```
+    fn parse_intel_device_memory(text: &str) -> Option<f64> {
+        let mut current_is_arc = false;
+        for line in text.lines() {
+            // xpu-smi wraps each field in a table cell
+            let Some((key, value)) = line
+                .split('|')
+                .find(|cell| cell.contains(':'))
+                .and_then(|cell| cell.split_once(':'))
+            else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+            if key == "name" || key == "device name" {
+                current_is_arc = value.to_lowercase().contains("arc");
+            } else if current_is_arc
+                && key.contains("mem")
+                && (key.contains("size") || key.contains("global"))
+            {
+                // Plain integers are byte counts; anything else has a unit
+                let gb = match value.parse::<u64>() {
+                    Ok(bytes) => bytes as f64 / (1024.0 * 1024.0 * 1024.0),
+                    Err(_) => parse_memory_size(value)?,
+                };
+                if gb > 0.0 {
+                    return Some(gb);
+                }
+            }
+        }
+        None
+    }
+
+    /// Detect an NVIDIA Jetson / Tegra board from the device tree or the L4T
+    /// release file. Returns the board name.
+    fn detect_jetson() -> Option<String> {
+        if !cfg!(target_os = "linux") {
             return None;
         }
+        if let Ok(model) = std::fs::read_to_string("/proc/device-tree/model")
+            && let Some(name) = Self::parse_jetson_model(&model)
+        {
+            return Some(name);
+        }
+        std::path::Path::new("/etc/nv_tegra_release")
+            .exists()
+            .then(|| "NVIDIA Jetson".to_string())
+    }
 
-        let text = String::from_utf8(output.stdout).ok()?;
+    /// The device-tree model is NUL-terminated, e.g.
+    /// "NVIDIA Jetson AGX Orin Developer Kit\0".
+    fn parse_jetson_model(text: &str) -> Option<String> {
+        let model = text.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+        let lower = model.to_lowercase();
+        (lower.contains("jetson") || lower.contains("tegra")).then(|| model.to_string())
+    }
+
+    /// Detect a GPU that is only usable through OpenCL.
+    /// Requires an installed OpenCL ICD (`/etc/OpenCL/vendors/*.icd` on Linux,
+    /// `OpenCL.dll` on Windows). If `clinfo` is available it is used to name the
+    /// device and to skip CPU-only OpenCL runtimes such as PoCL.
+    fn detect_opencl_gpu() -> Option<GpuInfo> {
+        let has_icd = if cfg!(target_os = "windows") {
+            let root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+            std::path::Path::new(&root)
+                .join("System32")
+                .join("OpenCL.dll")
+                .exists()
+        } else {
+            std::fs::read_dir("/etc/OpenCL/vendors")
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .any(|e| e.path().extension().is_some_and(|ext| ext == "icd"))
+                })
+                .unwrap_or(false)
+        };
+        if !has_icd {
+            return None;
+        }
+
+        let clinfo = run_with_timeout(
+            std::process::Command::new("clinfo").arg("--list"),
+            DETECTION_COMMAND_TIMEOUT,
+        )
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok());
+
+        let name = match clinfo {
+            // clinfo ran: trust it, including when it lists no GPU device
+            Some(text) => Self::parse_clinfo_gpu_name(&text)?,
+            None => "OpenCL GPU".to_string(),
+        };
+
+        let est = estimate_vram_from_name(&name);
+        let class = GpuClass::from_name(&name);
+        Some(GpuInfo {
+            name,
+            vram_gb: if est > 0.0 { Some(est) } else { None },
+            backend: GpuBackend::OpenCL,
+            count: 1,
+            unified_memory: false,
+            class,
+        })
+    }
+
+    /// Parse `clinfo --list` and return the first non-CPU device name.
+    /// Device lines look like " `-- Device #0: Mali-G610 r0p0".
+    fn parse_clinfo_gpu_name(text: &str) -> Option<String> {
+        text.lines()
+            .filter(|line| line.contains("Device #"))
+            .filter_map(|line| line.split_once(": ").map(|(_, name)| name.trim()))
+            .find(|name| {
+                let lower = name.to_lowercase();
+                !name.is_empty() && !lower.contains("cpu") && !lower.contains("pthread")
+            })
+            .map(|name| name.to_string())
+    }
+
+    /// Detect Apple Silicon GPU via system_profiler, returning whether one
+    /// was found and its GPU core count. The caller reports total system RAM
+    /// as VRAM since memory is unified: the pool capacity is the total RAM --
+    /// it doesn't fluctuate with current usage the way available RAM does.
+    fn detect_apple_gpu() -> (bool, Option<u32>) {
+        // system_profiler only exists on macOS
+        run_with_timeout(
+            std::process::Command::new("system_profiler").arg("SPDisplaysDataType"),
+            DETECTION_COMMAND_TIMEOUT,
+        )
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|text| Self::parse_apple_gpu(&text))
+        .unwrap_or_default()
+    }
 
-        // Apple Silicon GPUs show "Apple M1/M2/M3/M4" in the chipset line.
-        // Discrete AMD/Intel GPUs on older Macs won't match.
-        let is_apple_gpu = text.lines().any(|line| {
+    /// Parse `system_profiler SPDisplaysDataType`:
+    ///
+    /// ```text
+    ///       Apple M3 Max:
+    ///         Chipset Model: Apple M3 Max
+    ///         Type: GPU
+    ///         Total Number of Cores: 40
+    /// ```
+    ///
+    /// Apple Silicon GPUs show "Apple M1/M2/M3/M4" in the chipset line;
+    /// discrete AMD/Intel GPUs on older Macs won't match.
+    fn parse_apple_gpu(text: &str) -> (bool, Option<u32>) {
+        let found = text.lines().any(|line| {
             let lower = line.to_lowercase();
             lower.contains("apple m") || lower.contains("apple gpu")
         });
+        if !found {
+            return (false, None);
+        }
+        let cores = text
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("Total Number of Cores:"))
+            .find_map(|v| v.trim().parse().ok());
+        (true, cores)
+    }
 
-        if is_apple_gpu {
-            // Unified memory: GPU and CPU share the same RAM pool.
-            // Report total RAM as the VRAM capacity.
-            Some(total_ram_gb)
-        } else {
-            None
+    /// Detect a GPU in a Thunderbolt enclosure on an Intel Mac. The
+    /// enclosure shows up in `system_profiler SPThunderboltDataType`; the
+    /// card inside, with its VRAM, in `SPDisplaysDataType`.
+    fn detect_egpu_macos() -> Option<GpuInfo> {
+        let profile = |data_type: &str| {
+            run_with_timeout(
+                std::process::Command::new("system_profiler").arg(data_type),
+                DETECTION_COMMAND_TIMEOUT,
+            )
+            .filter(|o| o.status.success())
+            .and_then(|o| String::from_utf8(o.stdout).ok())
+        };
+        let thunderbolt = profile("SPThunderboltDataType")?;
+        Self::parse_thunderbolt_egpu(&thunderbolt)?;
+        Self::parse_egpu_display(&profile("SPDisplaysDataType")?)
+    }
+
+    /// Name of the first eGPU enclosure in `system_profiler
+    /// SPThunderboltDataType`, e.g. "Core X" from
+    /// "          Device Name: Core X".
+    fn parse_thunderbolt_egpu(text: &str) -> Option<String> {
+        text.lines()
+            .filter_map(|line| line.trim().strip_prefix("Device Name:"))
+            .map(str::trim)
+            .find(|name| {
+                let lower = name.to_lowercase();
+                EGPU_ENCLOSURES.iter().any(|e| lower.contains(e))
+                    || lower.split_whitespace().any(|word| word == "node")
+            })
+            .map(str::to_string)
+    }
+
+    /// The eGPU's entry in `system_profiler SPDisplaysDataType`:
+    ///
+    /// ```text
+    ///       Radeon RX 6800 XT:
+    ///         Chipset Model: Radeon RX 6800 XT
+    ///         Type: External GPU
+    ///         Bus: PCIe
+    ///         VRAM (Total): 16 GB
+    /// ```
+    ///
+    /// An entry marked external wins; otherwise the non-Intel, non-Apple
+    /// GPU with the most VRAM, since the built-in discrete Radeons of
+    /// 15"/16" MacBook Pros are smaller than any card worth an enclosure.
+    fn parse_egpu_display(text: &str) -> Option<GpuInfo> {
+        let mut candidates: Vec<(String, f64, bool)> = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if let Some(model) = line.strip_prefix("Chipset Model:") {
+                candidates.push((model.trim().to_string(), 0.0, false));
+            } else if let Some((_, vram, external)) = candidates.last_mut() {
+                if let Some(size) = line
+                    .strip_prefix("VRAM (Total):")
+                    .or_else(|| line.strip_prefix("VRAM (Dynamic, Max):"))
+                {
+                    *vram = parse_memory_size(size).unwrap_or(0.0);
+                } else if line.to_lowercase().contains("external") {
+                    *external = true;
+                }
+            }
         }
+        candidates.retain(|(name, vram, _)| {
+            let lower = name.to_lowercase();
+            *vram > 0.0 && !lower.contains("intel") && !lower.contains("apple")
+        });
+        let (name, vram, _) = candidates.into_iter().max_by(|a, b| {
+            a.2.cmp(&b.2)
+                .then(a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        })?;
+        Some(GpuInfo {
+            name,
+            vram_gb: Some(vram),
+            backend: GpuBackend::Metal,
+            count: 1,
+            unified_memory: false,
+            class: GpuClass::Discrete,
+        })
+    }
+
+    /// Determine the Apple Silicon tier from `system_profiler SPHardwareDataType`,
+    /// falling back to the CPU brand string reported by sysinfo.
+    fn detect_apple_chip_variant(cpu_name: &str) -> Option<AppleChipVariant> {
+        let from_profiler = run_with_timeout(
+            std::process::Command::new("system_profiler").arg("SPHardwareDataType"),
+            DETECTION_COMMAND_TIMEOUT,
+        )
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .and_then(|text| Self::parse_apple_chip_variant(&text));
+
+        from_profiler.or_else(|| AppleChipVariant::from_chip_name(cpu_name))
+    }
+
+    /// Parse the "Chip:" line from `system_profiler SPHardwareDataType`, e.g.
+    /// "      Chip: Apple M3 Max".
+    fn parse_apple_chip_variant(text: &str) -> Option<AppleChipVariant> {
+        text.lines()
+            .filter_map(|line| line.trim().strip_prefix("Chip:"))
+            .find_map(AppleChipVariant::from_chip_name)
     }
 
     /// Detect Ascend NPUs via npu-smi. Returns a vector of NPU info.
     fn detect_ascend_npus() -> Vec<GpuInfo> {
         // 1. Get the list of IDs
-        let list_output = match std::process::Command::new("npu-smi")
-            .args(["info", "-l"])
-            .output()
-        {
-            Ok(o) if o.status.success() => o,
+        let list_output = match run_with_timeout(
+            std::process::Command::new("npu-smi").args(["info", "-l"]),
+            DETECTION_COMMAND_TIMEOUT,
+        ) {
+            Some(o) if o.status.success() => o,
             _ => return Vec::new(),
         };
 
@@ -1015,7 +2803,7 @@ impl SystemSpecs {
         let ids: Vec<String> = list_stdout
             .lines()
             .filter(|line| line.contains("NPU ID"))
-            .filter_map(|line| line.split(':').last())
+            .filter_map(|line| line.split(':').next_back())
             .map(|s| s.trim().to_string())
             .collect();
 
@@ -1028,19 +2816,20 @@ impl SystemSpecs {
 
         // 2. Loop through NPUs
         for id in &ids {
-            let mem_output = std::process::Command::new("npu-smi")
-                .args(["info", "-t", "memory", "-i", id])
-                .output();
+            let mem_output = run_with_timeout(
+                std::process::Command::new("npu-smi").args(["info", "-t", "memory", "-i", id]),
+                DETECTION_COMMAND_TIMEOUT,
+            );
 
-            if let Ok(o) = mem_output {
+            if let Some(o) = mem_output {
                 let s = String::from_utf8_lossy(&o.stdout);
 
                 // Parse HBM Capacity (e.g., from "HBM Capacity(MB) : 65536")
                 let mem = s
                     .lines()
                     .find(|l| l.contains("HBM Capacity"))
-                    .and_then(|l| l.split(':').last())
-                    .and_then(|v| v.trim().split_whitespace().next())
+                    .and_then(|l| l.split(':').next_back())
+                    .and_then(|v| v.split_whitespace().next())
                     .and_then(|num| num.parse::<u64>().ok())
                     .unwrap_or(0);
 
@@ -1050,17 +2839,29 @@ impl SystemSpecs {
                     backend: GpuBackend::Ascend,
                     count: 1,
                     unified_memory: false,
+                    class: GpuClass::Discrete,
                 };
                 npu_infos.push(npu_info);
             }
         }
 
-        return npu_infos;
+        npu_infos
     }
 
     /// Fallback for available RAM when sysinfo returns 0.
     /// Tries total - used first, then macOS vm_stat parsing.
-    fn available_ram_fallback(sys: &System, total_bytes: u64, total_gb: f64) -> f64 {
+    fn available_ram_fallback(
+        sys: &System,
+        total_bytes: u64,
+        total_gb: f64,
+        cgroup_available: Option<u64>,
+    ) -> f64 {
+        // Inside a memory-limited container, the cgroup's own usage counter is
+        // the only number that matters
+        if let Some(headroom) = cgroup_available {
+            return headroom as f64 / (1024.0 * 1024.0 * 1024.0);
+        }
+
         // Try total - used from sysinfo (may also use vm_statistics64 internally)
         let used = sys.used_memory();
         if used > 0 && used < total_bytes {
@@ -1079,7 +2880,10 @@ impl SystemSpecs {
     /// Parse macOS `vm_stat` to compute available memory.
     /// Available ≈ (free + inactive + purgeable) * page_size
     fn available_ram_from_vm_stat() -> Option<f64> {
-        let output = std::process::Command::new("vm_stat").output().ok()?;
+        let output = run_with_timeout(
+            &mut std::process::Command::new("vm_stat"),
+            DETECTION_COMMAND_TIMEOUT,
+        )?;
         if !output.status.success() {
             return None;
         }
@@ -1153,10 +2957,14 @@ impl SystemSpecs {
                 backend,
                 count: 1,
                 unified_memory: false,
+                class: GpuClass::Discrete,
             });
             self.has_gpu = true;
             self.gpu_vram_gb = Some(vram_gb);
             self.total_gpu_vram_gb = Some(vram_gb);
+            self.gpu_vram_gb_per_device = vec![vram_gb];
+            self.gpu_vram_available_gb = None;
+            self.gpu_vram_used_gb = None;
             self.gpu_name = Some("User-specified GPU".to_string());
             self.gpu_count = 1;
             self.backend = backend;
@@ -1167,17 +2975,205 @@ impl SystemSpecs {
             // Update total VRAM: per-card VRAM * count.
             let count = self.gpus[0].count;
             self.total_gpu_vram_gb = Some(vram_gb * count as f64);
+            self.gpu_vram_gb_per_device = Self::per_device_vram(&self.gpus);
+            // Live usage no longer relates to the overridden capacity.
+            self.gpu_vram_available_gb = None;
+            self.gpu_vram_used_gb = None;
             self.has_gpu = true;
         }
         self
     }
 
-    pub fn display(&self) {
-        println!("\n=== System Specifications ===");
+    /// Pretend the machine has `ram_gb` of RAM, all of it free. On unified
+    /// memory systems the GPU pool follows the new RAM size.
+    pub fn with_ram_override(mut self, ram_gb: f64) -> Self {
+        self.total_ram_gb = ram_gb;
+        self.available_ram_gb = ram_gb;
+        if self.unified_memory {
+            self = self.with_gpu_memory_override(ram_gb);
+        }
+        self
+    }
+
+    /// Force the inference backend, e.g. to model a CUDA box from a Mac.
+    pub fn with_backend_override(mut self, backend: GpuBackend) -> Self {
+        self.backend = backend;
+        if let Some(primary) = self.gpus.first_mut() {
+            primary.backend = backend;
+        }
+        self
+    }
+
+    /// Rename the primary GPU. Bandwidth is re-estimated from the new name so
+    /// speed estimates match the card being modelled.
+    pub fn with_gpu_name_override(mut self, name: &str) -> Self {
+        self.gpu_name = Some(name.to_string());
+        if let Some(primary) = self.gpus.first_mut() {
+            primary.name = name.to_string();
+        }
+        self.memory_bandwidth_gbps = memory_bandwidth_gbps(name);
+        self
+    }
+
+    /// Patch detected fields from `LLMFIT_OVERRIDE_*` environment variables,
+    /// so CI and containers can model a fixed machine:
+    ///
+    /// | Variable | Field |
+    /// |---|---|
+    /// | `LLMFIT_OVERRIDE_VRAM_GB` | primary GPU VRAM, e.g. `24` or `24G` (adds a GPU if none) |
+    /// | `LLMFIT_OVERRIDE_GPU_COUNT` | number of cards of the primary GPU |
+    /// | `LLMFIT_OVERRIDE_BACKEND` | inference backend, e.g. `cuda` |
+    /// | `LLMFIT_OVERRIDE_GPU_NAME` | primary GPU name (bandwidth follows it) |
+    /// | `LLMFIT_OVERRIDE_UNIFIED_MEMORY` | `true`/`false` |
+    /// | `LLMFIT_OVERRIDE_BANDWIDTH_GBPS` | memory bandwidth in GB/s |
+    /// | `LLMFIT_OVERRIDE_RAM_GB` | total RAM, all of it free |
+    /// | `LLMFIT_OVERRIDE_AVAILABLE_RAM_GB` | free RAM |
+    /// | `LLMFIT_OVERRIDE_CPU_CORES` | CPU core count |
+    /// | `LLMFIT_OVERRIDE_CPU_NAME` | CPU name |
+    /// | `LLMFIT_OVERRIDE_OVERHEAD_GB` | runtime overhead in GB |
+    ///
+    /// The older `LLMFIT_VRAM_GB`, `LLMFIT_RAM_GB`, `LLMFIT_BACKEND`,
+    /// `LLMFIT_GPU_NAME` and `LLMFIT_OVERHEAD_GB` still work; the
+    /// `LLMFIT_OVERRIDE_` form wins when both are set. Returns a message for
+    /// each value that couldn't be parsed and was skipped.
+    pub fn apply_env_overrides(&mut self) -> Vec<String> {
+        self.apply_overrides_from(|name| std::env::var(name).ok())
+    }
+
+    /// [`SystemSpecs::apply_env_overrides`] with variables looked up by
+    /// `var`. VRAM goes first so a synthetic GPU picks up the count,
+    /// backend and name.
+    fn apply_overrides_from(&mut self, var: impl Fn(&str) -> Option<String>) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let read = |key: &str| {
+            let current = format!("LLMFIT_OVERRIDE_{}", key);
+            let legacy = format!("LLMFIT_{}", key);
+            let legacy_ok = LEGACY_OVERRIDES.contains(&key);
+            var(&current)
+                .map(|v| (current, v))
+                .or_else(|| {
+                    legacy_ok
+                        .then(|| var(&legacy).map(|v| (legacy, v)))
+                        .flatten()
+                })
+                .filter(|(_, v)| !v.trim().is_empty())
+        };
+        let mut invalid = |name: &str, raw: &str, expected: &str| {
+            warnings.push(format!(
+                "could not parse {}='{}'. Expected {}",
+                name, raw, expected
+            ))
+        };
+
+        if let Some((name, raw)) = read("VRAM_GB") {
+            match parse_memory_size(&raw) {
+                Some(gb) => *self = self.clone().with_gpu_memory_override(gb),
+                None => invalid(&name, &raw, "a size such as 24, 24G or 24000M"),
+            }
+        }
+        if let Some((name, raw)) = read("GPU_COUNT") {
+            match raw.trim().parse::<u32>() {
+                Ok(count) if count > 0 && self.has_gpu => self.set_gpu_count(count),
+                Ok(count) if count > 0 => {} // nothing to multiply without a GPU
+                _ => invalid(&name, &raw, "a positive number of cards"),
+            }
+        }
+        if let Some((name, raw)) = read("BACKEND") {
+            match GpuBackend::from_label(&raw) {
+                Some(backend) => *self = self.clone().with_backend_override(backend),
+                None => invalid(
+                    &name,
+                    &raw,
+                    &format!("one of: {}", GpuBackend::ALL.map(|b| b.label()).join(", ")),
+                ),
+            }
+        }
+        if let Some((_, name)) = read("GPU_NAME") {
+            *self = self.clone().with_gpu_name_override(name.trim());
+        }
+        if let Some((name, raw)) = read("UNIFIED_MEMORY") {
+            match raw.trim().to_lowercase().as_str() {
+                "1" | "true" | "yes" => self.set_unified_memory(true),
+                "0" | "false" | "no" => self.set_unified_memory(false),
+                _ => invalid(&name, &raw, "true or false"),
+            }
+        }
+        if let Some((name, raw)) = read("BANDWIDTH_GBPS") {
+            match raw.trim().parse::<f64>() {
+                Ok(gbps) if gbps > 0.0 => self.memory_bandwidth_gbps = Some(gbps),
+                _ => invalid(&name, &raw, "a bandwidth in GB/s, e.g. 1008"),
+            }
+        }
+        if let Some((name, raw)) = read("RAM_GB") {
+            match parse_memory_size(&raw) {
+                Some(gb) => *self = self.clone().with_ram_override(gb),
+                None => invalid(&name, &raw, "a size such as 64, 64G or 65536M"),
+            }
+        }
+        if let Some((name, raw)) = read("AVAILABLE_RAM_GB") {
+            match parse_memory_size(&raw) {
+                Some(gb) => self.available_ram_gb = gb.min(self.total_ram_gb),
+                None => invalid(&name, &raw, "a size such as 48, 48G or 49152M"),
+            }
+        }
+        if let Some((name, raw)) = read("CPU_CORES") {
+            match raw.trim().parse::<usize>() {
+                Ok(cores) if cores > 0 => self.total_cpu_cores = cores,
+                _ => invalid(&name, &raw, "a positive number of cores"),
+            }
+        }
+        if let Some((_, cpu)) = read("CPU_NAME") {
+            self.cpu_name = cpu.trim().to_string();
+        }
+        if let Some((name, raw)) = read("OVERHEAD_GB") {
+            match raw.trim().parse::<f64>() {
+                Ok(gb) if gb >= 0.0 => *self = self.clone().with_overhead_override(gb),
+                _ => invalid(&name, &raw, "a number of GB, e.g. 1.5"),
+            }
+        }
+        warnings
+    }
+
+    /// Treat the primary GPU as `count` identical cards.
+    fn set_gpu_count(&mut self, count: u32) {
+        if let Some(primary) = self.gpus.first_mut() {
+            primary.count = count;
+        }
+        self.gpu_count = count;
+        self.total_gpu_vram_gb = self.gpu_vram_gb.map(|vram| vram * count as f64);
+        self.gpu_vram_gb_per_device = Self::per_device_vram(&self.gpus);
+    }
+
+    fn set_unified_memory(&mut self, unified: bool) {
+        self.unified_memory = unified;
+        if let Some(primary) = self.gpus.first_mut() {
+            primary.unified_memory = unified;
+            primary.class = if unified {
+                GpuClass::Unified
+            } else {
+                GpuClass::Discrete
+            };
+        }
+    }
+
+    pub fn display(&self) {
+        println!("\n=== System Specifications ===");
         println!("CPU: {} ({} cores)", self.cpu_name, self.total_cpu_cores);
         println!("Total RAM: {:.2} GB", self.total_ram_gb);
         println!("Available RAM: {:.2} GB", self.available_ram_gb);
+        if self.swap_total_gb > 0.0 {
+            println!(
+                "Swap: {:.2} GB free of {:.2} GB",
+                self.swap_available_gb, self.swap_total_gb
+            );
+        }
         println!("Backend: {}", self.backend.label());
+        if let Some(variant) = self.apple_chip_variant {
+            println!("Apple chip tier: {}", variant.label());
+        }
+        if let Some(variant) = self.jetson_variant {
+            println!("Jetson module: {}", variant.label());
+        }
 
         if self.gpus.is_empty() {
             println!("GPU: Not detected");
@@ -1190,9 +3186,14 @@ impl SystemSpecs {
                 };
                 if gpu.unified_memory {
                     println!(
-                        "{}{} (unified memory, {:.2} GB shared, {})",
+                        "{}{} ({} memory, {:.2} GB shared, {})",
                         prefix,
                         gpu.name,
+                        if gpu.class == GpuClass::Integrated {
+                            "integrated"
+                        } else {
+                            "unified"
+                        },
                         gpu.vram_gb.unwrap_or(0.0),
                         gpu.backend.label(),
                     );
@@ -1236,380 +3237,3138 @@ impl SystemSpecs {
                 }
             }
         }
+        if let Some(rocm) = &self.rocm_version {
+            println!("ROCm version: {}", rocm);
+        }
+        // The ROCm version is printed above
+        if let Some(driver) = &self.gpu_driver_version {
+            match self.backend {
+                GpuBackend::Cuda => println!("NVIDIA driver: {}", driver),
+                GpuBackend::Rocm => {}
+                GpuBackend::Metal => println!("GPU driver: macOS {}", driver),
+                _ => println!("GPU driver: {}", driver),
+            }
+        }
+        if let Some(temp) = self.gpu_temperature_c {
+            println!(
+                "GPU temperature: {:.0}°C{}",
+                temp,
+                if self.thermal_throttle_risk() {
+                    " (may throttle under sustained load)"
+                } else {
+                    ""
+                }
+            );
+        }
+        match self.container_env {
+            ContainerEnv::None => {}
+            ContainerEnv::Wsl => {
+                println!("Environment: WSL (RAM is the VM's share of the host)")
+            }
+            env => println!(
+                "Environment: {} container (RAM reflects the container's memory limit)",
+                env.label()
+            ),
+        }
+        if let Some(free) = self.gpu_vram_available_gb {
+            println!("Free VRAM: {:.2} GB", free);
+            if self.vram_contended()
+                && let Some(used) = self.gpu_vram_used_gb
+            {
+                println!("Warning: {:.1} GB of VRAM is held by other processes", used);
+            }
+        }
+        if let Some(cores) = self.gpu_core_count {
+            println!("GPU cores: {}", cores);
+        }
+        if self.numa_nodes > 1 {
+            println!("NUMA nodes: {}", self.numa_nodes);
+        }
+        if let (Some(generation), Some(width), Some(bw)) =
+            (self.pcie_gen, self.pcie_width, self.pcie_bandwidth_gbps())
+        {
+            println!("PCIe link: Gen{} x{} (~{:.0} GB/s)", generation, width, bw);
+        }
+        if let Some(bw) = self.memory_bandwidth_gbps {
+            println!("Memory bandwidth: ~{:.0} GB/s", bw);
+        }
+        if let Some((major, minor)) = self.cuda_compute_capability {
+            println!(
+                "CUDA compute capability: {}.{} (sm_{}{}{})",
+                major,
+                minor,
+                major,
+                minor,
+                if self.supports_flash_attention_2() {
+                    ", FlashAttention-2"
+                } else {
+                    ""
+                }
+            );
+        }
+        if let Some(disk) = self.disk_available_gb {
+            println!("Free disk (model storage): {:.1} GB", disk);
+        }
+        if let Some(read) = self.nvme_read_bandwidth_gb_s {
+            println!("NVMe read speed (model storage): {:.1} GB/s", read);
+        }
+        if let Some(limit) = self.mlock_limit_gb {
+            println!("mlock limit (RLIMIT_MEMLOCK): {:.2} GB", limit);
+        }
+        if self.gpu_link_topology != GpuLinkTopology::SingleGpu {
+            println!("GPU interconnect: {}", self.gpu_link_topology.label());
+        }
+        if self.has_gpu {
+            println!(
+                "Runtime overhead: {:.2} GB reserved from GPU memory",
+                self.runtime_overhead_gb()
+            );
+        }
+        if !self.cpu_features.is_empty() {
+            println!("CPU features: {}", self.cpu_features.join(", "));
+        }
+        if let Some(best) = self.cpu_inference_path() {
+            println!("CPU inference path: {}", best);
+        }
         println!();
     }
 }
 
-/// Parse a human-readable memory size string into gigabytes.
-/// Accepts formats: "32G", "32g", "32GB", "32gb", "32000M", "32000m", "32000MB", etc.
-/// Returns `None` if the input is malformed.
-pub fn parse_memory_size(s: &str) -> Option<f64> {
-    let s = s.trim();
-    if s.is_empty() {
-        return None;
-    }
+/// CPU and RAM facts read from sysinfo in one refresh.
+struct HostInfo {
+    total_ram_gb: f64,
+    available_ram_gb: f64,
+    total_cpu_cores: usize,
+    cpu_name: String,
+    cpu_features: Vec<String>,
+    swap_total_gb: f64,
+    swap_available_gb: f64,
+    disk_available_gb: Option<f64>,
+    nvme_read_bandwidth_gb_s: Option<f64>,
+    mlock_limit_gb: Option<f64>,
+    numa_nodes: usize,
+}
 
-    // Split into numeric part and suffix
-    let num_end = s
-        .find(|c: char| !c.is_ascii_digit() && c != '.')
-        .unwrap_or(s.len());
-    let (num_str, suffix) = s.split_at(num_end);
-    let value: f64 = num_str.parse().ok()?;
-    if value < 0.0 {
-        return None;
+impl HostInfo {
+    fn read() -> Self {
+        let mut sys = System::new_all();
+        sys.refresh_all();
+        let (total_ram_gb, available_ram_gb) = SystemSpecs::read_ram(&sys);
+        let (swap_total_gb, swap_available_gb) = SystemSpecs::read_swap(&sys);
+        let model_disk = model_storage_disk();
+        HostInfo {
+            total_ram_gb,
+            available_ram_gb,
+            total_cpu_cores: sys.cpus().len(),
+            cpu_name: SystemSpecs::read_cpu_name(&sys),
+            cpu_features: detect_cpu_features(),
+            swap_total_gb,
+            swap_available_gb,
+            disk_available_gb: model_disk
+                .as_ref()
+                .map(|(free, _)| *free as f64 / (1024.0 * 1024.0 * 1024.0)),
+            nvme_read_bandwidth_gb_s: detect_nvme_read_bandwidth(
+                model_disk.as_ref().map(|(_, device)| device.as_str()),
+            ),
+            mlock_limit_gb: detect_mlock_limit_gb(),
+            numa_nodes: detect_numa_nodes(),
+        }
     }
+}
 
-    let suffix = suffix.trim().to_lowercase();
-    match suffix.as_str() {
-        "g" | "gb" | "gib" | "" => Some(value),     // already in GB
-        "m" | "mb" | "mib" => Some(value / 1024.0), // MB → GB
-        "t" | "tb" | "tib" => Some(value * 1024.0), // TB → GB
-        _ => None,
-    }
+/// Intel Arc discrete cards by PCI device ID: name and VRAM in GB. Where a
+/// model ships with more than one memory size, the larger is listed.
+const INTEL_ARC_DEVICES: &[(u16, &str, f64)] = &[
+    (0x56a0, "Intel Arc A770", 16.0),
+    (0x56a1, "Intel Arc A750", 8.0),
+    (0x56a2, "Intel Arc A580", 8.0),
+    (0x56a5, "Intel Arc A380", 6.0),
+    (0x56a6, "Intel Arc A310", 4.0),
+    (0x56b0, "Intel Arc Pro A30M", 4.0),
+    (0x56b1, "Intel Arc Pro A40/A50", 6.0),
+    (0x56b3, "Intel Arc Pro A60", 12.0),
+    (0xe20b, "Intel Arc B580", 12.0),
+    (0xe20c, "Intel Arc B570", 10.0),
+    (0xe211, "Intel Arc Pro B60", 24.0),
+    (0xe212, "Intel Arc Pro B50", 16.0),
+    // BMG-G31; ID not yet confirmed on retail boards
+    (0xe221, "Intel Arc B770", 16.0),
+];
+
+fn intel_arc_device(id: u16) -> Option<(&'static str, f64)> {
+    INTEL_ARC_DEVICES
+        .iter()
+        .find(|(device, _, _)| *device == id)
+        .map(|&(_, name, vram)| (name, vram))
 }
 
-pub fn is_running_in_wsl() -> bool {
-    static IS_WSL: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
-    *IS_WSL.get_or_init(detect_running_in_wsl)
+/// Device ID from the `[8086:xxxx]` tag of an `lspci -nn` line.
+fn parse_lspci_intel_device_id(line: &str) -> Option<u16> {
+    let start = line.find("[8086:")? + "[8086:".len();
+    u16::from_str_radix(line.get(start..start + 4)?, 16).ok()
 }
 
-fn detect_running_in_wsl() -> bool {
-    if !cfg!(target_os = "linux") {
-        return false;
+/// Raw results of the per-vendor GPU probes. The probes don't depend on each
+/// other, so they can run in any order (or concurrently); all cross-vendor
+/// de-duplication happens in [`SystemSpecs::merge_gpu_probes`].
+#[derive(Default)]
+struct GpuProbes {
+    nvidia: Vec<GpuInfo>,
+    /// `memory.free, memory.used, name` lines from the nvidia-smi call behind
+    /// `nvidia`.
+    nvidia_usage: Option<String>,
+    amd: Option<GpuInfo>,
+    windows: Vec<GpuInfo>,
+    /// Name and VRAM of an Intel GPU.
+    intel: Option<(String, f64)>,
+    apple: bool,
+    apple_gpu_cores: Option<u32>,
+    /// Thunderbolt eGPU on macOS.
+    egpu: Option<GpuInfo>,
+    ascend: Vec<GpuInfo>,
+    /// Board name of an NVIDIA Jetson / Tegra SoC.
+    jetson: Option<String>,
+    opencl: Option<GpuInfo>,
+}
+
+impl GpuProbes {
+    /// Run every probe in sequence.
+    fn run() -> Self {
+        let (apple, apple_gpu_cores) = SystemSpecs::detect_apple_gpu();
+        let (nvidia, nvidia_usage) = Self::probe_nvidia();
+        let mut probes = GpuProbes {
+            nvidia,
+            nvidia_usage,
+            amd: Self::probe_amd(),
+            windows: SystemSpecs::detect_gpu_windows_info(),
+            intel: SystemSpecs::detect_intel_gpu(),
+            apple,
+            apple_gpu_cores,
+            egpu: SystemSpecs::detect_egpu_macos(),
+            ascend: SystemSpecs::detect_ascend_npus(),
+            jetson: SystemSpecs::detect_jetson(),
+            opencl: None,
+        };
+        // clinfo is a last resort, so skip it when anything else turned up
+        if probes.nvidia.is_empty()
+            && probes.amd.is_none()
+            && probes.windows.is_empty()
+            && probes.intel.is_none()
+            && !probes.apple
+            && probes.egpu.is_none()
+            && probes.ascend.is_empty()
+            && probes.jetson.is_none()
+        {
+            probes.opencl = SystemSpecs::detect_opencl_gpu();
+        }
+        probes
     }
 
-    if std::env::var_os("WSL_INTEROP").is_some() || std::env::var_os("WSL_DISTRO_NAME").is_some() {
-        return true;
+    /// nvidia-smi, falling back to sysfs for Linux/toolbox setups. Also
+    /// returns nvidia-smi's VRAM usage lines, if it ran.
+    fn probe_nvidia() -> (Vec<GpuInfo>, Option<String>) {
+        let (nvidia, usage) = SystemSpecs::detect_nvidia_gpus();
+        if nvidia.is_empty() {
+            let sysfs = SystemSpecs::detect_nvidia_gpu_sysfs_info()
+                .into_iter()
+                .collect();
+            (sysfs, None)
+        } else {
+            (nvidia, usage)
+        }
     }
 
-    ["/proc/sys/kernel/osrelease", "/proc/version"]
-        .iter()
-        .any(|path| {
-            std::fs::read_to_string(path)
-                .map(|text| text.to_ascii_lowercase().contains("microsoft"))
-                .unwrap_or(false)
-        })
+    /// rocm-smi, falling back to sysfs.
+    fn probe_amd() -> Option<GpuInfo> {
+        SystemSpecs::detect_amd_gpu_rocm_info().or_else(SystemSpecs::detect_amd_gpu_sysfs_info)
+    }
 }
 
-/// Check if the CPU name indicates an AMD APU with unified memory architecture.
-/// These APUs share the full system RAM between CPU and GPU (like Apple Silicon).
-/// Currently covers:
-///  - Ryzen AI MAX / MAX+ (Strix Halo): up to 128 GB unified.
-///  - Ryzen AI 9 / 7 / 5 (Strix Point, Krackan Point): configurable shared
-///    memory, users can allocate most of system RAM to GPU via BIOS.
-/// All Ryzen AI APUs have integrated Radeon GPUs that share system memory.
-fn is_amd_unified_memory_apu(cpu_name: &str) -> bool {
-    let lower = cpu_name.to_lowercase();
-    // All "Ryzen AI" branded APUs use unified/shared memory.
-    // Examples:
-    //   "AMD Ryzen AI MAX+ 395 w/ Radeon 8060S"
-    //   "AMD Ryzen AI 9 HX 370 w/ Radeon 890M"
-    //   "AMD Ryzen AI 7 350"
-    if lower.contains("ryzen ai") {
-        return true;
-    }
-    false
+/// Probes that depend on which GPUs were found.
+struct GpuFollowUps {
+    /// (free, used) VRAM of the primary GPU model.
+    vram_usage: Option<(f64, f64)>,
+    apple_chip_variant: Option<AppleChipVariant>,
+    link_topology: GpuLinkTopology,
+    cuda_info: (Option<String>, Option<String>),
+    rocm_version: Option<String>,
+    /// (generation, lane width) of the primary GPU's PCIe link.
+    pcie_link: (Option<u8>, Option<u8>),
+    /// Carried over from the first-round Apple probe.
+    gpu_core_count: Option<u32>,
+    /// Carried over from the first-round Jetson probe.
+    jetson_variant: Option<JetsonVariant>,
+    /// macOS version or WMI driver version, for GPUs without a vendor tool
+    /// that reports one.
+    os_driver_version: Option<String>,
+    gpu_temperature_c: Option<f32>,
 }
 
-/// Read total system RAM from /proc/meminfo (Linux only).
-/// Used as the unified memory pool on NVIDIA Tegra / Grace Blackwell platforms
-/// where nvidia-smi cannot report dedicated VRAM.
-fn read_proc_meminfo_total_gb() -> Option<f64> {
-    let text = std::fs::read_to_string("/proc/meminfo").ok()?;
-    for line in text.lines() {
-        if let Some(rest) = line.strip_prefix("MemTotal:") {
-            let kb: u64 = rest.split_whitespace().next()?.parse().ok()?;
-            return Some(kb as f64 / (1024.0 * 1024.0));
+impl GpuFollowUps {
+    fn run(gpus: &[GpuInfo], cpu_name: &str, nvidia_usage: Option<&str>) -> Self {
+        let apple_chip_variant =
+            if SystemSpecs::primary_backend(gpus, cpu_name) == GpuBackend::Metal {
+                SystemSpecs::detect_apple_chip_variant(cpu_name)
+            } else {
+                None
+            };
+        let cuda_info = if gpus.iter().any(|g| g.backend == GpuBackend::Cuda) {
+            SystemSpecs::detect_nvidia_cuda_info()
+        } else {
+            (None, None)
+        };
+        GpuFollowUps {
+            vram_usage: gpus
+                .first()
+                .and_then(|g| SystemSpecs::initial_vram_usage(g, nvidia_usage)),
+            apple_chip_variant,
+            link_topology: SystemSpecs::detect_link_topology(gpus),
+            cuda_info,
+            rocm_version: if gpus.iter().any(|g| g.backend == GpuBackend::Rocm) {
+                SystemSpecs::detect_rocm_version()
+            } else {
+                None
+            },
+            pcie_link: SystemSpecs::detect_pcie_link(gpus),
+            gpu_core_count: None,
+            jetson_variant: None,
+            os_driver_version: SystemSpecs::detect_os_driver_version(gpus, cpu_name),
+            gpu_temperature_c: gpus.first().and_then(SystemSpecs::detect_gpu_temperature),
         }
     }
-    None
 }
 
-/// Fallback VRAM estimation from GPU model name.
-/// Used when nvidia-smi or other tools report 0 VRAM.
-fn estimate_vram_from_name(name: &str) -> f64 {
-    let lower = name.to_lowercase();
-    // NVIDIA RTX 50 series
-    if lower.contains("5090") {
-        return 32.0;
+/// Builder for what-if hardware scenarios ("would a 24 GB GPU be enough?").
+/// Every field can be overridden; anything left unset falls back to the
+/// detected value (or to the base passed to [`SystemSpecsBuilder::from_specs`]).
+///
+/// Overriding `gpu_vram_gb` or `gpu_count` also updates `gpus`,
+/// `gpu_vram_gb_per_device`, `total_gpu_vram_gb` (per-card VRAM times
+/// `gpu_count`) and `has_gpu`, unless those are set explicitly.
+#[derive(Debug, Clone, Default)]
+pub struct SystemSpecsBuilder {
+    base: Option<SystemSpecs>,
+    total_ram_gb: Option<f64>,
+    available_ram_gb: Option<f64>,
+    total_cpu_cores: Option<usize>,
+    cpu_name: Option<String>,
+    has_gpu: Option<bool>,
+    gpu_vram_gb: Option<Option<f64>>,
+    total_gpu_vram_gb: Option<Option<f64>>,
+    gpu_vram_gb_per_device: Option<Vec<f64>>,
+    gpu_vram_available_gb: Option<Option<f64>>,
+    gpu_vram_used_gb: Option<Option<f64>>,
+    gpu_name: Option<Option<String>>,
+    gpu_count: Option<u32>,
+    unified_memory: Option<bool>,
+    backend: Option<GpuBackend>,
+    gpus: Option<Vec<GpuInfo>>,
+    apple_chip_variant: Option<Option<AppleChipVariant>>,
+    memory_bandwidth_gbps: Option<Option<f64>>,
+    cpu_features: Option<Vec<String>>,
+    gpu_link_topology: Option<GpuLinkTopology>,
+    cuda_compute_capability: Option<Option<(u8, u8)>>,
+    rocm_version: Option<Option<String>>,
+    gpu_driver_version: Option<Option<String>>,
+    gpu_temperature_c: Option<Option<f32>>,
+    container_env: Option<ContainerEnv>,
+    swap_total_gb: Option<f64>,
+    swap_available_gb: Option<f64>,
+    disk_available_gb: Option<Option<f64>>,
+    gpu_core_count: Option<Option<u32>>,
+    pcie_gen: Option<Option<u8>>,
+    pcie_width: Option<Option<u8>>,
+    numa_nodes: Option<usize>,
+    overhead_gb: Option<Option<f64>>,
+    nvme_read_bandwidth_gb_s: Option<Option<f64>>,
+    mlock_limit_gb: Option<Option<f64>>,
+    jetson_variant: Option<Option<JetsonVariant>>,
+}
+
+impl SystemSpecsBuilder {
+    /// Start from real hardware detection (performed in `build()`).
+    pub fn new() -> Self {
+        Self::default()
     }
-    if lower.contains("5080") {
-        return 16.0;
+
+    /// Start from an existing profile instead of detecting hardware.
+    pub fn from_specs(specs: SystemSpecs) -> Self {
+        SystemSpecsBuilder {
+            base: Some(specs),
+            ..Self::default()
+        }
     }
-    if lower.contains("5070 ti") {
-        return 16.0;
+
+    pub fn total_ram_gb(mut self, value: f64) -> Self {
+        self.total_ram_gb = Some(value);
+        self
     }
-    if lower.contains("5070") {
-        return 12.0;
+
+    pub fn available_ram_gb(mut self, value: f64) -> Self {
+        self.available_ram_gb = Some(value);
+        self
     }
-    if lower.contains("5060 ti") {
-        return 16.0;
+
+    pub fn total_cpu_cores(mut self, value: usize) -> Self {
+        self.total_cpu_cores = Some(value);
+        self
     }
-    if lower.contains("5060") {
-        return 8.0;
+
+    pub fn cpu_name(mut self, value: String) -> Self {
+        self.cpu_name = Some(value);
+        self
     }
-    // NVIDIA RTX 40 series
-    if lower.contains("4090") {
-        return 24.0;
+
+    pub fn has_gpu(mut self, value: bool) -> Self {
+        self.has_gpu = Some(value);
+        self
     }
-    if lower.contains("4080") {
-        return 16.0;
+
+    pub fn gpu_vram_gb(mut self, value: Option<f64>) -> Self {
+        self.gpu_vram_gb = Some(value);
+        self
     }
-    if lower.contains("4070 ti") {
-        return 12.0;
+
+    pub fn total_gpu_vram_gb(mut self, value: Option<f64>) -> Self {
+        self.total_gpu_vram_gb = Some(value);
+        self
     }
-    if lower.contains("4070") {
-        return 12.0;
+
+    pub fn gpu_vram_gb_per_device(mut self, value: Vec<f64>) -> Self {
+        self.gpu_vram_gb_per_device = Some(value);
+        self
     }
-    if lower.contains("4060 ti") {
-        return 16.0;
+
+    pub fn gpu_vram_available_gb(mut self, value: Option<f64>) -> Self {
+        self.gpu_vram_available_gb = Some(value);
+        self
     }
-    if lower.contains("4060") {
-        return 8.0;
+
+    pub fn gpu_vram_used_gb(mut self, value: Option<f64>) -> Self {
+        self.gpu_vram_used_gb = Some(value);
+        self
     }
-    // NVIDIA RTX 30 series
-    if lower.contains("3090") {
-        return 24.0;
+
+    pub fn gpu_name(mut self, value: Option<String>) -> Self {
+        self.gpu_name = Some(value);
+        self
     }
-    if lower.contains("3080 ti") {
-        return 12.0;
+
+    pub fn gpu_count(mut self, value: u32) -> Self {
+        self.gpu_count = Some(value);
+        self
     }
-    if lower.contains("3080") {
-        return 10.0;
+
+    pub fn unified_memory(mut self, value: bool) -> Self {
+        self.unified_memory = Some(value);
+        self
     }
-    if lower.contains("3070") {
-        return 8.0;
+
+    pub fn backend(mut self, value: GpuBackend) -> Self {
+        self.backend = Some(value);
+        self
     }
-    if lower.contains("3060 ti") {
-        return 8.0;
+
+    pub fn gpus(mut self, value: Vec<GpuInfo>) -> Self {
+        self.gpus = Some(value);
+        self
     }
-    if lower.contains("3060") {
-        return 12.0;
+
+    pub fn apple_chip_variant(mut self, value: Option<AppleChipVariant>) -> Self {
+        self.apple_chip_variant = Some(value);
+        self
     }
-    // Data center
-    if lower.contains("h100") {
-        return 80.0;
+
+    pub fn memory_bandwidth_gbps(mut self, value: Option<f64>) -> Self {
+        self.memory_bandwidth_gbps = Some(value);
+        self
     }
-    if lower.contains("a100") {
+
+    pub fn cpu_features(mut self, value: Vec<String>) -> Self {
+        self.cpu_features = Some(value);
+        self
+    }
+
+    pub fn gpu_link_topology(mut self, value: GpuLinkTopology) -> Self {
+        self.gpu_link_topology = Some(value);
+        self
+    }
+
+    pub fn cuda_compute_capability(mut self, value: Option<(u8, u8)>) -> Self {
+        self.cuda_compute_capability = Some(value);
+        self
+    }
+
+    pub fn rocm_version(mut self, value: Option<String>) -> Self {
+        self.rocm_version = Some(value);
+        self
+    }
+
+    pub fn gpu_driver_version(mut self, value: Option<String>) -> Self {
+        self.gpu_driver_version = Some(value);
+        self
+    }
+
+    pub fn gpu_temperature_c(mut self, value: Option<f32>) -> Self {
+        self.gpu_temperature_c = Some(value);
+        self
+    }
+
+    pub fn container_env(mut self, value: ContainerEnv) -> Self {
+        self.container_env = Some(value);
+        self
+    }
+
+    pub fn swap_total_gb(mut self, value: f64) -> Self {
+        self.swap_total_gb = Some(value);
+        self
+    }
+
+    pub fn swap_available_gb(mut self, value: f64) -> Self {
+        self.swap_available_gb = Some(value);
+        self
+    }
+
+    pub fn disk_available_gb(mut self, value: Option<f64>) -> Self {
+        self.disk_available_gb = Some(value);
+        self
+    }
+
+    pub fn nvme_read_bandwidth_gb_s(mut self, value: Option<f64>) -> Self {
+        self.nvme_read_bandwidth_gb_s = Some(value);
+        self
+    }
+
+    pub fn mlock_limit_gb(mut self, value: Option<f64>) -> Self {
+        self.mlock_limit_gb = Some(value);
+        self
+    }
+
+    pub fn jetson_variant(mut self, value: Option<JetsonVariant>) -> Self {
+        self.jetson_variant = Some(value);
+        self
+    }
+
+    pub fn gpu_core_count(mut self, value: Option<u32>) -> Self {
+        self.gpu_core_count = Some(value);
+        self
+    }
+
+    pub fn pcie_gen(mut self, value: Option<u8>) -> Self {
+        self.pcie_gen = Some(value);
+        self
+    }
+
+    pub fn pcie_width(mut self, value: Option<u8>) -> Self {
+        self.pcie_width = Some(value);
+        self
+    }
+
+    pub fn numa_nodes(mut self, value: usize) -> Self {
+        self.numa_nodes = Some(value);
+        self
+    }
+
+    pub fn overhead_gb(mut self, value: Option<f64>) -> Self {
+        self.overhead_gb = Some(value);
+        self
+    }
+
+    pub fn build(self) -> SystemSpecs {
+        let mut specs = self.base.unwrap_or_else(SystemSpecs::detect);
+
+        if let Some(v) = self.total_ram_gb {
+            specs.total_ram_gb = v;
+        }
+        if let Some(v) = self.available_ram_gb {
+            specs.available_ram_gb = v;
+        }
+        if let Some(v) = self.total_cpu_cores {
+            specs.total_cpu_cores = v;
+        }
+        if let Some(v) = self.cpu_name {
+            specs.cpu_name = v;
+        }
+        // GPU shape first, through the same setters as the CLI overrides, so
+        // `gpus` and the per-device list follow the VRAM and count
+        if let Some(v) = self.gpus {
+            specs.gpus = v;
+        }
+        match self.gpu_vram_gb {
+            Some(Some(vram)) => specs = specs.with_gpu_memory_override(vram),
+            Some(None) => specs.gpu_vram_gb = None,
+            None => {}
+        }
+        if let Some(v) = self.gpu_count {
+            specs.set_gpu_count(v);
+        }
+        if let Some(v) = self.unified_memory {
+            specs.set_unified_memory(v);
+        }
+        if let Some(v) = self.gpu_vram_gb_per_device {
+            specs.gpu_vram_gb_per_device = v;
+        }
+        if let Some(v) = self.gpu_vram_available_gb {
+            specs.gpu_vram_available_gb = v;
+        }
+        if let Some(v) = self.gpu_vram_used_gb {
+            specs.gpu_vram_used_gb = v;
+        }
+        if let Some(v) = self.gpu_name {
+            specs.gpu_name = v;
+        }
+        if let Some(v) = self.backend {
+            specs.backend = v;
+        }
+        if let Some(v) = self.apple_chip_variant {
+            specs.apple_chip_variant = v;
+        }
+        if let Some(v) = self.memory_bandwidth_gbps {
+            specs.memory_bandwidth_gbps = v;
+        }
+        if let Some(v) = self.cpu_features {
+            specs.cpu_features = v;
+        }
+        if let Some(v) = self.gpu_link_topology {
+            specs.gpu_link_topology = v;
+        }
+        if let Some(v) = self.cuda_compute_capability {
+            specs.cuda_compute_capability = v;
+        }
+        if let Some(v) = self.rocm_version {
+            specs.rocm_version = v;
+        }
+        if let Some(v) = self.gpu_driver_version {
+            specs.gpu_driver_version = v;
+        }
+        if let Some(v) = self.gpu_temperature_c {
+            specs.gpu_temperature_c = v;
+        }
+        if let Some(v) = self.container_env {
+            specs.container_env = v;
+        }
+        if let Some(v) = self.swap_total_gb {
+            specs.swap_total_gb = v;
+        }
+        if let Some(v) = self.swap_available_gb {
+            specs.swap_available_gb = v;
+        }
+        if let Some(v) = self.disk_available_gb {
+            specs.disk_available_gb = v;
+        }
+        if let Some(v) = self.nvme_read_bandwidth_gb_s {
+            specs.nvme_read_bandwidth_gb_s = v;
+        }
+        if let Some(v) = self.mlock_limit_gb {
+            specs.mlock_limit_gb = v;
+        }
+        if let Some(v) = self.jetson_variant {
+            specs.jetson_variant = v;
+        }
+        if let Some(v) = self.gpu_core_count {
+            specs.gpu_core_count = v;
+        }
+        if let Some(v) = self.pcie_gen {
+            specs.pcie_gen = v;
+        }
+        if let Some(v) = self.pcie_width {
+            specs.pcie_width = v;
+        }
+        if let Some(v) = self.numa_nodes {
+            specs.numa_nodes = v;
+        }
+        if let Some(v) = self.overhead_gb {
+            specs.overhead_gb = v;
+        }
+
+        if let Some(v) = self.has_gpu {
+            specs.has_gpu = v;
+        }
+        if let Some(v) = self.total_gpu_vram_gb {
+            specs.total_gpu_vram_gb = v;
+        }
+        specs
+    }
+}
+
+/// Run a detection subprocess, killing it if it hasn't exited within
+/// `timeout`. Returns `None` on spawn failure or timeout; a completed run is
+/// returned whatever its exit status.
+pub fn run_with_timeout(
+    cmd: &mut std::process::Command,
+    timeout: std::time::Duration,
+) -> Option<std::process::Output> {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    // Drain both pipes on their own threads so a chatty child can't block
+    // on a full pipe while we wait for it.
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    };
+    let stdout = drain(child.stdout.take().map(|p| Box::new(p) as _));
+    let stderr = drain(child.stderr.take().map(|p| Box::new(p) as _));
+
+    let deadline = std::time::Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if std::time::Instant::now() < deadline => {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    };
+
+    Some(std::process::Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Parse a human-readable memory size string into gigabytes.
+/// Accepts formats: "32G", "32g", "32GB", "32gb", "32000M", "32000m", "32000MB", etc.
+/// Returns `None` if the input is malformed.
+pub fn parse_memory_size(s: &str) -> Option<f64> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    // Split into numeric part and suffix
+    let num_end = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (num_str, suffix) = s.split_at(num_end);
+    let value: f64 = num_str.parse().ok()?;
+    if value < 0.0 {
+        return None;
+    }
+
+    let suffix = suffix.trim().to_lowercase();
+    match suffix.as_str() {
+        "g" | "gb" | "gib" | "" => Some(value),     // already in GB
+        "m" | "mb" | "mib" => Some(value / 1024.0), // MB → GB
+        "t" | "tb" | "tib" => Some(value * 1024.0), // TB → GB
+        _ => None,
+    }
+}
+
+/// Command to run nvidia-smi with. Under WSL the Linux binary is often
+/// missing while the Windows `nvidia-smi.exe` works through interop, so
+/// that one is used instead. Resolved once per process.
+fn nvidia_smi() -> &'static str {
+    static NVIDIA_SMI: std::sync::OnceLock<&'static str> = std::sync::OnceLock::new();
+    NVIDIA_SMI.get_or_init(|| {
+        choose_nvidia_smi(is_running_in_wsl(), |cmd| {
+            run_with_timeout(
+                std::process::Command::new(cmd).arg("-L"),
+                DETECTION_COMMAND_TIMEOUT,
+            )
+            .is_some_and(|o| o.status.success())
+        })
+    })
+}
+
+/// Split the leading `memory.free, memory.used` columns off nvidia-smi CSV
+/// output, so one query serves both the GPU list and VRAM usage. Returns the
+/// remaining columns and `memory.free, memory.used, name` lines, where
+/// `name_field` is the index of the name among the remaining columns.
+fn split_nvidia_usage_columns(text: &str, name_field: usize) -> (String, String) {
+    let mut rest = String::new();
+    let mut usage = String::new();
+    for line in text.lines() {
+        let mut columns = line.trim().splitn(3, ',');
+        let (Some(free), Some(used), Some(tail)) = (columns.next(), columns.next(), columns.next())
+        else {
+            continue;
+        };
+        rest.push_str(tail.trim());
+        rest.push('\n');
+        if let Some(name) = tail.splitn(name_field + 1, ',').nth(name_field) {
+            usage.push_str(&format!(
+                "{}, {}, {}\n",
+                free.trim(),
+                used.trim(),
+                name.trim()
+            ));
+        }
+    }
+    (rest, usage)
+}
+
+/// `nvidia-smi`, unless under WSL it fails where `nvidia-smi.exe` runs.
+fn choose_nvidia_smi(wsl: bool, runs: impl Fn(&str) -> bool) -> &'static str {
+    if wsl && !runs("nvidia-smi") && runs("nvidia-smi.exe") {
+        "nvidia-smi.exe"
+    } else {
+        "nvidia-smi"
+    }
+}
+
+pub fn is_running_in_wsl() -> bool {
+    static IS_WSL: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *IS_WSL.get_or_init(detect_running_in_wsl)
+}
+
+fn detect_running_in_wsl() -> bool {
+    if !cfg!(target_os = "linux") {
+        return false;
+    }
+
+    if std::env::var_os("WSL_INTEROP").is_some() || std::env::var_os("WSL_DISTRO_NAME").is_some() {
+        return true;
+    }
+
+    ["/proc/sys/kernel/osrelease", "/proc/version"]
+        .iter()
+        .any(|path| {
+            std::fs::read_to_string(path)
+                .map(|text| text.to_ascii_lowercase().contains("microsoft"))
+                .unwrap_or(false)
+        })
+}
+
+/// True inside Docker, Kubernetes, systemd-nspawn or another OCI container.
+/// WSL is a VM, not a container, so it doesn't count here.
+pub fn is_running_in_container() -> bool {
+    !matches!(container_env(), ContainerEnv::None | ContainerEnv::Wsl)
+}
+
+/// Which container or VM layer (if any) this process is running under.
+pub fn container_env() -> ContainerEnv {
+    static CONTAINER_ENV: std::sync::OnceLock<ContainerEnv> = std::sync::OnceLock::new();
+    *CONTAINER_ENV.get_or_init(|| {
+        if !cfg!(target_os = "linux") {
+            return ContainerEnv::None;
+        }
+        classify_container_env(
+            std::path::Path::new("/.dockerenv").exists(),
+            std::path::Path::new("/run/.containerenv").exists(),
+            std::env::var_os("KUBERNETES_SERVICE_HOST").is_some(),
+            std::env::var("container").ok().as_deref(),
+            std::fs::read_to_string("/proc/1/cgroup").ok().as_deref(),
+            is_running_in_wsl(),
+        )
+    })
+}
+
+fn classify_container_env(
+    dockerenv: bool,
+    containerenv: bool,
+    kubernetes_env: bool,
+    container_var: Option<&str>,
+    init_cgroup: Option<&str>,
+    wsl: bool,
+) -> ContainerEnv {
+    let cgroup = init_cgroup.unwrap_or("");
+    if kubernetes_env || cgroup.contains("kubepods") {
+        return ContainerEnv::Kubernetes;
+    }
+    if dockerenv || cgroup.contains("docker") {
+        return ContainerEnv::Docker;
+    }
+    // systemd-nspawn, podman and LXC set `container=` for PID 1
+    match container_var {
+        Some("systemd-nspawn") => return ContainerEnv::Nspawn,
+        Some("docker") => return ContainerEnv::Docker,
+        Some(v) if !v.is_empty() => return ContainerEnv::Unknown,
+        _ => {}
+    }
+    if containerenv || cgroup.contains("lxc") {
+        return ContainerEnv::Unknown;
+    }
+    if wsl {
+        return ContainerEnv::Wsl;
+    }
+    ContainerEnv::None
+}
+
+/// Parse a compute capability string such as "8.9" into (major, minor).
+pub fn parse_compute_capability(s: &str) -> Option<(u8, u8)> {
+    let (major, minor) = s.trim().split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// SIMD features relevant to CPU inference (llama.cpp / ggml kernels),
+/// detected at runtime for the current architecture.
+pub fn detect_cpu_features() -> Vec<String> {
+    #[allow(unused_mut)]
+    let mut features: Vec<String> = Vec::new();
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        let checks: [(&str, bool); 8] = [
+            ("sse4.2", std::arch::is_x86_feature_detected!("sse4.2")),
+            ("avx", std::arch::is_x86_feature_detected!("avx")),
+            ("avx2", std::arch::is_x86_feature_detected!("avx2")),
+            ("fma", std::arch::is_x86_feature_detected!("fma")),
+            ("f16c", std::arch::is_x86_feature_detected!("f16c")),
+            ("avx512f", std::arch::is_x86_feature_detected!("avx512f")),
+            ("avx512bw", std::arch::is_x86_feature_detected!("avx512bw")),
+            (
+                "avx512vnni",
+                std::arch::is_x86_feature_detected!("avx512vnni"),
+            ),
+        ];
+        features.extend(
+            checks
+                .iter()
+                .filter(|(_, present)| *present)
+                .map(|(name, _)| name.to_string()),
+        );
+        // `is_x86_feature_detected!("amx-tile")` is still unstable, so read
+        // the kernel's flag list instead.
+        #[cfg(target_os = "linux")]
+        if std::fs::read_to_string("/proc/cpuinfo")
+            .map(|info| cpuinfo_has_flag(&info, "amx_tile"))
+            .unwrap_or(false)
+        {
+            features.push("amx".to_string());
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        let checks: [(&str, bool); 5] = [
+            ("neon", std::arch::is_aarch64_feature_detected!("neon")),
+            (
+                "dotprod",
+                std::arch::is_aarch64_feature_detected!("dotprod"),
+            ),
+            ("i8mm", std::arch::is_aarch64_feature_detected!("i8mm")),
+            ("sve", std::arch::is_aarch64_feature_detected!("sve")),
+            ("sve2", std::arch::is_aarch64_feature_detected!("sve2")),
+        ];
+        features.extend(
+            checks
+                .iter()
+                .filter(|(_, present)| *present)
+                .map(|(name, _)| name.to_string()),
+        );
+    }
+
+    features
+}
+
+/// Whether the first `flags` line of /proc/cpuinfo lists `flag`.
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn cpuinfo_has_flag(cpuinfo: &str, flag: &str) -> bool {
+    cpuinfo
+        .lines()
+        .find(|l| l.starts_with("flags"))
+        .and_then(|l| l.split_once(':'))
+        .is_some_and(|(_, flags)| flags.split_whitespace().any(|f| f == flag))
+}
+
+/// Number of NUMA nodes: /sys/devices/system/node on Linux,
+/// `GetNumaHighestNodeNumber` on Windows. 1 when it can't be read.
+fn detect_numa_nodes() -> usize {
+    #[cfg(target_os = "linux")]
+    let nodes = count_numa_nodes(std::path::Path::new("/sys/devices/system/node"));
+    #[cfg(windows)]
+    let nodes = windows_numa_nodes();
+    #[cfg(not(any(target_os = "linux", windows)))]
+    let nodes = None;
+    nodes.unwrap_or(1)
+}
+
+/// Count the `nodeN` entries in a sysfs node directory.
+#[cfg(target_os = "linux")]
+fn count_numa_nodes(dir: &std::path::Path) -> Option<usize> {
+    let count = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|e| {
+            e.file_name()
+                .to_str()
+                .and_then(|n| n.strip_prefix("node"))
+                .is_some_and(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .count();
+    (count > 0).then_some(count)
+}
+
+#[cfg(windows)]
+fn windows_numa_nodes() -> Option<usize> {
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetNumaHighestNodeNumber(highest_node_number: *mut u32) -> i32;
+    }
+    let mut highest = 0u32;
+    // SAFETY: the call only writes a single u32 through the pointer.
+    let ok = unsafe { GetNumaHighestNodeNumber(&mut highest) };
+    (ok != 0).then_some(highest as usize + 1)
+}
+
+/// `RLIMIT_MEMLOCK` of this process in GB; see [`SystemSpecs::mlock_limit_gb`].
+#[cfg(unix)]
+fn detect_mlock_limit_gb() -> Option<f64> {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: getrlimit only writes the struct we pass; geteuid can't fail.
+    let (ok, euid) = unsafe {
+        (
+            libc::getrlimit(libc::RLIMIT_MEMLOCK, &mut limit) == 0,
+            libc::geteuid(),
+        )
+    };
+    // Root can lock memory past the limit
+    if !ok || euid == 0 || limit.rlim_cur == libc::RLIM_INFINITY {
+        return None;
+    }
+    Some(limit.rlim_cur as f64 / (1024.0 * 1024.0 * 1024.0))
+}
+
+#[cfg(not(unix))]
+fn detect_mlock_limit_gb() -> Option<f64> {
+    None
+}
+
+/// Whether an OpenCL runtime is usable, judged by `clinfo` running successfully.
+/// Cached for the process lifetime.
+pub fn has_opencl_runtime() -> bool {
+    static HAS_OPENCL: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *HAS_OPENCL.get_or_init(|| {
+        run_with_timeout(
+            std::process::Command::new("clinfo").arg("--list"),
+            DETECTION_COMMAND_TIMEOUT,
+        )
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+    })
+}
+
+/// Fastest PCIe link (generation, width) among the DRM cards under `drm_root`.
+/// Integrated GPUs usually have no link files and are skipped.
+fn read_drm_pcie_link(drm_root: &std::path::Path) -> Option<(u8, u8)> {
+    let mut cards: Vec<_> = std::fs::read_dir(drm_root).ok()?.flatten().collect();
+    cards.sort_by_key(|e| e.file_name());
+    cards
+        .iter()
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            // Only cardN, not connectors like cardN-DP-1
+            name.starts_with("card") && !name.contains('-')
+        })
+        .filter_map(|e| {
+            let device = e.path().join("device");
+            let read = |f: &str| std::fs::read_to_string(device.join(f)).ok();
+            let generation = SystemSpecs::parse_pcie_link_speed(&read("current_link_speed")?)?;
+            let width: u8 = read("current_link_width")?.trim().parse().ok()?;
+            Some((generation, width))
+        })
+        .max_by(|a, b| {
+            let bandwidth = |&(g, w): &(u8, u8)| pcie_lane_gbps(g) * w as f64;
+            bandwidth(a).total_cmp(&bandwidth(b))
+        })
+}
+
+/// Usable bandwidth of one PCIe lane in GB/s, by generation.
+fn pcie_lane_gbps(generation: u8) -> f64 {
+    match generation {
+        0 | 1 => 0.25,
+        2 => 0.5,
+        3 => 0.985,
+        4 => 1.969,
+        5 => 3.938,
+        _ => 7.563,
+    }
+}
+
+/// Check if the CPU name indicates an AMD APU with unified memory architecture.
+/// These APUs share the full system RAM between CPU and GPU (like Apple Silicon).
+/// Currently covers:
+///  - Ryzen AI MAX / MAX+ (Strix Halo): up to 128 GB unified.
+///  - Ryzen AI 9 / 7 / 5 (Strix Point, Krackan Point): configurable shared
+///    memory, users can allocate most of system RAM to GPU via BIOS.
+///
+/// All Ryzen AI APUs have integrated Radeon GPUs that share system memory.
+fn is_amd_unified_memory_apu(cpu_name: &str) -> bool {
+    let lower = cpu_name.to_lowercase();
+    // All "Ryzen AI" branded APUs use unified/shared memory.
+    // Examples:
+    //   "AMD Ryzen AI MAX+ 395 w/ Radeon 8060S"
+    //   "AMD Ryzen AI 9 HX 370 w/ Radeon 890M"
+    //   "AMD Ryzen AI 7 350"
+    if lower.contains("ryzen ai") {
+        return true;
+    }
+    false
+}
+
+/// Memory limit of the current cgroup in bytes, read from `memory.max`
+/// (cgroups v2) or `memory/memory.limit_in_bytes` (cgroups v1) under `root`
+/// (normally `/sys/fs/cgroup`). `None` when unlimited or not in a cgroup.
+fn read_cgroup_memory_limit(root: &std::path::Path) -> Option<u64> {
+    let v2 = std::fs::read_to_string(root.join("memory.max")).ok();
+    let raw = v2.or_else(|| {
+        std::fs::read_to_string(root.join("memory").join("memory.limit_in_bytes")).ok()
+    })?;
+    let limit: u64 = raw.trim().parse().ok()?; // "max" (v2) = unlimited
+    // v1 reports "unlimited" as a page-aligned i64::MAX
+    (limit > 0 && limit < (1 << 62)).then_some(limit)
+}
+
+/// The cgroup v2 directory whose memory limit binds the process. Starts at
+/// the `0::/path` line of `/proc/self/cgroup` and walks up to `root`, since
+/// a parent slice's `MemoryMax=` caps every child even when the leaf itself
+/// is unlimited; the directory with the lowest `memory.max` wins. Inside a
+/// container the path is `/` (the namespace root). Falls back to `root` when
+/// there is no such line or no level sets a limit.
+fn resolve_cgroup_dir(root: &std::path::Path, proc_self_cgroup: &str) -> std::path::PathBuf {
+    let Some(leaf) = proc_self_cgroup
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(|path| root.join(path.trim().trim_start_matches('/')))
+    else {
+        return root.to_path_buf();
+    };
+    leaf.ancestors()
+        .take_while(|dir| dir.starts_with(root))
+        .filter_map(|dir| read_cgroup_memory_limit(dir).map(|limit| (limit, dir)))
+        .min_by_key(|(limit, _)| *limit)
+        .map(|(_, dir)| dir.to_path_buf())
+        .unwrap_or_else(|| root.to_path_buf())
+}
+
+/// Current memory usage of the cgroup in bytes, from `memory.current`
+/// (cgroups v2) or `memory/memory.usage_in_bytes` (cgroups v1).
+fn read_cgroup_memory_usage(root: &std::path::Path) -> Option<u64> {
+    let v2 = std::fs::read_to_string(root.join("memory.current")).ok();
+    let raw = v2.or_else(|| {
+        std::fs::read_to_string(root.join("memory").join("memory.usage_in_bytes")).ok()
+    })?;
+    raw.trim().parse().ok()
+}
+
+/// Directory models are downloaded into: `$OLLAMA_MODELS` if set, otherwise
+/// the home directory, otherwise the current directory.
+fn model_storage_dir() -> Option<std::path::PathBuf> {
+    std::env::var_os("OLLAMA_MODELS")
+        .map(std::path::PathBuf::from)
+        .or_else(crate::paths::home_dir)
+        .or_else(|| std::env::current_dir().ok())
+}
+
+/// Free bytes and device name (e.g. "/dev/nvme0n1p2") of the filesystem
+/// holding [`model_storage_dir`].
+fn model_storage_disk() -> Option<(u64, String)> {
+    let dir = model_storage_dir()?;
+    // The models directory may not exist yet; its parent's filesystem will do
+    let dir = dir
+        .ancestors()
+        .find_map(|p| p.canonicalize().ok())
+        .unwrap_or(dir);
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    let mounts: Vec<(std::path::PathBuf, (u64, String))> = disks
+        .list()
+        .iter()
+        .map(|d| {
+            let name = d.name().to_string_lossy().into_owned();
+            (d.mount_point().to_path_buf(), (d.available_space(), name))
+        })
+        .collect();
+    mount_for_path(&mounts, &dir)
+}
+
+/// Value of the mount point that contains `path`, i.e. the longest mount
+/// point that is a prefix of it.
+fn mount_for_path<T: Clone>(
+    mounts: &[(std::path::PathBuf, T)],
+    path: &std::path::Path,
+) -> Option<T> {
+    mounts
+        .iter()
+        .filter(|(mount, _)| path.starts_with(mount))
+        .max_by_key(|(mount, _)| mount.components().count())
+        .map(|(_, value)| value.clone())
+}
+
+/// Sequential read speed (GB/s) of known NVMe drives, by lowercase model
+/// name fragment. More specific names come first.
+const NVME_READ_BANDWIDTH: &[(&str, f64)] = &[
+    ("990 pro", 7.45),
+    ("980 pro", 7.0),
+    ("970 evo plus", 3.5),
+    ("970 evo", 3.4),
+    ("970 pro", 3.5),
+    ("pm9a1", 7.0),
+    ("pm981", 3.5),
+    ("sn850x", 7.3),
+    ("sn850", 7.0),
+    ("sn770", 5.15),
+    ("sn750", 3.47),
+    ("sn580", 4.15),
+    ("t705", 14.5),
+    ("t700", 12.4),
+    ("t500", 7.4),
+    ("p5 plus", 6.6),
+    ("p3 plus", 5.0),
+    ("kc3000", 7.0),
+    ("fury renegade", 7.3),
+    ("mp600", 4.95),
+];
+
+/// Share of the PCIe link a drive of unknown model is assumed to reach.
+const NVME_LINK_EFFICIENCY: f64 = 0.8;
+
+/// Read speed of a drive from its model name.
+fn nvme_model_bandwidth(model: &str) -> Option<f64> {
+    let model = model.to_lowercase();
+    NVME_READ_BANDWIDTH
+        .iter()
+        .find(|(name, _)| model.contains(name))
+        .map(|&(_, gb_s)| gb_s)
+}
+
+/// The whole-drive block device behind an NVMe partition, e.g.
+/// "/dev/nvme0n1p2" -> "nvme0n1". `None` for other devices.
+fn nvme_block_device(device: &str) -> Option<&str> {
+    let name = device.strip_prefix("/dev/").unwrap_or(device);
+    if !name.starts_with("nvme") {
+        return None;
+    }
+    match name.rsplit_once('p') {
+        Some((drive, part)) if !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()) => {
+            Some(drive)
+        }
+        _ => Some(name),
+    }
+}
+
+/// Read speed of the drive at `/sys/block/<dev>`: its model from the
+/// table, else [`NVME_LINK_EFFICIENCY`] of its PCIe link.
+fn nvme_bandwidth_from_sysfs(block_dev: &std::path::Path) -> Option<f64> {
+    let device = block_dev.join("device");
+    if let Some(gb_s) = std::fs::read_to_string(device.join("model"))
+        .ok()
+        .and_then(|model| nvme_model_bandwidth(&model))
+    {
+        return Some(gb_s);
+    }
+    // device/ is the NVMe controller; its parent device/ the PCIe function
+    let pci = device.join("device");
+    let read = |f: &str| std::fs::read_to_string(pci.join(f)).ok();
+    let generation = SystemSpecs::parse_pcie_link_speed(&read("current_link_speed")?)?;
+    let width: u8 = read("current_link_width")?.trim().parse().ok()?;
+    Some(pcie_lane_gbps(generation) * width as f64 * NVME_LINK_EFFICIENCY)
+}
+
+/// Read speed of the NVMe drive behind `device`, from sysfs on Linux. When
+/// `device` isn't an NVMe partition (LVM, dm-crypt, ...), the slowest NVMe
+/// drive stands in. On macOS the boot volume's `diskutil info` is used.
+fn detect_nvme_read_bandwidth(device: Option<&str>) -> Option<f64> {
+    if cfg!(target_os = "linux") {
+        let sys_block = std::path::Path::new("/sys/block");
+        if let Some(drive) = device.and_then(nvme_block_device) {
+            return nvme_bandwidth_from_sysfs(&sys_block.join(drive));
+        }
+        std::fs::read_dir(sys_block)
+            .ok()?
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("nvme"))
+            .filter_map(|e| nvme_bandwidth_from_sysfs(&e.path()))
+            .reduce(f64::min)
+    } else if cfg!(target_os = "macos") {
+        let output = run_with_timeout(
+            std::process::Command::new("diskutil").args(["info", "/"]),
+            DETECTION_COMMAND_TIMEOUT,
+        )
+        .filter(|o| o.status.success())?;
+        parse_diskutil_read_bandwidth(&String::from_utf8(output.stdout).ok()?)
+    } else {
+        None
+    }
+}
+
+/// Read speed of a solid-state drive from `diskutil info /`: its media name
+/// from the table, else a typical figure for its bus. Apple Silicon's
+/// internal SSD reports the "Apple Fabric" protocol.
+fn parse_diskutil_read_bandwidth(text: &str) -> Option<f64> {
+    let field = |key: &str| {
+        text.lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(k, _)| k.trim() == key)
+            .map(|(_, v)| v.trim())
+    };
+    if field("Solid State") != Some("Yes") {
+        return None;
+    }
+    if let Some(gb_s) = field("Device / Media Name").and_then(nvme_model_bandwidth) {
+        return Some(gb_s);
+    }
+    match field("Protocol")? {
+        "Apple Fabric" => Some(5.0),
+        "PCI-Express" => Some(2.5),
+        _ => None,
+    }
+}
+
+/// Read total system RAM from /proc/meminfo (Linux only).
+/// Used as the unified memory pool on NVIDIA Tegra / Grace Blackwell platforms
+/// where nvidia-smi cannot report dedicated VRAM.
+fn read_proc_meminfo_total_gb() -> Option<f64> {
+    let text = std::fs::read_to_string("/proc/meminfo").ok()?;
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix("MemTotal:") {
+            let kb: u64 = rest.split_whitespace().next()?.parse().ok()?;
+            return Some(kb as f64 / (1024.0 * 1024.0));
+        }
+    }
+    None
+}
+
+/// VRAM of NVIDIA laptop GPUs ("... Laptop GPU"), by model number. More
+/// specific names come first. Where a model ships with more than one memory
+/// size, the smaller is listed so a laptop isn't credited VRAM it may lack.
+const LAPTOP_GPU_VRAM: &[(&str, f64)] = &[
+    ("5090", 24.0),
+    ("5080", 16.0),
+    ("5070 ti", 12.0),
+    ("5070", 8.0),
+    ("5060", 8.0),
+    ("5050", 8.0),
+    ("4090", 16.0),
+    ("4080", 12.0),
+    ("4070", 8.0),
+    ("4060", 8.0),
+    ("4050", 6.0),
+    ("3080 ti", 16.0),
+    ("3080", 8.0),
+    ("3070", 8.0),
+    ("3060", 6.0),
+    ("3050", 4.0),
+    ("2080", 8.0),
+    ("2070", 8.0),
+    ("2060", 6.0),
+];
+
+/// Fallback VRAM estimation from GPU model name.
+/// Used when nvidia-smi or other tools report 0 VRAM.
+fn estimate_vram_from_name(name: &str) -> f64 {
+    let lower = name.to_lowercase();
+    // Laptop parts share a model number with a bigger desktop card
+    if (lower.contains("laptop") || lower.contains("mobile"))
+        && let Some(&(_, vram)) = LAPTOP_GPU_VRAM
+            .iter()
+            .find(|(model, _)| lower.contains(model))
+    {
+        return vram;
+    }
+    // NVIDIA RTX 50 series
+    if lower.contains("5090") {
+        return 32.0;
+    }
+    if lower.contains("5080") {
+        return 16.0;
+    }
+    if lower.contains("5070 ti") {
+        return 16.0;
+    }
+    if lower.contains("5070") {
+        return 12.0;
+    }
+    if lower.contains("5060 ti") {
+        return 16.0;
+    }
+    if lower.contains("5060") {
+        return 8.0;
+    }
+    // NVIDIA RTX 40 series
+    if lower.contains("4090") {
+        return 24.0;
+    }
+    if lower.contains("4080") {
+        return 16.0;
+    }
+    if lower.contains("4070 ti") {
+        return 12.0;
+    }
+    if lower.contains("4070") {
+        return 12.0;
+    }
+    if lower.contains("4060 ti") {
+        return 16.0;
+    }
+    if lower.contains("4060") {
+        return 8.0;
+    }
+    // NVIDIA RTX 30 series
+    if lower.contains("3090") {
+        return 24.0;
+    }
+    if lower.contains("3080 ti") {
+        return 12.0;
+    }
+    if lower.contains("3080") {
+        return 10.0;
+    }
+    if lower.contains("3070") {
+        return 8.0;
+    }
+    if lower.contains("3060 ti") {
+        return 8.0;
+    }
+    if lower.contains("3060") {
+        return 12.0;
+    }
+    if lower.contains("3050") {
+        return 8.0;
+    }
+    // NVIDIA RTX 20 / GTX 16 series (Turing)
+    if lower.contains("2080 ti") {
+        return 11.0;
+    }
+    if lower.contains("2080") {
+        return 8.0;
+    }
+    if lower.contains("2070") {
+        return 8.0;
+    }
+    if lower.contains("2060") {
+        return 6.0;
+    }
+    if lower.contains("1660") {
+        return 6.0;
+    }
+    if lower.contains("1650") {
+        return 4.0;
+    }
+    // NVIDIA GTX 10 series (Pascal)
+    if lower.contains("1080 ti") {
+        return 11.0;
+    }
+    if lower.contains("1080") {
+        return 8.0;
+    }
+    if lower.contains("1070") {
+        return 8.0;
+    }
+    if lower.contains("1060") {
+        if lower.contains("3gb") || lower.contains("3 gb") {
+            return 3.0;
+        }
+        return 6.0;
+    }
+    if lower.contains("1050 ti") {
+        return 4.0;
+    }
+    if lower.contains("1050") {
+        return 2.0;
+    }
+    // NVIDIA GTX 900 / 700 series (Maxwell / Kepler).
+    // Three-digit model numbers are matched with the "gtx " prefix so they
+    // don't collide with AMD RX 7700 / 7800 style names.
+    if lower.contains("gtx 980 ti") {
+        return 6.0;
+    }
+    if lower.contains("gtx 980") {
+        return 4.0;
+    }
+    if lower.contains("gtx 970") {
+        return 4.0;
+    }
+    if lower.contains("gtx 960") {
+        return 2.0;
+    }
+    if lower.contains("gtx 780 ti") {
+        return 3.0;
+    }
+    if lower.contains("gtx 780") {
+        return 3.0;
+    }
+    if lower.contains("gtx 770") {
+        return 2.0;
+    }
+    // NVIDIA Titan cards
+    if lower.contains("titan rtx") {
+        return 24.0;
+    }
+    if lower.contains("titan v") {
+        return 12.0;
+    }
+    if lower.contains("titan x") {
+        return 12.0;
+    }
+    if lower.contains("titan black") || lower.contains("titan z") {
+        return 6.0;
+    }
+    if lower.contains("titan") {
+        return 6.0;
+    }
+    // NVIDIA professional / workstation (RTX Ada, RTX A-series, Quadro).
+    // Checked before data center entries because "a1000" would otherwise
+    // match "a100" and "t400" would match "t4".
+    // RTX PRO (Blackwell) names share their numbers with the Ada and Turing
+    // cards below.
+    if lower.contains("rtx pro") {
+        if lower.contains("6000") {
+            return 96.0;
+        }
+        if lower.contains("5000") {
+            return 48.0;
+        }
+        if lower.contains("4500") {
+            return 32.0;
+        }
+        if lower.contains("4000") {
+            return 24.0;
+        }
+        if lower.contains("2000") {
+            return 16.0;
+        }
+    }
+    if lower.contains(" ada") {
+        if lower.contains("6000") || lower.contains("5880") {
+            return 48.0;
+        }
+        if lower.contains("5000") {
+            return 32.0;
+        }
+        if lower.contains("4500") {
+            return 24.0;
+        }
+        if lower.contains("4000") {
+            return 20.0;
+        }
+        if lower.contains("2000") {
+            return 16.0;
+        }
+    }
+    if lower.contains("rtx 8000") {
+        return 48.0;
+    }
+    if lower.contains("rtx 6000") {
+        return 24.0;
+    }
+    if lower.contains("rtx 5000") {
+        return 16.0;
+    }
+    if lower.contains("rtx 4000") {
+        return 8.0;
+    }
+    if lower.contains("a6000") {
+        return 48.0;
+    }
+    if lower.contains("a5500") {
+        return 24.0;
+    }
+    if lower.contains("a5000") {
+        return 24.0;
+    }
+    if lower.contains("a4500") {
+        return 20.0;
+    }
+    if lower.contains("a4000") {
+        return 16.0;
+    }
+    if lower.contains("a2000") {
+        return 12.0;
+    }
+    if lower.contains("a1000") {
+        return 8.0;
+    }
+    if lower.contains("a400") {
+        return 4.0;
+    }
+    if lower.contains("gv100") {
+        return 32.0;
+    }
+    if lower.contains("p6000") {
+        return 24.0;
+    }
+    if lower.contains("p5000") {
+        return 16.0;
+    }
+    if lower.contains("p4000") {
+        return 8.0;
+    }
+    if lower.contains("t1000") {
+        return 8.0;
+    }
+    if lower.contains("t600") || lower.contains("t400") {
+        return 4.0;
+    }
+    // Data center
+    if lower.contains("h200") {
+        return 141.0;
+    }
+    if lower.contains("h100") {
+        return 80.0;
+    }
+    if lower.contains("a100") {
         return 80.0;
     }
-    if lower.contains("l40") {
-        return 48.0;
+    // NVIDIA L-series (Ada data center)
+    if lower.contains("l40s") || lower.contains("l40") || lower.contains("l20") {
+        return 48.0;
+    }
+    if lower.contains("nvidia l4") || lower.ends_with(" l4") || lower.contains(" l4 ") {
+        return 24.0;
+    }
+    // Ampere A40 / A30, whole-word so "A3000" and the like don't match
+    if lower.ends_with(" a40") || lower.contains(" a40 ") {
+        return 48.0;
+    }
+    if lower.ends_with(" a30") || lower.contains(" a30 ") {
+        return 24.0;
+    }
+    if lower.contains("a10") {
+        return 24.0;
+    }
+    if lower.contains("t4") {
+        return 16.0;
+    }
+    // NVIDIA Grace / DGX Spark unified memory SoCs
+    if lower.contains("gb10") {
+        return 128.0;
+    }
+    if lower.contains("gb20") {
+        return 128.0;
+    }
+    // AMD RX 9000 series (RDNA 4)
+    if lower.contains("9070 xt") {
+        return 16.0;
+    }
+    if lower.contains("9070") {
+        return 12.0;
+    }
+    if lower.contains("9060 xt") {
+        return 16.0;
+    }
+    if lower.contains("9060") {
+        return 8.0;
+    }
+    // AMD RX 7000 series
+    if lower.contains("7900 xtx") {
+        return 24.0;
+    }
+    if lower.contains("7900") {
+        return 20.0;
+    }
+    if lower.contains("7800") {
+        return 16.0;
+    }
+    if lower.contains("7700") {
+        return 12.0;
+    }
+    if lower.contains("7600") {
+        return 8.0;
+    }
+    // AMD RX 6000 series
+    if lower.contains("6950") {
+        return 16.0;
+    }
+    if lower.contains("6900") {
+        return 16.0;
+    }
+    if lower.contains("6800") {
+        return 16.0;
+    }
+    if lower.contains("6750") {
+        return 12.0;
+    }
+    if lower.contains("6700") {
+        return 12.0;
+    }
+    if lower.contains("6650") {
+        return 8.0;
+    }
+    if lower.contains("6600") {
+        return 8.0;
+    }
+    if lower.contains("6500") {
+        return 4.0;
+    }
+    // AMD RX 5000 series
+    if lower.contains("5700 xt") {
+        return 8.0;
+    }
+    if lower.contains("5700") {
+        return 8.0;
+    }
+    if lower.contains("5600") {
+        return 6.0;
+    }
+    if lower.contains("5500") {
+        return 4.0;
+    }
+    // AMD Radeon 8000 series (Ryzen AI MAX / Strix Halo integrated)
+    // These are unified memory APUs; VRAM = system RAM in practice,
+    // but this fallback gives a reasonable discrete estimate for name-only detection.
+    if lower.contains("8060s") {
+        return 32.0;
+    }
+    if lower.contains("8050s") {
+        return 24.0;
+    }
+    if lower.contains("8060") && !lower.contains("8060s") {
+        return 16.0;
+    }
+    if lower.contains("8050") && !lower.contains("8050s") {
+        return 12.0;
+    }
+    // AMD Radeon 800M series (Ryzen AI 9 / Strix Point integrated)
+    if lower.contains("890m") {
+        return 16.0;
+    }
+    if lower.contains("880m") {
+        return 12.0;
+    }
+    if lower.contains("870m") {
+        return 8.0;
+    }
+    if lower.contains("860m") {
+        return 8.0;
+    }
+
+    // Integrated GPUs (APU iGPUs) — must check before generic fallbacks
+    // APU names like "AMD Radeon(TM) Graphics" or "Radeon Graphics" without
+    // a discrete model number (RX/HD/R5/R7/R9) have very limited dedicated VRAM.
+    if (lower.contains("radeon") || lower.contains("amd"))
+        && !lower.contains("rx ")
+        && !lower.contains("hd ")
+        && !lower.contains(" r5 ")
+        && !lower.contains(" r7 ")
+        && !lower.contains(" r9 ")
+        && !lower.contains("8060")
+        && !lower.contains("8050")
+        && (lower.contains("graphics") || lower.contains("igpu"))
+    {
+        return 0.5;
+    }
+
+    // Generic fallbacks
+    if lower.contains("rtx") {
+        return 8.0;
+    }
+    if lower.contains("gtx") {
+        return 4.0;
+    }
+    if lower.contains("rx ") || lower.contains("radeon") {
+        return 8.0;
     }
-    if lower.contains("a10") {
-        return 24.0;
+    0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AppleChipVariant, ContainerEnv, GpuBackend, GpuClass, GpuInfo, GpuLinkTopology, GpuProbes,
+        SystemSpecs, SystemSpecsBuilder, THERMAL_THROTTLE_WARN_C, run_with_timeout,
+    };
+
+    #[test]
+    fn test_parse_nvidia_smi_does_not_sum_multi_gpu_vram() {
+        let text = "24564, NVIDIA GeForce RTX 4090\n24564, NVIDIA GeForce RTX 4090\n";
+        let gpus = SystemSpecs::parse_nvidia_smi_list(text);
+
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].count, 2);
+        let vram = gpus[0]
+            .vram_gb
+            .expect("VRAM should be parsed for RTX 4090 entries");
+        // 24564 MiB ~= 23.99 GiB; must stay single-card VRAM, not 2x summed.
+        assert!(vram > 23.0 && vram < 25.0, "unexpected VRAM value: {vram}");
     }
-    if lower.contains("t4") {
-        return 16.0;
+
+    #[test]
+    fn test_wsl_falls_back_to_windows_nvidia_smi() {
+        let only_exe = |cmd: &str| cmd == "nvidia-smi.exe";
+        assert_eq!(super::choose_nvidia_smi(true, only_exe), "nvidia-smi.exe");
+        assert_eq!(super::choose_nvidia_smi(false, only_exe), "nvidia-smi");
+        assert_eq!(super::choose_nvidia_smi(true, |_| true), "nvidia-smi");
+        assert_eq!(super::choose_nvidia_smi(true, |_| false), "nvidia-smi");
+
+        // nvidia-smi.exe prints Windows line endings
+        let gpus = SystemSpecs::parse_nvidia_smi_list("24564, NVIDIA GeForce RTX 4090\r\n");
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].name, "NVIDIA GeForce RTX 4090");
     }
-    // NVIDIA Grace / DGX Spark unified memory SoCs
-    if lower.contains("gb10") {
-        return 128.0;
+
+    #[test]
+    fn test_parse_nvidia_smi_keeps_distinct_models() {
+        let text = "24564, NVIDIA GeForce RTX 4090\n16376, NVIDIA GeForce RTX 4080\n";
+        let gpus = SystemSpecs::parse_nvidia_smi_list(text);
+
+        assert_eq!(gpus.len(), 2);
+        assert!(gpus.iter().any(|g| g.name.contains("4090") && g.count == 1));
+        assert!(gpus.iter().any(|g| g.name.contains("4080") && g.count == 1));
     }
-    if lower.contains("gb20") {
-        return 128.0;
+
+    #[test]
+    fn test_parse_nvidia_smi_gb10_gets_vram_estimate() {
+        // DGX Spark reports GB10 with 0 VRAM from nvidia-smi
+        let text = "0, NVIDIA GB10\n";
+        let gpus = SystemSpecs::parse_nvidia_smi_list(text);
+
+        assert_eq!(gpus.len(), 1);
+        assert!(gpus[0].name.contains("GB10"));
+        // estimate_vram_from_name should kick in and return 128GB
+        let vram = gpus[0].vram_gb.expect("GB10 should have estimated VRAM");
+        assert!(vram > 100.0, "GB10 VRAM should be ~128GB, got {vram}");
     }
-    // AMD RX 9000 series (RDNA 4)
-    if lower.contains("9070 xt") {
-        return 16.0;
+
+    #[test]
+    fn test_estimate_vram_gb10() {
+        assert_eq!(super::estimate_vram_from_name("NVIDIA GB10"), 128.0);
+        assert_eq!(super::estimate_vram_from_name("NVIDIA GB20"), 128.0);
     }
-    if lower.contains("9070") {
-        return 12.0;
+
+    #[test]
+    fn test_estimate_vram_laptop_gpus() {
+        use super::estimate_vram_from_name as est;
+        assert_eq!(est("NVIDIA GeForce RTX 4090 Laptop GPU"), 16.0);
+        assert_eq!(est("NVIDIA GeForce RTX 4080 Laptop GPU"), 12.0);
+        assert_eq!(est("NVIDIA GeForce RTX 4050 Laptop GPU"), 6.0);
+        assert_eq!(est("NVIDIA GeForce RTX 3050 Ti Laptop GPU"), 4.0);
+        assert_eq!(est("NVIDIA GeForce RTX 3080 Ti Mobile"), 16.0);
+        assert_eq!(est("NVIDIA GeForce RTX 5070 Ti Laptop GPU"), 12.0);
+        // Desktop cards keep their own sizes
+        assert_eq!(est("NVIDIA GeForce RTX 4090"), 24.0);
+        assert_eq!(est("NVIDIA GeForce RTX 3050"), 8.0);
     }
-    if lower.contains("9060 xt") {
-        return 16.0;
+
+    #[test]
+    fn test_estimate_vram_older_nvidia_generations() {
+        use super::estimate_vram_from_name as est;
+        assert_eq!(est("NVIDIA GeForce GTX 1080 Ti"), 11.0);
+        assert_eq!(est("NVIDIA GeForce GTX 1080"), 8.0);
+        assert_eq!(est("NVIDIA GeForce GTX 1070 Ti"), 8.0);
+        assert_eq!(est("NVIDIA GeForce GTX 1070"), 8.0);
+        assert_eq!(est("NVIDIA GeForce GTX 1060 6GB"), 6.0);
+        assert_eq!(est("NVIDIA GeForce GTX 1060 3GB"), 3.0);
+        assert_eq!(est("NVIDIA GeForce GTX 980 Ti"), 6.0);
+        assert_eq!(est("NVIDIA GeForce GTX 980"), 4.0);
+        assert_eq!(est("NVIDIA GeForce GTX 970"), 4.0);
+        assert_eq!(est("NVIDIA GeForce GTX 960"), 2.0);
+        assert_eq!(est("NVIDIA GeForce GTX 780 Ti"), 3.0);
+        assert_eq!(est("NVIDIA GeForce GTX TITAN Black"), 6.0);
+        assert_eq!(est("NVIDIA GeForce GTX TITAN Z"), 6.0);
+        assert_eq!(est("NVIDIA GeForce GTX TITAN"), 6.0);
+        assert_eq!(est("NVIDIA GeForce GTX TITAN X"), 12.0);
+        assert_eq!(est("NVIDIA TITAN RTX"), 24.0);
+        assert_eq!(est("NVIDIA GeForce RTX 2080 Ti"), 11.0);
+        assert_eq!(est("NVIDIA GeForce GTX 1660 SUPER"), 6.0);
+        // Three-digit GTX names must not shadow AMD RDNA 3 cards
+        assert_eq!(est("AMD Radeon RX 7700 XT"), 12.0);
+        assert_eq!(est("AMD Radeon RX 7800 XT"), 16.0);
     }
-    if lower.contains("9060") {
-        return 8.0;
+
+    #[test]
+    fn test_estimate_vram_professional_nvidia() {
+        use super::estimate_vram_from_name as est;
+        assert_eq!(est("NVIDIA RTX 6000 Ada Generation"), 48.0);
+        assert_eq!(est("NVIDIA RTX 5880 Ada Generation"), 48.0);
+        assert_eq!(est("NVIDIA RTX 5000 Ada Generation"), 32.0);
+        assert_eq!(est("NVIDIA RTX 4500 Ada Generation"), 24.0);
+        assert_eq!(est("NVIDIA RTX 4000 SFF Ada Generation"), 20.0);
+        assert_eq!(est("NVIDIA RTX 2000 Ada Generation"), 16.0);
+        assert_eq!(est("Quadro RTX 8000"), 48.0);
+        assert_eq!(est("Quadro RTX 6000"), 24.0);
+        assert_eq!(est("Quadro RTX 5000"), 16.0);
+        assert_eq!(est("Quadro RTX 4000"), 8.0);
+        assert_eq!(est("NVIDIA RTX A6000"), 48.0);
+        assert_eq!(est("NVIDIA RTX A5500"), 24.0);
+        assert_eq!(est("NVIDIA RTX A5000"), 24.0);
+        assert_eq!(est("NVIDIA RTX A4500"), 20.0);
+        assert_eq!(est("NVIDIA RTX A4000"), 16.0);
+        assert_eq!(est("NVIDIA RTX A2000 12GB"), 12.0);
+        assert_eq!(est("NVIDIA RTX A1000"), 8.0);
+        assert_eq!(est("NVIDIA RTX A400"), 4.0);
+        assert_eq!(est("Quadro GV100"), 32.0);
+        assert_eq!(est("Quadro P6000"), 24.0);
+        assert_eq!(est("Quadro P5000"), 16.0);
+        assert_eq!(est("Quadro P4000"), 8.0);
+        assert_eq!(est("NVIDIA T1000"), 8.0);
+        assert_eq!(est("NVIDIA T400"), 4.0);
+        // L-series
+        assert_eq!(est("NVIDIA L40S"), 48.0);
+        assert_eq!(est("NVIDIA L40"), 48.0);
+        assert_eq!(est("NVIDIA L20"), 48.0);
+        assert_eq!(est("NVIDIA L4"), 24.0);
+        // RTX PRO (Blackwell)
+        assert_eq!(
+            est("NVIDIA RTX PRO 6000 Blackwell Workstation Edition"),
+            96.0
+        );
+        assert_eq!(est("NVIDIA RTX PRO 5000 Blackwell"), 48.0);
+        assert_eq!(est("NVIDIA RTX PRO 4000 Blackwell SFF Edition"), 24.0);
+        // Other data center cards
+        assert_eq!(est("NVIDIA H200"), 141.0);
+        assert_eq!(est("NVIDIA A40"), 48.0);
+        assert_eq!(est("NVIDIA A30"), 24.0);
+        // Existing data center entries still resolve
+        assert_eq!(est("NVIDIA A100-SXM4-80GB"), 80.0);
+        assert_eq!(est("Tesla T4"), 16.0);
     }
-    // AMD RX 7000 series
-    if lower.contains("7900 xtx") {
-        return 24.0;
+
+    #[test]
+    fn test_parse_extended_discrete_gpu_not_unified() {
+        // Discrete GPU: addressing_mode is "None", VRAM is reported normally
+        let text = "None, 24564, NVIDIA GeForce RTX 4090\n";
+        let gpus = SystemSpecs::parse_nvidia_smi_extended(text);
+
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].name, "NVIDIA GeForce RTX 4090");
+        assert!(
+            !gpus[0].unified_memory,
+            "discrete GPU should not be unified"
+        );
+        let vram = gpus[0].vram_gb.expect("VRAM should be present");
+        assert!(vram > 23.0 && vram < 25.0, "unexpected VRAM: {vram}");
     }
-    if lower.contains("7900") {
-        return 20.0;
+
+    #[test]
+    fn test_parse_extended_tegra_unified_memory() {
+        // NVIDIA Tegra / Grace Blackwell: ATS addressing, VRAM is [N/A]
+        // On a real system, /proc/meminfo would provide the fallback.
+        // In tests, /proc/meminfo may or may not exist.
+        let text = "ATS, [N/A], NVIDIA Thor\n";
+        let gpus = SystemSpecs::parse_nvidia_smi_extended(text);
+
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].name, "NVIDIA Thor");
+        assert!(gpus[0].unified_memory, "ATS should set unified_memory=true");
+        // VRAM comes from /proc/meminfo; if unavailable, it's None
+        // (on Linux test machines it will be Some, on macOS CI it will be None)
     }
-    if lower.contains("7800") {
-        return 16.0;
+
+    fn base_specs() -> SystemSpecs {
+        SystemSpecs {
+            total_ram_gb: 32.0,
+            available_ram_gb: 24.0,
+            total_cpu_cores: 8,
+            cpu_name: "Test CPU".to_string(),
+            has_gpu: false,
+            gpu_vram_gb: None,
+            total_gpu_vram_gb: None,
+            gpu_vram_gb_per_device: vec![],
+            gpu_vram_available_gb: None,
+            gpu_vram_used_gb: None,
+            gpu_name: None,
+            gpu_count: 0,
+            unified_memory: false,
+            backend: GpuBackend::CpuX86,
+            gpus: vec![],
+            apple_chip_variant: None,
+            memory_bandwidth_gbps: None,
+            cpu_features: Vec::new(),
+            gpu_link_topology: GpuLinkTopology::SingleGpu,
+            cuda_compute_capability: None,
+            rocm_version: None,
+            gpu_driver_version: None,
+            gpu_temperature_c: None,
+            container_env: ContainerEnv::None,
+            swap_total_gb: 0.0,
+            swap_available_gb: 0.0,
+            disk_available_gb: None,
+            gpu_core_count: None,
+            pcie_gen: None,
+            pcie_width: None,
+            numa_nodes: 1,
+            overhead_gb: None,
+            nvme_read_bandwidth_gb_s: None,
+            mlock_limit_gb: None,
+            jetson_variant: None,
+        }
     }
-    if lower.contains("7700") {
-        return 12.0;
+
+    #[test]
+    fn test_infer_gpu_backend_opencl_fallback() {
+        assert_eq!(
+            SystemSpecs::infer_gpu_backend_with("Mali-G78 MP24", true, false),
+            GpuBackend::OpenCL
+        );
+        assert_eq!(
+            SystemSpecs::infer_gpu_backend_with("Mali-G78 MP24", false, false),
+            GpuBackend::Vulkan
+        );
+        // Vendor heuristics still win when OpenCL is present
+        assert_eq!(
+            SystemSpecs::infer_gpu_backend_with("NVIDIA GeForce RTX 4090", true, false),
+            GpuBackend::Cuda
+        );
+        assert_eq!(
+            SystemSpecs::infer_gpu_backend_with("AMD Radeon RX 580", true, false),
+            GpuBackend::Vulkan
+        );
     }
-    if lower.contains("7600") {
-        return 8.0;
+
+    #[test]
+    fn test_parse_nvidia_cuda_info() {
+        assert_eq!(
+            SystemSpecs::parse_nvidia_cuda_info("8.9, 550.54.14\n8.6, 550.54.14\n"),
+            (Some("8.9".to_string()), Some("550.54.14".to_string()))
+        );
+        assert_eq!(
+            SystemSpecs::parse_nvidia_cuda_info("[N/A], [N/A]\n12.0, 570.86.10\n"),
+            (Some("12.0".to_string()), Some("570.86.10".to_string()))
+        );
+        assert_eq!(SystemSpecs::parse_nvidia_cuda_info(""), (None, None));
     }
-    // AMD RX 6000 series
-    if lower.contains("6950") {
-        return 16.0;
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_detect_async_matches_detect() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let mut async_specs = runtime.block_on(SystemSpecs::detect_async());
+        let sync_specs = SystemSpecs::detect();
+        // Free RAM, VRAM, swap, disk and GPU temperature drift between the two runs
+        async_specs.available_ram_gb = sync_specs.available_ram_gb;
+        async_specs.gpu_vram_available_gb = sync_specs.gpu_vram_available_gb;
+        async_specs.gpu_vram_used_gb = sync_specs.gpu_vram_used_gb;
+        async_specs.swap_available_gb = sync_specs.swap_available_gb;
+        async_specs.disk_available_gb = sync_specs.disk_available_gb;
+        async_specs.gpu_temperature_c = sync_specs.gpu_temperature_c;
+        assert_eq!(async_specs, sync_specs);
+    }
+
+    /// Fresh directory under the system temp dir for cgroup fixtures.
+    fn cgroup_fixture(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("llmfit-cgroup-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for (path, contents) in files {
+            let file = root.join(path);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, contents).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_parse_pcie_link() {
+        assert_eq!(
+            SystemSpecs::parse_nvidia_pcie_link("4, 16\n3, 8\n"),
+            Some((4, 16))
+        );
+        assert_eq!(SystemSpecs::parse_nvidia_pcie_link("[N/A], 16\n"), None);
+        assert_eq!(
+            SystemSpecs::parse_pcie_link_speed("16.0 GT/s PCIe\n"),
+            Some(4)
+        );
+        assert_eq!(SystemSpecs::parse_pcie_link_speed("8.0 GT/s PCIe"), Some(3));
+        assert_eq!(SystemSpecs::parse_pcie_link_speed("2.5 GT/s"), Some(1));
+        assert_eq!(SystemSpecs::parse_pcie_link_speed("Unknown"), None);
+    }
+
+    #[test]
+    fn test_read_drm_pcie_link_picks_fastest_card() {
+        let root = cgroup_fixture(
+            "drm-pcie",
+            &[
+                ("card0/device/vendor", "0x8086\n"),
+                ("card1/device/current_link_speed", "8.0 GT/s PCIe\n"),
+                ("card1/device/current_link_width", "4\n"),
+                ("card2/device/current_link_speed", "16.0 GT/s PCIe\n"),
+                ("card2/device/current_link_width", "16\n"),
+                ("card2-DP-1/device/current_link_width", "32\n"),
+            ],
+        );
+        assert_eq!(super::read_drm_pcie_link(&root), Some((4, 16)));
+
+        let specs = SystemSpecsBuilder::from_specs(base_specs())
+            .pcie_gen(Some(3))
+            .pcie_width(Some(4))
+            .build();
+        let bw = specs.pcie_bandwidth_gbps().unwrap();
+        assert!((bw - 3.94).abs() < 0.01);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_count_numa_nodes() {
+        let root = cgroup_fixture(
+            "numa",
+            &[
+                ("node0/cpulist", "0-15\n"),
+                ("node1/cpulist", "16-31\n"),
+                ("possible", "0-1\n"),
+                ("has_cpu", "0-1\n"),
+            ],
+        );
+        assert_eq!(super::count_numa_nodes(&root), Some(2));
+        assert_eq!(super::count_numa_nodes(&root.join("missing")), None);
+    }
+
+    #[test]
+    fn test_read_cgroup_v2_memory() {
+        let root = cgroup_fixture(
+            "v2",
+            &[
+                ("memory.max", "17179869184\n"),
+                ("memory.current", "4294967296\n"),
+            ],
+        );
+        assert_eq!(super::read_cgroup_memory_limit(&root), Some(16 << 30));
+        assert_eq!(super::read_cgroup_memory_usage(&root), Some(4 << 30));
+
+        let unlimited = cgroup_fixture("v2-max", &[("memory.max", "max\n")]);
+        assert_eq!(super::read_cgroup_memory_limit(&unlimited), None);
+
+        let _ = std::fs::remove_dir_all(root);
+        let _ = std::fs::remove_dir_all(unlimited);
+    }
+
+    #[test]
+    fn test_resolve_cgroup_dir() {
+        let root = cgroup_fixture(
+            "self",
+            &[
+                ("memory.max", "max\n"),
+                ("user.slice/llmfit.scope/memory.max", "8589934592\n"),
+            ],
+        );
+        let dir = super::resolve_cgroup_dir(&root, "0::/user.slice/llmfit.scope\n");
+        assert_eq!(dir, root.join("user.slice/llmfit.scope"));
+        assert_eq!(super::read_cgroup_memory_limit(&dir), Some(8 << 30));
+
+        // Container namespace root, cgroup v1 hierarchy, and unknown paths
+        assert_eq!(super::resolve_cgroup_dir(&root, "0::/\n"), root);
+        assert_eq!(
+            super::resolve_cgroup_dir(&root, "4:memory:/docker/abc\n"),
+            root
+        );
+        assert_eq!(
+            super::resolve_cgroup_dir(&root, "0::/missing.scope\n"),
+            root
+        );
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_resolve_cgroup_dir_uses_parent_limit() {
+        let root = cgroup_fixture(
+            "parent",
+            &[
+                ("memory.max", "max\n"),
+                ("user.slice/memory.max", "4294967296\n"),
+                ("user.slice/memory.current", "1073741824\n"),
+                ("user.slice/llmfit.scope/memory.max", "max\n"),
+            ],
+        );
+        let dir = super::resolve_cgroup_dir(&root, "0::/user.slice/llmfit.scope\n");
+        assert_eq!(dir, root.join("user.slice"));
+        assert_eq!(super::read_cgroup_memory_limit(&dir), Some(4 << 30));
+        assert_eq!(super::read_cgroup_memory_usage(&dir), Some(1 << 30));
+
+        // A tighter leaf limit still wins over the parent's
+        std::fs::write(
+            root.join("user.slice/llmfit.scope/memory.max"),
+            "2147483648\n",
+        )
+        .unwrap();
+        let dir = super::resolve_cgroup_dir(&root, "0::/user.slice/llmfit.scope\n");
+        assert_eq!(dir, root.join("user.slice/llmfit.scope"));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_read_cgroup_v1_memory() {
+        let root = cgroup_fixture(
+            "v1",
+            &[
+                ("memory/memory.limit_in_bytes", "8589934592\n"),
+                ("memory/memory.usage_in_bytes", "1073741824\n"),
+            ],
+        );
+        assert_eq!(super::read_cgroup_memory_limit(&root), Some(8 << 30));
+        assert_eq!(super::read_cgroup_memory_usage(&root), Some(1 << 30));
+
+        let unlimited = cgroup_fixture(
+            "v1-unlimited",
+            &[("memory/memory.limit_in_bytes", "9223372036854771712\n")],
+        );
+        assert_eq!(super::read_cgroup_memory_limit(&unlimited), None);
+
+        let missing = cgroup_fixture("none", &[]);
+        assert_eq!(super::read_cgroup_memory_limit(&missing), None);
+
+        let _ = std::fs::remove_dir_all(root);
+        let _ = std::fs::remove_dir_all(unlimited);
+    }
+
+    #[test]
+    fn test_free_space_for_path_picks_deepest_mount() {
+        use std::path::{Path, PathBuf};
+        let mounts = vec![
+            (PathBuf::from("/"), 10),
+            (PathBuf::from("/home"), 200),
+            (PathBuf::from("/home/user/models"), 3000),
+        ];
+        let free = |p: &str| super::mount_for_path(&mounts, Path::new(p));
+        assert_eq!(free("/home/user"), Some(200));
+        assert_eq!(free("/home/user/models/blobs"), Some(3000));
+        assert_eq!(free("/var/lib"), Some(10));
+        // "/homework" must not match the "/home" mount
+        assert_eq!(free("/homework"), Some(10));
+        assert_eq!(super::mount_for_path::<u64>(&[], Path::new("/")), None);
+    }
+
+    #[test]
+    fn test_nvme_read_bandwidth_from_sysfs() {
+        assert_eq!(super::nvme_block_device("/dev/nvme0n1p2"), Some("nvme0n1"));
+        assert_eq!(super::nvme_block_device("/dev/nvme1n1"), Some("nvme1n1"));
+        assert_eq!(super::nvme_block_device("/dev/sda1"), None);
+        assert_eq!(super::nvme_block_device("/dev/mapper/root"), None);
+
+        let root = std::env::temp_dir().join(format!("llmfit-nvme-{}", std::process::id()));
+        let known = root.join("nvme0n1");
+        std::fs::create_dir_all(known.join("device")).unwrap();
+        std::fs::write(known.join("device/model"), "Samsung SSD 990 PRO 2TB\n").unwrap();
+        assert_eq!(super::nvme_bandwidth_from_sysfs(&known), Some(7.45));
+
+        // Unknown model: 80% of a PCIe 4.0 x4 link
+        let unknown = root.join("nvme1n1");
+        std::fs::create_dir_all(unknown.join("device/device")).unwrap();
+        std::fs::write(unknown.join("device/model"), "Generic NVMe 1TB\n").unwrap();
+        std::fs::write(
+            unknown.join("device/device/current_link_speed"),
+            "16.0 GT/s PCIe\n",
+        )
+        .unwrap();
+        std::fs::write(unknown.join("device/device/current_link_width"), "4\n").unwrap();
+        let gb_s = super::nvme_bandwidth_from_sysfs(&unknown).unwrap();
+        assert!((gb_s - 1.969 * 4.0 * 0.8).abs() < 1e-9);
+
+        assert_eq!(
+            super::nvme_bandwidth_from_sysfs(&root.join("nvme9n1")),
+            None
+        );
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_parse_diskutil_read_bandwidth() {
+        let apple = "   Device / Media Name:      APPLE SSD AP1024Z\n   Protocol:                 Apple Fabric\n   Solid State:              Yes\n";
+        assert_eq!(super::parse_diskutil_read_bandwidth(apple), Some(5.0));
+        let t2 = "   Device / Media Name:      APPLE SSD AP0512M\n   Protocol:                 PCI-Express\n   Solid State:              Yes\n";
+        assert_eq!(super::parse_diskutil_read_bandwidth(t2), Some(2.5));
+        let external = "   Device / Media Name:      Samsung SSD 990 PRO 2TB\n   Protocol:                 PCI-Express\n   Solid State:              Yes\n";
+        assert_eq!(super::parse_diskutil_read_bandwidth(external), Some(7.45));
+        let hdd = "   Protocol:                 SATA\n   Solid State:              No\n";
+        assert_eq!(super::parse_diskutil_read_bandwidth(hdd), None);
+    }
+
+    #[test]
+    fn test_mock_is_deterministic_and_round_trips() {
+        let specs = SystemSpecs::mock(24.0, 64.0, GpuBackend::Cuda);
+        assert_eq!(specs, SystemSpecs::mock(24.0, 64.0, GpuBackend::Cuda));
+        assert_eq!(specs.gpu_vram_gb, Some(24.0));
+        assert_eq!(specs.total_ram_gb, 64.0);
+        assert_eq!(specs.gpu_count, 1);
+        assert_eq!(specs.backend, GpuBackend::Cuda);
+
+        let json = serde_json::to_string(&specs).unwrap();
+        assert_eq!(serde_json::from_str::<SystemSpecs>(&json).unwrap(), specs);
+
+        assert!(!SystemSpecs::mock(24.0, 64.0, GpuBackend::CpuX86).has_gpu);
+    }
+
+    #[test]
+    fn test_new_manual() {
+        let specs = SystemSpecs::new_manual(64.0, Some(24.0), GpuBackend::Cuda, 2);
+        assert_eq!(specs.total_ram_gb, 64.0);
+        assert!(specs.has_gpu);
+        assert_eq!(specs.gpu_vram_gb, Some(24.0));
+        assert_eq!(specs.total_gpu_vram_gb, Some(48.0));
+        assert_eq!(specs.gpu_vram_gb_per_device, vec![24.0, 24.0]);
+        assert_eq!(specs.gpu_count, 2);
+        assert_eq!(specs.backend, GpuBackend::Cuda);
+        assert_eq!(specs.gpu_link_topology, GpuLinkTopology::PcieSli);
+
+        let cpu = SystemSpecs::new_manual(16.0, None, GpuBackend::Cuda, 1);
+        assert!(!cpu.has_gpu);
+        assert!(matches!(
+            cpu.backend,
+            GpuBackend::CpuArm | GpuBackend::CpuX86
+        ));
+
+        let mac = SystemSpecs::new_manual(36.0, Some(36.0), GpuBackend::Metal, 1);
+        assert!(mac.unified_memory);
+
+        // Every field stays adjustable without triggering detection
+        let tweaked = SystemSpecsBuilder::from_specs(mac)
+            .apple_chip_variant(Some(AppleChipVariant::Max))
+            .build();
+        assert_eq!(tweaked.apple_chip_variant, Some(AppleChipVariant::Max));
+    }
+
+    #[test]
+    fn test_gpu_backend_from_label() {
+        for backend in GpuBackend::ALL {
+            assert_eq!(GpuBackend::from_label(backend.label()), Some(backend));
+        }
+        assert_eq!(GpuBackend::from_label("cuda"), Some(GpuBackend::Cuda));
+        assert_eq!(GpuBackend::from_label("ROCM"), Some(GpuBackend::Rocm));
+        assert_eq!(
+            GpuBackend::from_label("cpu (x86)"),
+            Some(GpuBackend::CpuX86)
+        );
+        assert_eq!(GpuBackend::from_label("CpuArm"), Some(GpuBackend::CpuArm));
+        assert_eq!(GpuBackend::from_label("tpu"), None);
+    }
+
+    #[test]
+    fn test_hardware_overrides() {
+        let specs = base_specs()
+            .with_gpu_memory_override(24.0)
+            .with_backend_override(GpuBackend::Cuda)
+            .with_gpu_name_override("NVIDIA GeForce RTX 4090")
+            .with_ram_override(64.0);
+        assert_eq!(specs.total_ram_gb, 64.0);
+        assert_eq!(specs.available_ram_gb, 64.0);
+        assert_eq!(specs.backend, GpuBackend::Cuda);
+        assert_eq!(specs.gpus[0].backend, GpuBackend::Cuda);
+        assert_eq!(specs.gpu_name.as_deref(), Some("NVIDIA GeForce RTX 4090"));
+        assert_eq!(specs.gpus[0].name, "NVIDIA GeForce RTX 4090");
+        assert_eq!(specs.gpu_vram_gb, Some(24.0));
+        assert!(specs.memory_bandwidth_gbps.is_some());
+    }
+
+    #[test]
+    fn test_apply_overrides_from_vars() {
+        let vars: std::collections::HashMap<&str, &str> = [
+            ("LLMFIT_OVERRIDE_VRAM_GB", "80G"),
+            ("LLMFIT_VRAM_GB", "24"), // older name loses to LLMFIT_OVERRIDE_
+            ("LLMFIT_OVERRIDE_GPU_COUNT", "2"),
+            ("LLMFIT_BACKEND", "cuda"),
+            ("LLMFIT_OVERRIDE_CPU_CORES", "32"),
+            ("LLMFIT_OVERRIDE_RAM_GB", "256"),
+            ("LLMFIT_OVERRIDE_AVAILABLE_RAM_GB", "200"),
+            ("LLMFIT_OVERRIDE_UNIFIED_MEMORY", "maybe"),
+            ("LLMFIT_CPU_NAME", "ignored: no legacy name"),
+        ]
+        .into();
+        let mut specs = base_specs();
+        let cpu_name = specs.cpu_name.clone();
+        let warnings = specs.apply_overrides_from(|name| vars.get(name).map(|v| v.to_string()));
+
+        assert_eq!(specs.gpu_vram_gb, Some(80.0));
+        assert_eq!(specs.gpu_count, 2);
+        assert_eq!(specs.total_gpu_vram_gb, Some(160.0));
+        assert_eq!(specs.gpu_vram_gb_per_device, vec![80.0, 80.0]);
+        assert_eq!(specs.backend, GpuBackend::Cuda);
+        assert_eq!(specs.total_cpu_cores, 32);
+        assert_eq!(specs.total_ram_gb, 256.0);
+        assert_eq!(specs.available_ram_gb, 200.0);
+        assert_eq!(specs.cpu_name, cpu_name);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("LLMFIT_OVERRIDE_UNIFIED_MEMORY='maybe'"));
+    }
+
+    #[test]
+    fn test_classify_container_env() {
+        use super::classify_container_env;
+        assert_eq!(
+            classify_container_env(false, false, false, None, None, false),
+            ContainerEnv::None
+        );
+        assert_eq!(
+            classify_container_env(true, false, false, None, None, false),
+            ContainerEnv::Docker
+        );
+        assert_eq!(
+            classify_container_env(false, false, false, None, Some("0::/docker/3f2a\n"), false),
+            ContainerEnv::Docker
+        );
+        assert_eq!(
+            classify_container_env(true, false, true, None, None, false),
+            ContainerEnv::Kubernetes
+        );
+        assert_eq!(
+            classify_container_env(false, false, false, Some("systemd-nspawn"), None, false),
+            ContainerEnv::Nspawn
+        );
+        assert_eq!(
+            classify_container_env(false, true, false, Some("podman"), None, false),
+            ContainerEnv::Unknown
+        );
+        assert_eq!(
+            classify_container_env(false, false, false, None, Some("0::/\n"), true),
+            ContainerEnv::Wsl
+        );
+    }
+
+    #[test]
+    fn test_parse_jetson_model() {
+        assert_eq!(
+            SystemSpecs::parse_jetson_model("NVIDIA Jetson AGX Orin Developer Kit\0"),
+            Some("NVIDIA Jetson AGX Orin Developer Kit".to_string())
+        );
+        assert_eq!(
+            SystemSpecs::parse_jetson_model("Raspberry Pi 5 Model B Rev 1.0\0"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_merge_gpu_probes_keeps_mixed_vendor_gpus() {
+        let probes = GpuProbes {
+            nvidia: vec![GpuInfo {
+                name: "NVIDIA GeForce RTX 3060".to_string(),
+                vram_gb: Some(12.0),
+                backend: GpuBackend::Cuda,
+                count: 1,
+                unified_memory: false,
+                class: GpuClass::Discrete,
+            }],
+            amd: Some(GpuInfo {
+                name: "AMD Radeon RX 6600".to_string(),
+                vram_gb: Some(8.0),
+                backend: GpuBackend::Vulkan,
+                count: 1,
+                unified_memory: false,
+                class: GpuClass::Discrete,
+            }),
+            ..Default::default()
+        };
+        let gpus = SystemSpecs::merge_gpu_probes(probes, 32.0, "AMD Ryzen 7 5800X");
+        assert_eq!(gpus.len(), 2);
+        // Most VRAM first; that card's backend becomes `SystemSpecs::backend`
+        assert_eq!(gpus[0].backend, GpuBackend::Cuda);
+        assert_eq!(gpus[1].backend, GpuBackend::Vulkan);
+        assert_eq!(
+            SystemSpecs::primary_backend(&gpus, "AMD Ryzen 7 5800X"),
+            GpuBackend::Cuda
+        );
+    }
+
+    #[test]
+    fn test_merge_gpu_probes_jetson_without_nvidia_smi() {
+        let probes = GpuProbes {
+            jetson: Some("NVIDIA Jetson Orin Nano".to_string()),
+            ..Default::default()
+        };
+        let gpus = SystemSpecs::merge_gpu_probes(probes, 8.0, "ARMv8 Processor");
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].backend, GpuBackend::Cuda);
+        assert!(gpus[0].unified_memory);
+        assert_eq!(gpus[0].vram_gb, Some(8.0));
+    }
+
+    #[test]
+    fn test_jetson_variant_from_board_name() {
+        use super::JetsonVariant;
+        let cases = [
+            ("NVIDIA Jetson AGX Orin Developer Kit", JetsonVariant::Orin),
+            (
+                "NVIDIA Jetson Orin NX Engineering Reference Developer Kit",
+                JetsonVariant::OrinNx,
+            ),
+            (
+                "NVIDIA Jetson Orin Nano Developer Kit",
+                JetsonVariant::OrinNano,
+            ),
+            ("NVIDIA Jetson Xavier NX Developer Kit", JetsonVariant::Nx),
+            ("Jetson-AGX", JetsonVariant::Agx),
+            ("NVIDIA Jetson AGX Xavier", JetsonVariant::Agx),
+            ("NVIDIA Jetson Nano Developer Kit", JetsonVariant::Nano),
+        ];
+        for (board, variant) in cases {
+            assert_eq!(
+                JetsonVariant::from_board_name(board),
+                Some(variant),
+                "{}",
+                board
+            );
+        }
+        assert_eq!(JetsonVariant::from_board_name("NVIDIA Jetson"), None);
+        assert_eq!(
+            JetsonVariant::from_board_name("Raspberry Pi 5 Model B"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_gpu_temperature() {
+        let nvidia = "71, NVIDIA GeForce RTX 3090\n84, NVIDIA GeForce RTX 3090\n40, NVIDIA T400\n";
+        assert_eq!(
+            SystemSpecs::parse_nvidia_smi_temperature(nvidia, "NVIDIA GeForce RTX 3090"),
+            Some(84.0)
+        );
+        assert_eq!(
+            SystemSpecs::parse_nvidia_smi_temperature("[N/A], NVIDIA A100\n", "NVIDIA A100"),
+            None
+        );
+
+        let rocm = "============ ROCm System Management Interface ============\n\
+                    ====================== Temperature =======================\n\
+                    GPU[0]\t\t: Temperature (Sensor edge) (C): 45.0\n\
+                    GPU[0]\t\t: Temperature (Sensor junction) (C): 48.0\n\
+                    GPU[1]\t\t: Temperature (Sensor edge) (C): 62.0\n";
+        assert_eq!(SystemSpecs::parse_rocm_smi_temperature(rocm), Some(62.0));
+
+        let mut specs = base_specs();
+        assert!(!specs.thermal_throttle_risk());
+        specs.gpu_temperature_c = Some(THERMAL_THROTTLE_WARN_C);
+        assert!(specs.thermal_throttle_risk());
+    }
+
+    #[test]
+    fn test_parse_windows_driver_version() {
+        let text = "Intel(R) UHD Graphics 770|31.0.101.4502\r\nAMD Radeon RX 7900 XTX|31.0.24027.1012\r\nMicrosoft Basic Display Adapter|\r\n";
+        assert_eq!(
+            SystemSpecs::parse_windows_driver_version(text, "AMD Radeon RX 7900 XTX").as_deref(),
+            Some("31.0.24027.1012")
+        );
+        assert_eq!(
+            SystemSpecs::parse_windows_driver_version(text, "Microsoft Basic Display Adapter"),
+            None
+        );
+        assert_eq!(
+            SystemSpecs::parse_windows_driver_version(text, "NVIDIA GeForce RTX 4090"),
+            None
+        );
     }
-    if lower.contains("6900") {
-        return 16.0;
+
+    #[test]
+    fn test_parse_rocm_version() {
+        assert_eq!(
+            SystemSpecs::parse_rocm_version_file("6.1.2-119\n"),
+            Some("6.1.2".to_string())
+        );
+        assert_eq!(SystemSpecs::parse_rocm_version_file(""), None);
+        let smi =
+            "ROCM-SMI version: 2.3.0+6c5d7d2\nROCM-SMI-LIB version: 7.3.0\nROCm version: 6.2.0\n";
+        assert_eq!(
+            SystemSpecs::parse_rocm_smi_version(smi),
+            Some("6.2.0".to_string())
+        );
+        assert_eq!(
+            SystemSpecs::parse_rocm_smi_version("ROCM-SMI version: 2.3.0\n"),
+            None
+        );
     }
-    if lower.contains("6800") {
-        return 16.0;
+
+    #[test]
+    fn test_supports_flash_attention_2() {
+        assert_eq!(super::parse_compute_capability("8.6"), Some((8, 6)));
+        assert_eq!(super::parse_compute_capability("12.0"), Some((12, 0)));
+        assert_eq!(super::parse_compute_capability("[N/A]"), None);
+
+        let mut specs = base_specs();
+        assert!(!specs.supports_flash_attention_2());
+        specs.cuda_compute_capability = Some((7, 5));
+        assert!(!specs.supports_flash_attention_2());
+        specs.cuda_compute_capability = Some((8, 0));
+        assert!(specs.supports_flash_attention_2());
+        specs.cuda_compute_capability = Some((9, 0));
+        assert!(specs.supports_flash_attention_2());
+        assert_eq!(specs.compute_capability_label().as_deref(), Some("9.0"));
     }
-    if lower.contains("6750") {
-        return 12.0;
+
+    #[test]
+    fn test_parse_nvidia_topo_matrix() {
+        let nvlink = "\tGPU0\tGPU1\tCPU Affinity\tNUMA Affinity\nGPU0\t X \tNV12\t0-63\t0\nGPU1\tNV12\t X \t0-63\t0\n\nLegend:\n  X    = Self\n  NV#  = Connection traversing a bonded set of # NVLinks\n";
+        assert_eq!(
+            SystemSpecs::parse_nvidia_topo_all_nvlink(nvlink),
+            Some(true)
+        );
+
+        let pcie = "\tGPU0\tGPU1\tCPU Affinity\nGPU0\t X \tPHB\t0-15\nGPU1\tPHB\t X \t0-15\n";
+        assert_eq!(SystemSpecs::parse_nvidia_topo_all_nvlink(pcie), Some(false));
+
+        let single = "\tGPU0\tCPU Affinity\nGPU0\t X \t0-15\n";
+        assert_eq!(SystemSpecs::parse_nvidia_topo_all_nvlink(single), None);
     }
-    if lower.contains("6700") {
-        return 12.0;
+
+    #[test]
+    fn test_classify_amd_sysfs_memory() {
+        const MB: u64 = 1024 * 1024;
+        // Radeon 780M: 512 MB carveout + 15.5 GB GTT
+        assert_eq!(
+            SystemSpecs::classify_amd_sysfs_memory(Some(512 * MB), Some(15872 * MB)),
+            (Some(16.0), true)
+        );
+        // APU without a GTT figure: pool is filled from RAM later
+        assert_eq!(
+            SystemSpecs::classify_amd_sysfs_memory(Some(512 * MB), None),
+            (None, true)
+        );
+        // RX 7900 XTX: real VRAM, GTT ignored
+        assert_eq!(
+            SystemSpecs::classify_amd_sysfs_memory(Some(24576 * MB), Some(32768 * MB)),
+            (Some(24.0), false)
+        );
+        assert_eq!(
+            SystemSpecs::classify_amd_sysfs_memory(None, None),
+            (None, false)
+        );
     }
-    if lower.contains("6650") {
-        return 8.0;
+
+    #[test]
+    fn test_merge_fills_amd_apu_pool_from_ram() {
+        let probes = GpuProbes {
+            amd: Some(GpuInfo {
+                name: "AMD Radeon 780M".to_string(),
+                vram_gb: None,
+                backend: GpuBackend::Vulkan,
+                count: 1,
+                unified_memory: true,
+                class: GpuClass::Integrated,
+            }),
+            ..Default::default()
+        };
+        let gpus = SystemSpecs::merge_gpu_probes(probes, 32.0, "AMD Ryzen 7 7840U");
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].vram_gb, Some(16.0));
+        assert!(gpus[0].unified_memory);
+        assert_eq!(gpus[0].class, GpuClass::Integrated);
     }
-    if lower.contains("6600") {
-        return 8.0;
+
+    #[test]
+    fn test_gpu_class_from_name() {
+        for name in [
+            "AMD Radeon(TM) Graphics",
+            "AMD Radeon RX Vega 10 Graphics",
+            "AMD Radeon 780M",
+            "Intel(R) UHD Graphics 630",
+            "Intel(R) Arc(TM) Graphics",
+            "Mali-G710",
+        ] {
+            assert_eq!(GpuClass::from_name(name), GpuClass::Integrated, "{}", name);
+        }
+        for name in [
+            "AMD Radeon RX 6600",
+            "Radeon RX Vega 64",
+            "AMD Radeon Pro W7900",
+            "Intel(R) Arc(TM) A770 Graphics",
+            "NVIDIA GeForce RTX 4090",
+        ] {
+            assert_eq!(GpuClass::from_name(name), GpuClass::Discrete, "{}", name);
+        }
+        assert_eq!(GpuClass::from_name("AMD Radeon 8060S"), GpuClass::Unified);
+
+        // Profiles saved before the field existed read back as discrete
+        let gpu: GpuInfo = serde_json::from_str(
+            r#"{"name":"RTX 3090","vram_gb":24.0,"backend":"Cuda","count":1,"unified_memory":false}"#,
+        )
+        .unwrap();
+        assert_eq!(gpu.class, GpuClass::Discrete);
     }
-    if lower.contains("6500") {
-        return 4.0;
+
+    #[test]
+    fn test_parse_intel_device_memory() {
+        let sycl = "Platforms: 2\nPlatform [#1]:\n    Name     : Intel(R) OpenCL\n        Device [#0]:\n        Type       : cpu\n        Name       : 13th Gen Intel(R) Core(TM) i7-13700K\n        Global Memory Size : 67108864000\n\
+Platform [#2]:\n    Name     : Intel(R) Level-Zero\n        Device [#0]:\n        Type       : gpu\n        Name       : Intel(R) Arc(TM) A770 Graphics\n        Global Memory Size : 17179869184\n";
+        assert_eq!(SystemSpecs::parse_intel_device_memory(sycl), Some(16.0));
+
+        let xpu = "+-----------+--------------------------------------------------+\n\
+                   | Device ID | Device Information                               |\n\
+                   | 0         | Device Name: Intel(R) Arc(TM) A770 Graphics      |\n\
+                   |           | Vendor Name: Intel(R) Corporation                |\n\
+                   |           | Memory Physical Size: 16384.00 MiB               |\n";
+        assert_eq!(SystemSpecs::parse_intel_device_memory(xpu), Some(16.0));
+
+        assert_eq!(
+            SystemSpecs::parse_intel_device_memory(
+                "Name : Intel(R) UHD Graphics 770\nGlobal Memory Size : 1000\n"
+            ),
+            None
+        );
     }
-    // AMD RX 5000 series
-    if lower.contains("5700 xt") {
-        return 8.0;
+
+    #[test]
+    fn test_intel_arc_device_ids() {
+        assert_eq!(
+            super::intel_arc_device(0x56a0),
+            Some(("Intel Arc A770", 16.0))
+        );
+        assert_eq!(
+            super::intel_arc_device(0xe20b),
+            Some(("Intel Arc B580", 12.0))
+        );
+        // UHD 770 iGPU
+        assert_eq!(super::intel_arc_device(0xa780), None);
+
+        let line = "03:00.0 VGA compatible controller [0300]: Intel Corporation DG2 [Arc A380] [8086:56a5] (rev 05)";
+        assert_eq!(super::parse_lspci_intel_device_id(line), Some(0x56a5));
+        assert_eq!(
+            super::parse_lspci_intel_device_id("01:00.0 VGA [0300]: NVIDIA [10de:2684]"),
+            None
+        );
     }
-    if lower.contains("5700") {
-        return 8.0;
+
+    #[test]
+    fn test_parse_nvlink_status() {
+        let active = "GPU 0: NVIDIA GeForce RTX 3090 (UUID: GPU-a)\n\t Link 0: 14.062 GB/s\n\t Link 1: 14.062 GB/s\n\
+                      GPU 1: NVIDIA GeForce RTX 3090 (UUID: GPU-b)\n\t Link 0: 14.062 GB/s\n\t Link 1: <inactive>\n";
+        assert!(SystemSpecs::parse_nvlink_status(active));
+
+        // Command succeeds on bridge-less cards but every link is inactive
+        let inactive = "GPU 0: NVIDIA GeForce RTX 4090 (UUID: GPU-a)\n\t Link 0: <inactive>\n\
+                        GPU 1: NVIDIA GeForce RTX 4090 (UUID: GPU-b)\n\t Link 0: <inactive>\n";
+        assert!(!SystemSpecs::parse_nvlink_status(inactive));
+
+        // One card bridged to nothing doesn't count
+        let one = "GPU 0: NVIDIA A100 (UUID: GPU-a)\n\t Link 0: 25 GB/s\n";
+        assert!(!SystemSpecs::parse_nvlink_status(one));
+        assert!(!SystemSpecs::parse_nvlink_status(""));
     }
-    if lower.contains("5600") {
-        return 6.0;
+
+    #[test]
+    fn test_vram_headroom_and_contention() {
+        let mut specs = SystemSpecs::new_manual(32.0, Some(24.0), GpuBackend::Cuda, 1);
+        assert_eq!(specs.vram_headroom_gb(1.0), None);
+        assert!(!specs.vram_contended());
+
+        specs.gpu_vram_available_gb = Some(23.0);
+        specs.gpu_vram_used_gb = Some(1.0);
+        assert_eq!(specs.vram_headroom_gb(1.0), Some(22.0));
+        assert!(!specs.vram_contended());
+
+        // A 3 GB desktop stays under the threshold, 6 GB doesn't
+        specs.gpu_vram_available_gb = Some(21.0);
+        specs.gpu_vram_used_gb = Some(3.0);
+        assert!(!specs.vram_contended());
+        specs.gpu_vram_available_gb = Some(18.0);
+        specs.gpu_vram_used_gb = Some(6.0);
+        assert!(specs.vram_contended());
+        assert_eq!(specs.vram_headroom_gb(30.0), Some(0.0));
+        // 24 GB less the 1 GB CUDA overhead and the 6 GB in use
+        assert_eq!(specs.available_gpu_memory_gb(), Some(17.0));
     }
-    if lower.contains("5500") {
-        return 4.0;
+
+    #[test]
+    fn test_pooled_vram_reserves_per_card_without_nvlink() {
+        let mut specs = SystemSpecs::new_manual(64.0, Some(24.0), GpuBackend::Cuda, 2);
+        specs.gpu_vram_gb_per_device = vec![24.0, 24.0];
+        assert_eq!(specs.pooled_vram_gb(), Some(47.5));
+        specs.gpu_link_topology = GpuLinkTopology::NvlinkPod;
+        assert_eq!(specs.pooled_vram_gb(), Some(48.0));
+
+        let single = SystemSpecs::new_manual(64.0, Some(24.0), GpuBackend::Cuda, 1);
+        assert_eq!(single.pooled_vram_gb(), Some(24.0));
     }
-    // AMD Radeon 8000 series (Ryzen AI MAX / Strix Halo integrated)
-    // These are unified memory APUs; VRAM = system RAM in practice,
-    // but this fallback gives a reasonable discrete estimate for name-only detection.
-    if lower.contains("8060s") {
-        return 32.0;
+
+    #[test]
+    fn test_usable_gpu_memory_reserves_runtime_overhead() {
+        let cuda = SystemSpecs::new_manual(64.0, Some(24.0), GpuBackend::Cuda, 1);
+        assert_eq!(cuda.runtime_overhead_gb(), 1.0);
+        assert_eq!(cuda.usable_gpu_memory_gb(), Some(23.0));
+
+        let metal = SystemSpecs::new_manual(64.0, Some(48.0), GpuBackend::Metal, 1);
+        assert_eq!(metal.usable_gpu_memory_gb(), Some(47.75));
+
+        let cpu = SystemSpecs::new_manual(64.0, None, GpuBackend::CpuX86, 0);
+        assert_eq!(cpu.runtime_overhead_gb(), 0.0);
+        assert_eq!(cpu.usable_gpu_memory_gb(), None);
+
+        let tuned = SystemSpecsBuilder::from_specs(cuda)
+            .overhead_gb(Some(2.5))
+            .build();
+        assert_eq!(tuned.usable_gpu_memory_gb(), Some(21.5));
     }
-    if lower.contains("8050s") {
-        return 24.0;
+
+    #[test]
+    fn test_link_topology_for_mixed_and_single_gpus() {
+        let gpus = SystemSpecs::parse_nvidia_smi_list(
+            "24564, NVIDIA GeForce RTX 4090\n12288, NVIDIA GeForce RTX 3060\n",
+        );
+        assert_eq!(
+            SystemSpecs::detect_link_topology(&gpus),
+            GpuLinkTopology::Independent
+        );
+        assert_eq!(
+            SystemSpecs::detect_link_topology(&gpus[..1]),
+            GpuLinkTopology::SingleGpu
+        );
+        assert_eq!(
+            SystemSpecs::detect_link_topology(&[]),
+            GpuLinkTopology::SingleGpu
+        );
     }
-    if lower.contains("8060") && !lower.contains("8060s") {
-        return 16.0;
+
+    #[test]
+    fn test_parse_clinfo_gpu_name_skips_cpu_devices() {
+        let text = "Platform #0: Portable Computing Language\n `-- Device #0: cpu-haswell-Intel(R) Core(TM) i7\nPlatform #1: ARM Platform\n `-- Device #0: Mali-G610 r0p0\n";
+        assert_eq!(
+            SystemSpecs::parse_clinfo_gpu_name(text),
+            Some("Mali-G610 r0p0".to_string())
+        );
+        assert_eq!(
+            SystemSpecs::parse_clinfo_gpu_name(
+                "Platform #0: Portable Computing Language\n `-- Device #0: pthread-Intel(R) Core(TM) i7\n"
+            ),
+            None
+        );
     }
-    if lower.contains("8050") && !lower.contains("8050s") {
-        return 12.0;
+
+    #[test]
+    fn test_infer_gpu_backend_directml_on_windows() {
+        assert_eq!(
+            SystemSpecs::infer_gpu_backend_with("AMD Radeon RX 7900 XTX", false, true),
+            GpuBackend::DirectML
+        );
+        assert_eq!(
+            SystemSpecs::infer_gpu_backend_with("Intel(R) Arc(TM) A770 Graphics", false, true),
+            GpuBackend::DirectML
+        );
+        assert_eq!(
+            SystemSpecs::infer_gpu_backend_with("NVIDIA GeForce RTX 4090", false, true),
+            GpuBackend::Cuda
+        );
+        assert_eq!(
+            SystemSpecs::infer_gpu_backend_with("Intel(R) Arc(TM) A770 Graphics", false, false),
+            GpuBackend::Sycl
+        );
     }
-    // AMD Radeon 800M series (Ryzen AI 9 / Strix Point integrated)
-    if lower.contains("890m") {
-        return 16.0;
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn test_windows_wmi_amd_gpu_uses_directml() {
+        let gpus = SystemSpecs::parse_windows_gpu_list("AMD Radeon RX 7800 XT|4293918720\n");
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].backend, GpuBackend::DirectML);
+        assert_eq!(gpus[0].backend.label(), "DirectML");
     }
-    if lower.contains("880m") {
-        return 12.0;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_with_timeout_returns_output() {
+        let output = run_with_timeout(
+            std::process::Command::new("sh").args(["-c", "echo hello"]),
+            std::time::Duration::from_secs(5),
+        )
+        .expect("sh should run");
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "hello");
     }
-    if lower.contains("870m") {
-        return 8.0;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_with_timeout_kills_hung_command() {
+        let start = std::time::Instant::now();
+        let output = run_with_timeout(
+            std::process::Command::new("sleep").arg("10"),
+            std::time::Duration::from_millis(100),
+        );
+        assert!(output.is_none());
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
     }
-    if lower.contains("860m") {
-        return 8.0;
+
+    #[test]
+    fn test_run_with_timeout_missing_binary_is_none() {
+        let output = run_with_timeout(
+            &mut std::process::Command::new("llmfit-no-such-binary"),
+            std::time::Duration::from_secs(1),
+        );
+        assert!(output.is_none());
     }
 
-    // Integrated GPUs (APU iGPUs) — must check before generic fallbacks
-    // APU names like "AMD Radeon(TM) Graphics" or "Radeon Graphics" without
-    // a discrete model number (RX/HD/R5/R7/R9) have very limited dedicated VRAM.
-    if (lower.contains("radeon") || lower.contains("amd"))
-        && !lower.contains("rx ")
-        && !lower.contains("hd ")
-        && !lower.contains(" r5 ")
-        && !lower.contains(" r7 ")
-        && !lower.contains(" r9 ")
-        && !lower.contains("8060")
-        && !lower.contains("8050")
-        && (lower.contains("graphics") || lower.contains("igpu"))
-    {
-        return 0.5;
+    #[test]
+    fn test_detect_ram_only_skips_gpu_fields() {
+        let specs = SystemSpecs::detect_ram_only();
+
+        assert!(!specs.has_gpu);
+        assert!(specs.gpus.is_empty());
+        assert_eq!(specs.gpu_count, 0);
+        assert!(matches!(
+            specs.backend,
+            GpuBackend::CpuArm | GpuBackend::CpuX86
+        ));
     }
 
-    // Generic fallbacks
-    if lower.contains("rtx") {
-        return 8.0;
+    #[test]
+    fn test_builder_overrides_only_set_fields() {
+        let specs = SystemSpecsBuilder::from_specs(base_specs())
+            .available_ram_gb(60.0)
+            .backend(GpuBackend::Cuda)
+            .gpu_count(2)
+            .gpu_vram_gb(Some(24.0))
+            .build();
+
+        assert_eq!(specs.total_ram_gb, 32.0);
+        assert_eq!(specs.available_ram_gb, 60.0);
+        assert_eq!(specs.backend, GpuBackend::Cuda);
+        assert!(specs.has_gpu, "overriding VRAM implies a GPU");
+        assert_eq!(specs.total_gpu_vram_gb, Some(48.0));
     }
-    if lower.contains("gtx") {
-        return 4.0;
+
+    #[test]
+    fn test_builder_keeps_gpu_list_in_step() {
+        let detected = SystemSpecs::new_manual(64.0, Some(24.0), GpuBackend::Cuda, 1);
+        let specs = SystemSpecsBuilder::from_specs(detected)
+            .gpu_vram_gb(Some(80.0))
+            .gpu_count(2)
+            .build();
+
+        assert_eq!(specs.gpu_vram_gb, Some(80.0));
+        assert_eq!(specs.gpus[0].vram_gb, Some(80.0));
+        assert_eq!(specs.gpus[0].count, 2);
+        assert_eq!(specs.gpu_vram_gb_per_device, vec![80.0, 80.0]);
+        assert_eq!(specs.total_gpu_vram_gb, Some(160.0));
     }
-    if lower.contains("rx ") || lower.contains("radeon") {
-        return 8.0;
+
+    #[test]
+    fn test_builder_explicit_total_wins() {
+        let specs = SystemSpecsBuilder::from_specs(base_specs())
+            .gpu_vram_gb(Some(24.0))
+            .total_gpu_vram_gb(Some(20.0))
+            .has_gpu(false)
+            .build();
+
+        assert_eq!(specs.total_gpu_vram_gb, Some(20.0));
+        assert!(!specs.has_gpu);
     }
-    0.0
-}
 
-#[cfg(test)]
-mod tests {
-    use super::SystemSpecs;
+    #[test]
+    fn test_split_nvidia_usage_columns() {
+        let text = "20000, 4564, [N/A], 24564, NVIDIA GeForce RTX 4090\n8000, 4288, [N/A], 12288, NVIDIA GeForce RTX 3060\n";
+        let (rest, usage) = super::split_nvidia_usage_columns(text, 2);
+        assert_eq!(SystemSpecs::parse_nvidia_smi_extended(&rest).len(), 2);
+        let (free_gb, used_gb) =
+            SystemSpecs::parse_nvidia_smi_usage(&usage, "NVIDIA GeForce RTX 3060").unwrap();
+        assert!(
+            (free_gb - 7.81).abs() < 0.01,
+            "unexpected free VRAM: {free_gb}"
+        );
+        assert!(
+            (used_gb - 4.19).abs() < 0.01,
+            "unexpected used VRAM: {used_gb}"
+        );
+
+        let (rest, usage) = super::split_nvidia_usage_columns("4096, 12288, 16384, Tesla T4\n", 1);
+        assert_eq!(
+            SystemSpecs::parse_nvidia_smi_list(&rest)[0].vram_gb,
+            Some(16.0)
+        );
+        assert_eq!(
+            SystemSpecs::parse_nvidia_smi_usage(&usage, "Tesla T4"),
+            Some((4.0, 12.0))
+        );
+    }
 
     #[test]
-    fn test_parse_nvidia_smi_does_not_sum_multi_gpu_vram() {
-        let text = "24564, NVIDIA GeForce RTX 4090\n24564, NVIDIA GeForce RTX 4090\n";
-        let gpus = SystemSpecs::parse_nvidia_smi_list(text);
+    fn test_parse_nvidia_smi_usage_sums_matching_cards() {
+        let text = "20000, 4564, NVIDIA GeForce RTX 4090\n4096, 20468, NVIDIA GeForce RTX 4090\n8000, 4288, NVIDIA GeForce RTX 3060\n";
+        let (free, used) = SystemSpecs::parse_nvidia_smi_usage(text, "NVIDIA GeForce RTX 4090")
+            .expect("VRAM usage should parse");
+        assert!((free - 23.53).abs() < 0.01, "unexpected free VRAM: {free}");
+        assert!((used - 24.44).abs() < 0.01, "unexpected used VRAM: {used}");
+        assert_eq!(SystemSpecs::parse_nvidia_smi_usage(text, "Tesla T4"), None);
+    }
 
-        assert_eq!(gpus.len(), 1);
-        assert_eq!(gpus[0].count, 2);
-        let vram = gpus[0]
-            .vram_gb
-            .expect("VRAM should be parsed for RTX 4090 entries");
-        // 24564 MiB ~= 23.99 GiB; must stay single-card VRAM, not 2x summed.
-        assert!(vram > 23.0 && vram < 25.0, "unexpected VRAM value: {vram}");
+    #[test]
+    fn test_parse_rocm_smi_usage_subtracts_used() {
+        let text = "GPU[0]\t\t: VRAM Total Memory (B): 25753026560\nGPU[0]\t\t: VRAM Total Used Memory (B): 4294967296\n";
+        let (free, used) =
+            SystemSpecs::parse_rocm_smi_usage(text).expect("VRAM usage should parse");
+        assert!((free - 19.98).abs() < 0.01, "unexpected free VRAM: {free}");
+        assert_eq!(used, 4.0);
     }
 
     #[test]
-    fn test_parse_nvidia_smi_keeps_distinct_models() {
-        let text = "24564, NVIDIA GeForce RTX 4090\n16376, NVIDIA GeForce RTX 4080\n";
-        let gpus = SystemSpecs::parse_nvidia_smi_list(text);
+    fn test_per_device_vram_expands_counts_and_mixed_models() {
+        let mut gpus = SystemSpecs::parse_nvidia_smi_list(
+            "12288, NVIDIA GeForce RTX 3060\n12288, NVIDIA GeForce RTX 3060\n24564, NVIDIA GeForce RTX 4090\n",
+        );
+        gpus.push(GpuInfo {
+            name: "Unknown".to_string(),
+            vram_gb: None,
+            backend: GpuBackend::Vulkan,
+            count: 1,
+            unified_memory: false,
+            class: GpuClass::Discrete,
+        });
+        let per_device = SystemSpecs::per_device_vram(&gpus);
 
-        assert_eq!(gpus.len(), 2);
-        assert!(gpus.iter().any(|g| g.name.contains("4090") && g.count == 1));
-        assert!(gpus.iter().any(|g| g.name.contains("4080") && g.count == 1));
+        assert_eq!(per_device.len(), 3);
+        assert!(per_device[0] > 23.0, "largest card first: {per_device:?}");
+        assert_eq!(per_device[1], 12.0);
+        assert_eq!(per_device[2], 12.0);
     }
 
     #[test]
-    fn test_parse_nvidia_smi_gb10_gets_vram_estimate() {
-        // DGX Spark reports GB10 with 0 VRAM from nvidia-smi
-        let text = "0, NVIDIA GB10\n";
-        let gpus = SystemSpecs::parse_nvidia_smi_list(text);
+    fn test_system_specs_serde_round_trip_all_backends() {
+        let backends = [
+            GpuBackend::Cuda,
+            GpuBackend::Metal,
+            GpuBackend::Rocm,
+            GpuBackend::Vulkan,
+            GpuBackend::Sycl,
+            GpuBackend::OpenCL,
+            GpuBackend::DirectML,
+            GpuBackend::CpuArm,
+            GpuBackend::CpuX86,
+            GpuBackend::Ascend,
+        ];
+        for backend in backends {
+            let specs = SystemSpecs {
+                total_ram_gb: 64.0,
+                available_ram_gb: 48.5,
+                total_cpu_cores: 16,
+                cpu_name: "Test CPU".to_string(),
+                has_gpu: true,
+                gpu_vram_gb: Some(24.0),
+                total_gpu_vram_gb: Some(48.0),
+                gpu_vram_gb_per_device: vec![24.0, 24.0],
+                gpu_vram_available_gb: Some(40.25),
+                gpu_vram_used_gb: Some(7.75),
+                gpu_name: Some("Test GPU".to_string()),
+                gpu_count: 2,
+                unified_memory: backend == GpuBackend::Metal,
+                backend,
+                gpus: vec![GpuInfo {
+                    name: "Test GPU".to_string(),
+                    vram_gb: Some(24.0),
+                    backend,
+                    count: 2,
+                    unified_memory: backend == GpuBackend::Metal,
+                    class: if backend == GpuBackend::Metal {
+                        GpuClass::Unified
+                    } else {
+                        GpuClass::Discrete
+                    },
+                }],
+                apple_chip_variant: if backend == GpuBackend::Metal {
+                    Some(AppleChipVariant::Max)
+                } else {
+                    None
+                },
+                memory_bandwidth_gbps: None,
+                cpu_features: Vec::new(),
+                gpu_link_topology: GpuLinkTopology::SingleGpu,
+                cuda_compute_capability: None,
+                rocm_version: None,
+                gpu_driver_version: None,
+                gpu_temperature_c: None,
+                container_env: ContainerEnv::None,
+                swap_total_gb: 0.0,
+                swap_available_gb: 0.0,
+                disk_available_gb: None,
+                gpu_core_count: None,
+                pcie_gen: None,
+                pcie_width: None,
+                numa_nodes: 1,
+                overhead_gb: None,
+                nvme_read_bandwidth_gb_s: None,
+                mlock_limit_gb: None,
+                jetson_variant: None,
+            };
 
-        assert_eq!(gpus.len(), 1);
-        assert!(gpus[0].name.contains("GB10"));
-        // estimate_vram_from_name should kick in and return 128GB
-        let vram = gpus[0].vram_gb.expect("GB10 should have estimated VRAM");
-        assert!(vram > 100.0, "GB10 VRAM should be ~128GB, got {vram}");
+            let json = serde_json::to_string(&specs).expect("specs should serialize");
+            let back: SystemSpecs = serde_json::from_str(&json).expect("specs should deserialize");
+            assert_eq!(back, specs, "round trip failed for {backend:?}");
+
+            let backend_json = serde_json::to_string(&backend).expect("backend should serialize");
+            let backend_back: GpuBackend =
+                serde_json::from_str(&backend_json).expect("backend should deserialize");
+            assert_eq!(backend_back, backend);
+        }
     }
 
     #[test]
-    fn test_estimate_vram_gb10() {
-        assert_eq!(super::estimate_vram_from_name("NVIDIA GB10"), 128.0);
-        assert_eq!(super::estimate_vram_from_name("NVIDIA GB20"), 128.0);
+    fn test_parse_apple_chip_variant_from_profiler() {
+        let text = "Hardware:\n\n    Hardware Overview:\n\n      Model Name: MacBook Pro\n      Chip: Apple M3 Max\n      Total Number of Cores: 16\n";
+        assert_eq!(
+            SystemSpecs::parse_apple_chip_variant(text),
+            Some(AppleChipVariant::Max)
+        );
+        assert_eq!(
+            SystemSpecs::parse_apple_chip_variant("Processor Name: Intel Core i9"),
+            None
+        );
     }
 
     #[test]
-    fn test_parse_extended_discrete_gpu_not_unified() {
-        // Discrete GPU: addressing_mode is "None", VRAM is reported normally
-        let text = "None, 24564, NVIDIA GeForce RTX 4090\n";
-        let gpus = SystemSpecs::parse_nvidia_smi_extended(text);
+    fn test_parse_apple_gpu_core_count() {
+        let text = "Graphics/Displays:\n\n    Apple M3 Max:\n\n      Chipset Model: Apple M3 Max\n      Type: GPU\n      Bus: Built-In\n      Total Number of Cores: 40\n      Vendor: Apple (0x106b)\n";
+        assert_eq!(SystemSpecs::parse_apple_gpu(text), (true, Some(40)));
 
-        assert_eq!(gpus.len(), 1);
-        assert_eq!(gpus[0].name, "NVIDIA GeForce RTX 4090");
-        assert!(
-            !gpus[0].unified_memory,
-            "discrete GPU should not be unified"
+        let no_cores = "      Chipset Model: Apple M1\n      Type: GPU\n";
+        assert_eq!(SystemSpecs::parse_apple_gpu(no_cores), (true, None));
+
+        // Intel Mac with a discrete Radeon
+        let intel = "      Chipset Model: AMD Radeon Pro 5500M\n      Total Number of Cores: 24\n";
+        assert_eq!(SystemSpecs::parse_apple_gpu(intel), (false, None));
+    }
+
+    #[test]
+    fn test_parse_egpu_macos() {
+        let thunderbolt = "Thunderbolt:\n\n    Thunderbolt Bus 0:\n\n      Vendor Name: Apple Inc.\n      Device Name: MacBook Pro\n\n        Core X:\n\n          Vendor Name: Razer\n          Device Name: Core X\n          Mode: Thunderbolt 3\n";
+        assert_eq!(
+            SystemSpecs::parse_thunderbolt_egpu(thunderbolt).as_deref(),
+            Some("Core X")
         );
-        let vram = gpus[0].vram_gb.expect("VRAM should be present");
-        assert!(vram > 23.0 && vram < 25.0, "unexpected VRAM: {vram}");
+        let dock = "      Device Name: MacBook Pro\n          Device Name: CalDigit TS3 Plus\n";
+        assert_eq!(SystemSpecs::parse_thunderbolt_egpu(dock), None);
+        assert_eq!(
+            SystemSpecs::parse_thunderbolt_egpu("          Device Name: Node Pro\n").as_deref(),
+            Some("Node Pro")
+        );
+
+        // 16" MacBook Pro: Intel iGPU, built-in 5500M, RX 6800 XT in the enclosure
+        let displays = "Graphics/Displays:\n\n    Intel UHD Graphics 630:\n\n      Chipset Model: Intel UHD Graphics 630\n      Type: GPU\n      Bus: Built-In\n      VRAM (Dynamic, Max): 1536 MB\n\n    AMD Radeon Pro 5500M:\n\n      Chipset Model: AMD Radeon Pro 5500M\n      Type: GPU\n      Bus: PCIe\n      VRAM (Total): 8 GB\n\n    Radeon RX 6800 XT:\n\n      Chipset Model: Radeon RX 6800 XT\n      Type: External GPU\n      Bus: PCIe\n      VRAM (Total): 16 GB\n";
+        let egpu = SystemSpecs::parse_egpu_display(displays).unwrap();
+        assert_eq!(egpu.name, "Radeon RX 6800 XT");
+        assert_eq!(egpu.vram_gb, Some(16.0));
+        assert_eq!(egpu.backend, GpuBackend::Metal);
+        assert!(!egpu.unified_memory);
+        assert_eq!(egpu.class, GpuClass::Discrete);
     }
 
     #[test]
-    fn test_parse_extended_tegra_unified_memory() {
-        // NVIDIA Tegra / Grace Blackwell: ATS addressing, VRAM is [N/A]
-        // On a real system, /proc/meminfo would provide the fallback.
-        // In tests, /proc/meminfo may or may not exist.
-        let text = "ATS, [N/A], NVIDIA Thor\n";
-        let gpus = SystemSpecs::parse_nvidia_smi_extended(text);
+    fn test_merge_prefers_egpu_over_apple_path() {
+        let egpu = GpuInfo {
+            name: "Radeon RX 6800 XT".to_string(),
+            vram_gb: Some(16.0),
+            backend: GpuBackend::Metal,
+            count: 1,
+            unified_memory: false,
+            class: GpuClass::Discrete,
+        };
+        let probes = GpuProbes {
+            apple: true,
+            egpu: Some(egpu.clone()),
+            ..Default::default()
+        };
+        let gpus = SystemSpecs::merge_gpu_probes(probes, 64.0, "Intel(R) Core(TM) i9-9980HK");
+        assert_eq!(gpus, vec![egpu]);
+    }
 
-        assert_eq!(gpus.len(), 1);
-        assert_eq!(gpus[0].name, "NVIDIA Thor");
-        assert!(gpus[0].unified_memory, "ATS should set unified_memory=true");
-        // VRAM comes from /proc/meminfo; if unavailable, it's None
-        // (on Linux test machines it will be Some, on macOS CI it will be None)
+    #[test]
+    fn test_apple_chip_variant_from_chip_name() {
+        assert_eq!(
+            AppleChipVariant::from_chip_name("Apple M1"),
+            Some(AppleChipVariant::Base)
+        );
+        assert_eq!(
+            AppleChipVariant::from_chip_name("Apple M4 Pro"),
+            Some(AppleChipVariant::Pro)
+        );
+        assert_eq!(
+            AppleChipVariant::from_chip_name("Apple M2 Ultra"),
+            Some(AppleChipVariant::Ultra)
+        );
+        assert_eq!(AppleChipVariant::from_chip_name("AMD Ryzen 9 7950X"), None);
     }
 
     #[test]
@@ -1622,4 +6381,32 @@ mod tests {
         assert_eq!(gpus[0].count, 2);
         assert!(!gpus[0].unified_memory);
     }
+
+    #[test]
+    fn test_cpu_inference_path() {
+        let with = |list: &[&str]| {
+            SystemSpecsBuilder::new()
+                .cpu_features(list.iter().map(|s| s.to_string()).collect())
+                .build()
+        };
+        assert_eq!(
+            with(&["avx2", "avx512f", "amx"]).cpu_inference_path(),
+            Some("AMX")
+        );
+        assert_eq!(with(&["neon", "sve2"]).cpu_inference_path(), Some("SVE"));
+        assert_eq!(with(&["avx2"]).cpu_inference_path(), Some("AVX2"));
+        assert_eq!(with(&["sse4.2"]).cpu_inference_path(), None);
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    #[test]
+    fn test_cpuinfo_has_flag() {
+        use super::cpuinfo_has_flag;
+
+        let info = "processor\t: 0\nflags\t\t: fpu avx2 avx512f amx_tile amx_int8\n\
+                    processor\t: 1\nflags\t\t: fpu avx2 avx512f amx_tile amx_int8\n";
+        assert!(cpuinfo_has_flag(info, "amx_tile"));
+        assert!(!cpuinfo_has_flag(info, "amx"));
+        assert!(!cpuinfo_has_flag("processor\t: 0\n", "amx_tile"));
+    }
 }
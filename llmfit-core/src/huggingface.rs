@@ -0,0 +1,375 @@
+//! Size a raw transformer checkpoint from its Hugging Face `config.json`,
+//! for models that aren't in the database or in Ollama.
+//!
+//! Configs are fetched from `https://huggingface.co/<repo>/resolve/main/`
+//! (or `$HF_ENDPOINT`), with `$HF_TOKEN` sent for gated repos, and cached
+//! under `~/.config/llmfit/hf/`. Delete a cached file to refetch it.
+
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::models::{KvCacheQuant, LlmModel, format_parameter_count, kv_cache_bytes};
+
+/// Quantization the database-style minimums are sized at: raw checkpoints
+/// are usually converted to a 4-bit GGUF before running locally.
+const SIZING_QUANT: &str = "Q4_K_M";
+
+/// Architecture of a checkpoint, read from its `config.json`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelSpec {
+    pub repo_id: String,
+    pub num_layers: u32,
+    pub hidden_size: u32,
+    pub intermediate_size: u32,
+    pub vocab_size: u32,
+    pub num_attention_heads: u32,
+    pub num_kv_heads: u32,
+    pub head_dim: u32,
+    pub max_position_embeddings: u32,
+    /// Checkpoint precision as a quantization label, e.g. "BF16".
+    pub dtype: String,
+    pub tie_word_embeddings: bool,
+    /// Expert count and experts used per token, for MoE models.
+    pub experts: Option<(u32, u32)>,
+    /// Per-expert FFN width when it differs from `intermediate_size`.
+    pub moe_intermediate_size: Option<u32>,
+}
+
+/// Why a repo couldn't be resolved.
+#[derive(Debug)]
+pub enum HfError {
+    Io(std::io::Error),
+    Http(String),
+    NotFound(String),     // no such repo, or no config.json in it
+    Unauthorized(String), // gated or private; needs HF_TOKEN
+    Parse(String),
+}
+
+impl fmt::Display for HfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HfError::Io(e) => write!(f, "{}", e),
+            HfError::Http(e) => write!(f, "Hugging Face request failed: {}", e),
+            HfError::NotFound(repo) => write!(f, "no config.json found for '{}'", repo),
+            HfError::Unauthorized(repo) => write!(
+                f,
+                "'{}' is gated or private; set HF_TOKEN to a token with access",
+                repo
+            ),
+            HfError::Parse(e) => write!(f, "invalid config.json: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HfError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for HfError {
+    fn from(e: std::io::Error) -> Self {
+        HfError::Io(e)
+    }
+}
+
+/// Resolve `repo_id` ("meta-llama/Llama-3.1-8B-Instruct"), from the cache
+/// when it has been fetched before.
+pub fn resolve_model(repo_id: &str) -> Result<ModelSpec, HfError> {
+    resolve_model_in(repo_id, cache_dir().as_deref())
+}
+
+/// [`resolve_model`] with an explicit cache directory; `None` disables
+/// caching.
+pub fn resolve_model_in(repo_id: &str, cache: Option<&Path>) -> Result<ModelSpec, HfError> {
+    let cache_file = cache.map(|dir| dir.join(cache_file_name(repo_id)));
+    if let Some(path) = &cache_file {
+        match fs::read_to_string(path) {
+            Ok(text) => return parse_config_text(repo_id, &text),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let text = fetch_config(repo_id)?;
+    // Parse before caching, so a bad response is refetched next time
+    let spec = parse_config_text(repo_id, &text)?;
+    if let Some(path) = &cache_file {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, &text)?;
+    }
+    Ok(spec)
+}
+
+fn parse_config_text(repo_id: &str, text: &str) -> Result<ModelSpec, HfError> {
+    let json: Value = serde_json::from_str(text).map_err(|e| HfError::Parse(e.to_string()))?;
+    parse_config(repo_id, &json)
+}
+
+/// ~/.config/llmfit/hf
+fn cache_dir() -> Option<PathBuf> {
+    Some(crate::paths::config_dir()?.join("hf"))
+}
+
+/// "org/name" -> "org--name.json", as the HF hub cache names repos.
+fn cache_file_name(repo_id: &str) -> String {
+    format!("{}.json", repo_id.replace('/', "--"))
+}
+
+fn fetch_config(repo_id: &str) -> Result<String, HfError> {
+    let endpoint = std::env::var("HF_ENDPOINT")
+        .ok()
+        .filter(|e| !e.is_empty())
+        .unwrap_or_else(|| "https://huggingface.co".to_string());
+    let url = format!(
+        "{}/{}/resolve/main/config.json",
+        endpoint.trim_end_matches('/'),
+        repo_id
+    );
+
+    let mut request = ureq::get(&url);
+    if let Ok(token) = std::env::var("HF_TOKEN")
+        && !token.trim().is_empty()
+    {
+        request = request.header("Authorization", &format!("Bearer {}", token.trim()));
+    }
+    let mut resp = request
+        .config()
+        .timeout_global(Some(Duration::from_secs(15)))
+        .http_status_as_error(false)
+        .build()
+        .call()
+        .map_err(|e| HfError::Http(e.to_string()))?;
+
+    match resp.status().as_u16() {
+        200..=299 => resp
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| HfError::Http(e.to_string())),
+        401 | 403 => Err(HfError::Unauthorized(repo_id.to_string())),
+        404 => Err(HfError::NotFound(repo_id.to_string())),
+        status => Err(HfError::Http(format!("HTTP {}", status))),
+    }
+}
+
+/// Read the architecture out of a `config.json`. Multimodal configs keep
+/// the language model's fields under `text_config`.
+pub fn parse_config(repo_id: &str, json: &Value) -> Result<ModelSpec, HfError> {
+    let cfg = if json.get("num_hidden_layers").is_none() && json.get("text_config").is_some() {
+        &json["text_config"]
+    } else {
+        json
+    };
+    let field = |key: &str| cfg[key].as_u64().and_then(|n| u32::try_from(n).ok());
+    let required = |key: &str| field(key).ok_or_else(|| HfError::Parse(format!("missing {}", key)));
+
+    let hidden_size = required("hidden_size")?;
+    let num_attention_heads = required("num_attention_heads")?;
+    if num_attention_heads == 0 {
+        return Err(HfError::Parse("num_attention_heads is 0".to_string()));
+    }
+    let experts = field("num_local_experts")
+        .or_else(|| field("num_experts"))
+        .filter(|&n| n > 1)
+        .map(|n| (n, field("num_experts_per_tok").unwrap_or(2)));
+    let dtype = cfg["torch_dtype"]
+        .as_str()
+        .or_else(|| json["torch_dtype"].as_str())
+        .map(|d| match d {
+            "float32" => "F32",
+            "float16" => "F16",
+            _ => "BF16",
+        })
+        .unwrap_or("BF16");
+
+    Ok(ModelSpec {
+        repo_id: repo_id.to_string(),
+        num_layers: required("num_hidden_layers")?,
+        hidden_size,
+        intermediate_size: required("intermediate_size")?,
+        vocab_size: required("vocab_size")?,
+        num_attention_heads,
+        num_kv_heads: field("num_key_value_heads").unwrap_or(num_attention_heads),
+        head_dim: field("head_dim").unwrap_or(hidden_size / num_attention_heads),
+        max_position_embeddings: field("max_position_embeddings").unwrap_or(4096),
+        dtype: dtype.to_string(),
+        tie_word_embeddings: cfg["tie_word_embeddings"]
+            .as_bool()
+            .or_else(|| json["tie_word_embeddings"].as_bool())
+            .unwrap_or(false),
+        experts,
+        moe_intermediate_size: field("moe_intermediate_size"),
+    })
+}
+
+impl ModelSpec {
+    fn attention_params(&self) -> u64 {
+        let hidden = self.hidden_size as u64;
+        let q_width = self.num_attention_heads as u64 * self.head_dim as u64;
+        let kv_width = self.num_kv_heads as u64 * self.head_dim as u64;
+        // Q and O projections, plus K and V at the KV width
+        2 * hidden * q_width + 2 * hidden * kv_width
+    }
+
+    /// Gated FFN (gate, up, down) of one expert, or of the dense MLP.
+    fn ffn_params(&self) -> u64 {
+        let width = self.moe_intermediate_size.unwrap_or(self.intermediate_size);
+        3 * self.hidden_size as u64 * width as u64
+    }
+
+    fn embedding_params(&self) -> u64 {
+        let tables = if self.tie_word_embeddings { 1 } else { 2 };
+        tables * self.vocab_size as u64 * self.hidden_size as u64
+    }
+
+    /// Total parameters, estimated from the layer shapes. Norms and biases
+    /// are left out; they're well under 0.1%.
+    pub fn params_raw(&self) -> u64 {
+        let experts = self.experts.map_or(1, |(total, _)| total) as u64;
+        self.num_layers as u64 * (self.attention_params() + experts * self.ffn_params())
+            + self.embedding_params()
+    }
+
+    /// Parameters read per token: all of them for a dense model, the
+    /// routed experts only for MoE.
+    pub fn active_params_raw(&self) -> u64 {
+        let used = self.experts.map_or(1, |(_, used)| used) as u64;
+        self.num_layers as u64 * (self.attention_params() + used * self.ffn_params())
+            + self.embedding_params()
+    }
+
+    /// KV cache (GB) for `context_len` tokens, from the real layer and
+    /// KV-head layout.
+    pub fn kv_cache_gb(&self, context_len: u32, kv_quant: KvCacheQuant) -> f64 {
+        let kv_width = self.num_kv_heads * self.head_dim;
+        kv_cache_bytes(self.num_layers, kv_width, context_len, kv_quant) as f64 / 1e9
+    }
+
+    /// As a database-style model for [`crate::fit::ModelFit::analyze`].
+    /// Memory minimums are sized at Q4_K_M with a 4K context.
+    pub fn to_llm_model(&self) -> LlmModel {
+        let params = self.params_raw();
+        let mut model = LlmModel {
+            name: self.repo_id.clone(),
+            provider: self
+                .repo_id
+                .split('/')
+                .next()
+                .unwrap_or(&self.repo_id)
+                .to_string(),
+            parameter_count: format_parameter_count(params),
+            parameters_raw: Some(params),
+            min_ram_gb: 0.0,
+            recommended_ram_gb: 0.0,
+            min_vram_gb: None,
+            quantization: self.dtype.clone(),
+            context_length: self.max_position_embeddings,
+            use_case: "General".to_string(),
+            is_moe: self.experts.is_some(),
+            num_experts: self.experts.map(|(total, _)| total),
+            active_experts: self.experts.map(|(_, used)| used),
+            active_parameters: self.experts.map(|_| self.active_params_raw()),
+            release_date: None,
+        };
+        model.size_memory_minimums(SIZING_QUANT);
+        model
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn llama_31_8b() -> Value {
+        serde_json::json!({
+            "architectures": ["LlamaForCausalLM"],
+            "hidden_size": 4096,
+            "intermediate_size": 14336,
+            "max_position_embeddings": 131072,
+            "num_attention_heads": 32,
+            "num_hidden_layers": 32,
+            "num_key_value_heads": 8,
+            "tie_word_embeddings": false,
+            "torch_dtype": "bfloat16",
+            "vocab_size": 128256
+        })
+    }
+
+    #[test]
+    fn test_parse_config_llama() {
+        let spec = parse_config("meta-llama/Llama-3.1-8B-Instruct", &llama_31_8b()).unwrap();
+        assert_eq!(spec.head_dim, 128);
+        assert_eq!(spec.num_kv_heads, 8);
+        assert_eq!(spec.dtype, "BF16");
+        // Published count is 8.03B
+        assert!((spec.params_raw() as f64 / 1e9 - 8.03).abs() < 0.01);
+        assert_eq!(spec.active_params_raw(), spec.params_raw());
+        // 32 layers x 1024 KV width x 2 (K, V) x 2 bytes = 128 KiB per token
+        let kv = spec.kv_cache_gb(8192, KvCacheQuant::F16);
+        assert!((kv - 8192.0 * 131_072.0 / 1e9).abs() < 1e-9);
+
+        let model = spec.to_llm_model();
+        assert_eq!(model.provider, "meta-llama");
+        assert_eq!(model.parameter_count, "8.0B");
+        assert_eq!(model.context_length, 131_072);
+        assert!(!model.is_moe);
+    }
+
+    #[test]
+    fn test_parse_config_moe_and_nested() {
+        let mixtral = serde_json::json!({
+            "hidden_size": 4096,
+            "intermediate_size": 14336,
+            "num_attention_heads": 32,
+            "num_hidden_layers": 32,
+            "num_key_value_heads": 8,
+            "num_local_experts": 8,
+            "num_experts_per_tok": 2,
+            "vocab_size": 32000
+        });
+        let spec = parse_config("mistralai/Mixtral-8x7B-v0.1", &mixtral).unwrap();
+        assert_eq!(spec.experts, Some((8, 2)));
+        // ~46.7B total, ~12.9B active
+        assert!((spec.params_raw() as f64 / 1e9 - 46.7).abs() < 0.1);
+        assert!((spec.active_params_raw() as f64 / 1e9 - 12.9).abs() < 0.1);
+        assert!(spec.to_llm_model().is_moe);
+
+        let nested = serde_json::json!({ "text_config": llama_31_8b() });
+        assert_eq!(parse_config("x/y", &nested).unwrap().num_layers, 32);
+
+        let broken = serde_json::json!({ "hidden_size": 4096 });
+        assert!(matches!(
+            parse_config("x/y", &broken),
+            Err(HfError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_model_reads_cache() {
+        let dir = std::env::temp_dir().join(format!("llmfit-hf-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let repo = "meta-llama/Llama-3.1-8B-Instruct";
+        assert_eq!(
+            cache_file_name(repo),
+            "meta-llama--Llama-3.1-8B-Instruct.json"
+        );
+        fs::write(dir.join(cache_file_name(repo)), llama_31_8b().to_string()).unwrap();
+
+        // Served from the cache, no network needed
+        let spec = resolve_model_in(repo, Some(&dir)).unwrap();
+        assert_eq!(spec.repo_id, repo);
+        assert_eq!(spec.num_layers, 32);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
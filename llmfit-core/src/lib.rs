@@ -1,9 +1,28 @@
 pub mod fit;
+pub mod gguf;
+pub mod gpu_specs;
 pub mod hardware;
+pub mod huggingface;
 pub mod models;
+pub mod ollama;
+pub mod paths;
+pub mod profile;
 pub mod providers;
 
-pub use fit::{FitLevel, InferenceRuntime, ModelFit, RunMode, ScoreComponents, SortColumn};
-pub use hardware::{GpuBackend, SystemSpecs};
-pub use models::{LlmModel, ModelDatabase, UseCase};
+pub use fit::{
+    FitLevel, FitWarning, InferenceRuntime, ModelFit, ModelFitResult, MultiGpuFitResult, RunMode,
+    ScoreComponents, SortColumn, estimate_tokens_per_second, evaluate_fit,
+};
+pub use gguf::{GgufError, GgufMetadata, parse_gguf_header};
+pub use hardware::{
+    AppleChipVariant, ContainerEnv, GpuBackend, GpuClass, GpuLinkTopology, JetsonVariant,
+    SystemSpecs, SystemSpecsBuilder,
+};
+pub use huggingface::{HfError, ModelSpec};
+pub use models::{
+    GgufQuantization, KvCacheQuant, LlmModel, ModelArchConfig, ModelDatabase, UseCase,
+};
+pub use ollama::{OllamaError, OllamaModelInfo};
+pub use paths::config_dir;
+pub use profile::ProfileError;
 pub use providers::{MlxProvider, ModelProvider, OllamaProvider};
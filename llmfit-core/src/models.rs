@@ -7,20 +7,15 @@ pub const QUANT_HIERARCHY: &[&str] = &["Q8_0", "Q6_K", "Q5_K_M", "Q4_K_M", "Q3_K
 /// MLX-native quantization hierarchy (best quality to most compressed).
 pub const MLX_QUANT_HIERARCHY: &[&str] = &["mlx-8bit", "mlx-4bit"];
 
-/// Bytes per parameter for each quantization level.
+/// Bytes per parameter for each quantization level. GGUF labels go through
+/// [`GgufQuantization::bytes_per_param`].
 pub fn quant_bpp(quant: &str) -> f64 {
     match quant {
-        "F32" => 4.0,
-        "F16" | "BF16" => 2.0,
-        "Q8_0" => 1.05,
-        "Q6_K" => 0.80,
-        "Q5_K_M" => 0.68,
-        "Q4_K_M" | "Q4_0" => 0.58,
-        "Q3_K_M" => 0.48,
-        "Q2_K" => 0.37,
         "mlx-4bit" => 0.55,
         "mlx-8bit" => 1.0,
-        _ => 0.58,
+        _ => quant
+            .parse::<GgufQuantization>()
+            .map_or(0.58, |q| q.bytes_per_param()),
     }
 }
 
@@ -56,6 +51,301 @@ pub fn quant_quality_penalty(quant: &str) -> f64 {
     }
 }
 
+/// GGUF (llama.cpp) quantization types, as they appear in file names such as
+/// `llama-3-8B-Q4_K_M.gguf`.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GgufQuantization {
+    Q2_K,
+    Q3_K_S,
+    Q3_K_M,
+    Q3_K_L,
+    Q4_0,
+    Q4_K_S,
+    Q4_K_M,
+    Q5_0,
+    Q5_K_S,
+    Q5_K_M,
+    Q6_K,
+    Q8_0,
+    F16,
+    BF16,
+    F32,
+}
+
+impl GgufQuantization {
+    /// Every quantization type, grouped by bit width from smallest to largest.
+    pub const ALL: [GgufQuantization; 15] = [
+        GgufQuantization::Q2_K,
+        GgufQuantization::Q3_K_S,
+        GgufQuantization::Q3_K_M,
+        GgufQuantization::Q3_K_L,
+        GgufQuantization::Q4_0,
+        GgufQuantization::Q4_K_S,
+        GgufQuantization::Q4_K_M,
+        GgufQuantization::Q5_0,
+        GgufQuantization::Q5_K_S,
+        GgufQuantization::Q5_K_M,
+        GgufQuantization::Q6_K,
+        GgufQuantization::Q8_0,
+        GgufQuantization::F16,
+        GgufQuantization::BF16,
+        GgufQuantization::F32,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GgufQuantization::Q2_K => "Q2_K",
+            GgufQuantization::Q3_K_S => "Q3_K_S",
+            GgufQuantization::Q3_K_M => "Q3_K_M",
+            GgufQuantization::Q3_K_L => "Q3_K_L",
+            GgufQuantization::Q4_0 => "Q4_0",
+            GgufQuantization::Q4_K_S => "Q4_K_S",
+            GgufQuantization::Q4_K_M => "Q4_K_M",
+            GgufQuantization::Q5_0 => "Q5_0",
+            GgufQuantization::Q5_K_S => "Q5_K_S",
+            GgufQuantization::Q5_K_M => "Q5_K_M",
+            GgufQuantization::Q6_K => "Q6_K",
+            GgufQuantization::Q8_0 => "Q8_0",
+            GgufQuantization::F16 => "F16",
+            GgufQuantization::BF16 => "BF16",
+            GgufQuantization::F32 => "F32",
+        }
+    }
+
+    /// Average bits per weight, including block scales, as reported by
+    /// llama.cpp's `quantize` tool for a 7B Llama model.
+    pub fn bits_per_weight(&self) -> f32 {
+        match self {
+            GgufQuantization::Q2_K => 2.63,
+            GgufQuantization::Q3_K_S => 3.41,
+            GgufQuantization::Q3_K_M => 3.74,
+            GgufQuantization::Q3_K_L => 4.03,
+            GgufQuantization::Q4_0 => 4.55,
+            GgufQuantization::Q4_K_S => 4.37,
+            GgufQuantization::Q4_K_M => 4.58,
+            GgufQuantization::Q5_0 => 5.54,
+            GgufQuantization::Q5_K_S => 5.21,
+            GgufQuantization::Q5_K_M => 5.33,
+            GgufQuantization::Q6_K => 6.56,
+            GgufQuantization::Q8_0 => 8.50,
+            GgufQuantization::F16 | GgufQuantization::BF16 => 16.0,
+            GgufQuantization::F32 => 32.0,
+        }
+    }
+
+    /// Whole-file bytes per parameter, used for weight memory in the fit
+    /// estimate. Token embeddings and the output layer stay at higher
+    /// precision, so the ranked types sit above `bits_per_weight() / 8`;
+    /// the rest fall back to that ratio.
+    pub fn bytes_per_param(&self) -> f64 {
+        match self {
+            GgufQuantization::F32 => 4.0,
+            GgufQuantization::F16 | GgufQuantization::BF16 => 2.0,
+            GgufQuantization::Q8_0 => 1.05,
+            GgufQuantization::Q6_K => 0.80,
+            GgufQuantization::Q5_K_M => 0.68,
+            GgufQuantization::Q4_K_M | GgufQuantization::Q4_0 => 0.58,
+            GgufQuantization::Q3_K_M => 0.48,
+            GgufQuantization::Q2_K => 0.37,
+            other => other.bits_per_weight() as f64 / 8.0,
+        }
+    }
+
+    /// Parse a quantization label case-insensitively (e.g. "q4_k_m").
+    pub fn from_label(s: &str) -> Option<GgufQuantization> {
+        Self::ALL
+            .into_iter()
+            .find(|q| q.label().eq_ignore_ascii_case(s.trim()))
+    }
+
+    /// Pick the quantization out of a GGUF file name, e.g.
+    /// "llama-3-8B-Q4_K_M.gguf" -> `Q4_K_M`.
+    pub fn from_filename(name: &str) -> Option<GgufQuantization> {
+        let stem = name.trim_end_matches(".gguf");
+        stem.rsplit(['-', '.']).find_map(Self::from_label)
+    }
+}
+
+impl std::str::FromStr for GgufQuantization {
+    type Err = String;
+
+    /// Accepts a label ("Q4_K_M", "q4_k_m") or a GGUF file name ending in one
+    /// ("llama-3-8B-q4_k_m.gguf").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_label(s)
+            .or_else(|| Self::from_filename(s))
+            .ok_or_else(|| format!("unknown quantization '{}'", s))
+    }
+}
+
+/// Context length assumed by [`model_vram_gb`] when sizing the KV cache.
+pub const DEFAULT_CONTEXT_LENGTH: u32 = 4096;
+
+/// A raw parameter count the way the database writes it: "8.0B", "135M".
+pub fn format_parameter_count(params_raw: u64) -> String {
+    let params_b = params_raw as f64 / 1e9;
+    if params_b >= 1.0 {
+        format!("{:.1}B", params_b)
+    } else {
+        format!("{:.0}M", params_b * 1000.0)
+    }
+}
+
+/// Memory (GB) needed to run a GGUF model of `params_billions` parameters:
+/// weights at the quantization's bits-per-weight, plus a KV cache at
+/// [`DEFAULT_CONTEXT_LENGTH`] and the same runtime overhead used by
+/// [`LlmModel::estimate_memory_gb`].
+pub fn model_vram_gb(params_billions: f32, quant: GgufQuantization) -> f64 {
+    model_memory_gb(params_billions, quant, DEFAULT_CONTEXT_LENGTH)
+}
+
+/// Like [`model_vram_gb`], with the KV cache sized for `context_len` tokens.
+pub fn model_memory_gb(params_billions: f32, quant: GgufQuantization, context_len: u32) -> f64 {
+    let params = params_billions as f64;
+    let weights = params * quant.bits_per_weight() as f64 / 8.0;
+    let kv_cache = approx_kv_cache_gb(params, context_len);
+    let overhead = 0.5;
+    weights + kv_cache + overhead
+}
+
+/// KV cache size (GB) when only the parameter count is known:
+/// ~0.000008 GB per billion params per context token.
+pub fn approx_kv_cache_gb(params_billions: f64, context_len: u32) -> f64 {
+    0.000008 * params_billions * context_len as f64
+}
+
+/// Element type of the KV cache. llama.cpp keeps it in F16 by default and
+/// can quantize it with `--cache-type-k` / `--cache-type-v`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum KvCacheQuant {
+    #[default]
+    F16, // 2 bytes per element
+    Q8_0, // 34-byte blocks of 32 elements
+    Q4_0, // 18-byte blocks of 32 elements
+}
+
+impl KvCacheQuant {
+    pub const ALL: [KvCacheQuant; 3] = [KvCacheQuant::F16, KvCacheQuant::Q8_0, KvCacheQuant::Q4_0];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            KvCacheQuant::F16 => "f16",
+            KvCacheQuant::Q8_0 => "q8_0",
+            KvCacheQuant::Q4_0 => "q4_0",
+        }
+    }
+
+    /// Bytes per block and elements per block, as laid out by ggml.
+    fn block_layout(&self) -> (u64, u64) {
+        match self {
+            KvCacheQuant::F16 => (2, 1),
+            KvCacheQuant::Q8_0 => (34, 32),
+            KvCacheQuant::Q4_0 => (18, 32),
+        }
+    }
+}
+
+impl std::str::FromStr for KvCacheQuant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "f16" | "fp16" => Ok(KvCacheQuant::F16),
+            "q8" | "q8_0" => Ok(KvCacheQuant::Q8_0),
+            "q4" | "q4_0" => Ok(KvCacheQuant::Q4_0),
+            _ => Err(format!("unknown KV cache type '{}'", s)),
+        }
+    }
+}
+
+/// KV cache size in bytes for `n_ctx` tokens: one K and one V row of
+/// `n_embd` elements per layer, per token. For grouped-query attention
+/// `n_embd` is the KV width (`num_kv_heads * head_dim`), not the model's
+/// hidden size.
+pub fn kv_cache_bytes(n_layers: u32, n_embd: u32, n_ctx: u32, kv_quant: KvCacheQuant) -> u64 {
+    let elements = 2 * n_layers as u64 * n_embd as u64 * n_ctx as u64;
+    let (block_bytes, block_elems) = kv_quant.block_layout();
+    elements.div_ceil(block_elems) * block_bytes
+}
+
+/// The architecture parameters that size the KV cache. Models with grouped
+/// query attention (GQA) have far fewer KV heads than attention heads, which
+/// is why the per-parameter KV approximation in
+/// [`LlmModel::estimate_memory_gb`] overshoots for them at long contexts and
+/// undershoots for older full-attention models.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ModelArchConfig {
+    pub name: &'static str,
+    pub params_billions: f32,
+    pub num_layers: u32,
+    pub num_attention_heads: u32,
+    pub num_kv_heads: u32,
+    pub head_dim: u32,
+}
+
+impl ModelArchConfig {
+    pub const LLAMA_3_8B: ModelArchConfig = ModelArchConfig {
+        name: "LLaMA-3-8B",
+        params_billions: 8.03,
+        num_layers: 32,
+        num_attention_heads: 32,
+        num_kv_heads: 8,
+        head_dim: 128,
+    };
+    pub const MISTRAL_7B: ModelArchConfig = ModelArchConfig {
+        name: "Mistral-7B",
+        params_billions: 7.24,
+        num_layers: 32,
+        num_attention_heads: 32,
+        num_kv_heads: 8,
+        head_dim: 128,
+    };
+    pub const MIXTRAL_8X7B: ModelArchConfig = ModelArchConfig {
+        name: "Mixtral-8x7B",
+        params_billions: 46.7,
+        num_layers: 32,
+        num_attention_heads: 32,
+        num_kv_heads: 8,
+        head_dim: 128,
+    };
+
+    /// Built-in presets.
+    pub const KNOWN: [ModelArchConfig; 3] = [
+        ModelArchConfig::LLAMA_3_8B,
+        ModelArchConfig::MISTRAL_7B,
+        ModelArchConfig::MIXTRAL_8X7B,
+    ];
+
+    /// KV cache (GB) for `context_len` tokens with an F16 cache.
+    pub fn kv_cache_gb(&self, context_len: u32) -> f64 {
+        self.kv_cache_gb_with(context_len, KvCacheQuant::F16)
+    }
+
+    /// KV cache (GB) for `context_len` tokens stored as `kv_quant`.
+    pub fn kv_cache_gb_with(&self, context_len: u32, kv_quant: KvCacheQuant) -> f64 {
+        let n_embd = self.num_kv_heads * self.head_dim;
+        kv_cache_bytes(self.num_layers, n_embd, context_len, kv_quant) as f64 / 1e9
+    }
+
+    /// Weights + F16 KV cache + runtime overhead, in GB.
+    pub fn memory_gb(&self, quant: GgufQuantization, context_len: u32) -> f64 {
+        self.memory_gb_with(quant, context_len, KvCacheQuant::F16)
+    }
+
+    /// Weights + KV cache stored as `kv_quant` + runtime overhead, in GB.
+    pub fn memory_gb_with(
+        &self,
+        quant: GgufQuantization,
+        context_len: u32,
+        kv_quant: KvCacheQuant,
+    ) -> f64 {
+        let weights = self.params_billions as f64 * quant.bits_per_weight() as f64 / 8.0;
+        let overhead = 0.5;
+        weights + self.kv_cache_gb_with(context_len, kv_quant) + overhead
+    }
+}
+
 /// Use-case category for scoring weights.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 pub enum UseCase {
@@ -151,19 +441,50 @@ impl LlmModel {
         }
     }
 
+    /// Fill `min_ram_gb`, `min_vram_gb` and `recommended_ram_gb` (20% more)
+    /// for running at `quant` with the context capped at
+    /// [`DEFAULT_CONTEXT_LENGTH`], for models built from a live lookup
+    /// rather than the database.
+    pub fn size_memory_minimums(&mut self, quant: &str) {
+        let min = self.estimate_memory_gb(quant, self.context_length.min(DEFAULT_CONTEXT_LENGTH));
+        self.min_ram_gb = min;
+        self.recommended_ram_gb = min * 1.2;
+        self.min_vram_gb = Some(min);
+    }
+
     /// Estimate memory required (GB) at a given quantization and context length.
     /// Formula: model_weights + KV_cache + runtime_overhead
     pub fn estimate_memory_gb(&self, quant: &str, ctx: u32) -> f64 {
         let bpp = quant_bpp(quant);
         let params = self.params_b();
         let model_mem = params * bpp;
-        // KV cache: ~0.000008 GB per billion params per context token
-        let kv_cache = 0.000008 * params * ctx as f64;
+        let kv_cache = self.kv_cache_gb(ctx);
         // Runtime overhead (CUDA/Metal context, buffers)
         let overhead = 0.5;
         model_mem + kv_cache + overhead
     }
 
+    /// Transformer block count typical of dense models this size. The
+    /// database doesn't record architectures, so this drives the
+    /// `--n-gpu-layers` estimate for partial offload.
+    pub fn estimated_layer_count(&self) -> u32 {
+        match self.params_b() {
+            p if p < 2.0 => 22,
+            p if p < 5.0 => 28,
+            p if p < 10.0 => 32,
+            p if p < 20.0 => 40,
+            p if p < 40.0 => 60,
+            p if p < 100.0 => 80,
+            p if p < 200.0 => 96,
+            _ => 126,
+        }
+    }
+
+    /// Estimated KV cache size (GB) at a given context length.
+    pub fn kv_cache_gb(&self, ctx: u32) -> f64 {
+        approx_kv_cache_gb(self.params_b(), ctx)
+    }
+
     /// Select the best quantization level that fits within a memory budget.
     /// Returns the quant name and estimated memory in GB, or None if nothing fits.
     pub fn best_quant_for_budget(&self, budget_gb: f64, ctx: u32) -> Option<(&'static str, f64)> {
@@ -197,6 +518,34 @@ impl LlmModel {
         None
     }
 
+    /// Parameters (billions) read per generated token: the routed experts
+    /// plus shared layers for MoE models, everything for dense ones. Drives
+    /// the speed estimate.
+    pub fn active_params_b(&self) -> f64 {
+        match self.active_parameters {
+            Some(active) if self.is_moe => active as f64 / 1_000_000_000.0,
+            _ => self.params_b(),
+        }
+    }
+
+    /// Fill in the MoE fields from the model name when the database entry
+    /// only records the total parameter count (see [`moe_hint_from_name`]).
+    pub fn infer_moe_from_name(&mut self) {
+        if self.is_moe {
+            return;
+        }
+        let Some(hint) = moe_hint_from_name(&self.name, self.params_b()) else {
+            return;
+        };
+        self.is_moe = true;
+        self.num_experts = self.num_experts.or(hint.num_experts);
+        if self.active_parameters.is_none() {
+            self.active_parameters = hint
+                .active_params_b
+                .map(|b| (b * 1_000_000_000.0).round() as u64);
+        }
+    }
+
     /// For MoE models, compute estimated VRAM for active experts only.
     /// Returns None for dense models.
     pub fn moe_active_vram_gb(&self) -> Option<f64> {
@@ -226,6 +575,57 @@ impl LlmModel {
     }
 }
 
+/// Mixture-of-experts shape read from a model name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoeNameHint {
+    pub num_experts: Option<u32>,
+    pub active_params_b: Option<f64>,
+}
+
+/// Recognise MoE naming conventions, given the model's total size:
+/// - `A3B`, `A2.7B`: active parameters (Qwen3-30B-A3B, Qwen1.5-MoE-A2.7B)
+/// - `8x7B`: expert count; top-2 routing assumed, as in Mixtral
+/// - `MoE` with two sizes, smaller first: active then total (OLMoE-1B-7B)
+///
+/// A bare `MoE` marks the model as MoE with no active count. Returns None
+/// for names that look dense.
+pub fn moe_hint_from_name(name: &str, total_params_b: f64) -> Option<MoeNameHint> {
+    let base = name.rsplit('/').next().unwrap_or(name).to_lowercase();
+    let tokens: Vec<&str> = base.split(['-', '_']).collect();
+    let billions = |t: &str| t.strip_suffix('b')?.parse::<f64>().ok();
+
+    let mut is_moe = tokens.iter().any(|t| t.contains("moe"));
+    let mut num_experts = None;
+    let mut active = None;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if let Some(b) = token.strip_prefix('a').and_then(billions) {
+            active = Some(b);
+            is_moe = true;
+        } else if let Some((n, size)) = token.split_once('x')
+            && let (Ok(n), Some(_)) = (n.parse::<u32>(), billions(size))
+            && n > 1
+        {
+            num_experts = Some(n);
+            active = active.or(Some(total_params_b * 2.0 / n as f64));
+            is_moe = true;
+        } else if is_moe
+            && active.is_none()
+            && let Some(small) = billions(token)
+            && let Some(large) = tokens.get(i + 1).and_then(|t| billions(t))
+            && small < large
+        {
+            active = Some(small);
+        }
+    }
+
+    is_moe.then_some(MoeNameHint {
+        num_experts,
+        // An "active" count at or above the total is a misparse
+        active_params_b: active.filter(|&a| a > 0.0 && a < total_params_b),
+    })
+}
+
 /// Intermediate struct matching the JSON schema from the scraper.
 /// Extra fields are ignored when mapping to LlmModel.
 #[derive(Deserialize)]
@@ -267,8 +667,13 @@ impl Default for ModelDatabase {
 
 impl ModelDatabase {
     pub fn new() -> Self {
-        let entries: Vec<HfModelEntry> =
-            serde_json::from_str(HF_MODELS_JSON).expect("Failed to parse embedded hf_models.json");
+        Self::from_json(HF_MODELS_JSON).expect("Failed to parse embedded hf_models.json")
+    }
+
+    /// A database from JSON in the format of the embedded
+    /// `hf_models.json`, e.g. a locally maintained copy.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let entries: Vec<HfModelEntry> = serde_json::from_str(json)?;
 
         let models = entries
             .into_iter()
@@ -289,9 +694,13 @@ impl ModelDatabase {
                 active_parameters: e.active_parameters,
                 release_date: e.release_date,
             })
+            .map(|mut model| {
+                model.infer_moe_from_name();
+                model
+            })
             .collect();
 
-        ModelDatabase { models }
+        Ok(ModelDatabase { models })
     }
 
     pub fn get_all_models(&self) -> &Vec<LlmModel> {
@@ -351,6 +760,87 @@ mod tests {
     // Quantization function tests
     // ────────────────────────────────────────────────────────────────────
 
+    #[test]
+    fn test_gguf_quantization_bits_per_weight() {
+        assert!((GgufQuantization::Q4_K_M.bits_per_weight() - 4.5).abs() < 0.1);
+        assert_eq!(GgufQuantization::F16.bits_per_weight(), 16.0);
+        assert!(
+            GgufQuantization::Q2_K.bits_per_weight() < GgufQuantization::Q8_0.bits_per_weight()
+        );
+        for q in GgufQuantization::ALL {
+            assert_eq!(GgufQuantization::from_label(q.label()), Some(q));
+        }
+    }
+
+    #[test]
+    fn test_gguf_quantization_from_filename() {
+        assert_eq!(
+            GgufQuantization::from_filename("llama-3-8B-Q4_K_M.gguf"),
+            Some(GgufQuantization::Q4_K_M)
+        );
+        assert_eq!(
+            GgufQuantization::from_filename("mistral-7b-instruct-v0.2.Q5_K_S.gguf"),
+            Some(GgufQuantization::Q5_K_S)
+        );
+        assert_eq!(GgufQuantization::from_filename("model.safetensors"), None);
+    }
+
+    #[test]
+    fn test_kv_cache_bytes_scales_with_context_and_type() {
+        // LLaMA-3-8B: 32 layers, 8 KV heads x 128 = 1024-wide K and V rows
+        let f16_4k = kv_cache_bytes(32, 1024, 4096, KvCacheQuant::F16);
+        assert_eq!(f16_4k, 512 * 1024 * 1024);
+        assert_eq!(
+            kv_cache_bytes(32, 1024, 32_768, KvCacheQuant::F16),
+            8 * f16_4k
+        );
+        // Q8_0 stores 32 elements in 34 bytes, Q4_0 in 18
+        assert_eq!(
+            kv_cache_bytes(32, 1024, 4096, KvCacheQuant::Q8_0),
+            f16_4k / 64 * 34
+        );
+        assert_eq!(
+            kv_cache_bytes(32, 1024, 4096, KvCacheQuant::Q4_0),
+            f16_4k / 64 * 18
+        );
+        assert_eq!("Q8".parse::<KvCacheQuant>(), Ok(KvCacheQuant::Q8_0));
+        assert!("q5_1".parse::<KvCacheQuant>().is_err());
+    }
+
+    #[test]
+    fn test_format_parameter_count() {
+        assert_eq!(format_parameter_count(8_030_261_248), "8.0B");
+        assert_eq!(format_parameter_count(134_515_008), "135M");
+    }
+
+    #[test]
+    fn test_arch_kv_cache_gb() {
+        // LLaMA-3-8B, F16 cache: 128 KiB per token -> ~4.3 GB at 32K
+        let gb = ModelArchConfig::LLAMA_3_8B.kv_cache_gb(32_768);
+        assert!((gb - 4.295).abs() < 0.001, "got {}", gb);
+        // A Q8_0 cache is about half the size
+        let q8 = ModelArchConfig::LLAMA_3_8B.kv_cache_gb_with(32_768, KvCacheQuant::Q8_0);
+        assert_eq!(q8, gb / 2.0 * 34.0 / 32.0);
+        // Mixtral shares Mistral's attention layout, so same KV per token
+        assert_eq!(
+            ModelArchConfig::MIXTRAL_8X7B.kv_cache_gb(4096),
+            ModelArchConfig::MISTRAL_7B.kv_cache_gb(4096)
+        );
+        assert!(
+            ModelArchConfig::LLAMA_3_8B.memory_gb(GgufQuantization::Q4_K_M, 32_768)
+                > ModelArchConfig::LLAMA_3_8B.memory_gb(GgufQuantization::Q4_K_M, 4096) + 3.5
+        );
+    }
+
+    #[test]
+    fn test_model_vram_gb() {
+        // 8B at Q4_K_M: 4.58 GB weights + 0.26 GB KV + 0.5 GB overhead
+        let gb = model_vram_gb(8.0, GgufQuantization::Q4_K_M);
+        assert!((gb - 5.34).abs() < 0.01, "got {}", gb);
+        assert!(model_vram_gb(8.0, GgufQuantization::Q8_0) > gb);
+        assert!(model_vram_gb(8.0, GgufQuantization::Q2_K) < gb);
+    }
+
     #[test]
     fn test_mlx_quant_bpp_values() {
         assert_eq!(quant_bpp("mlx-4bit"), 0.55);
@@ -394,6 +884,19 @@ mod tests {
         assert_eq!(quant, "mlx-4bit");
     }
 
+    #[test]
+    fn test_gguf_quantization_from_str() {
+        assert_eq!("q4_k_m".parse(), Ok(GgufQuantization::Q4_K_M));
+        assert_eq!("F32".parse(), Ok(GgufQuantization::F32));
+        assert_eq!(
+            "Meta-Llama-3-8B-Instruct-q5_k_m.gguf".parse(),
+            Ok(GgufQuantization::Q5_K_M)
+        );
+        assert!("q9_z".parse::<GgufQuantization>().is_err());
+        // Types without a measured ratio fall back to bits per weight
+        assert_eq!(quant_bpp("Q5_K_S"), 5.21_f32 as f64 / 8.0);
+    }
+
     #[test]
     fn test_quant_bpp() {
         assert_eq!(quant_bpp("F32"), 4.0);
@@ -655,6 +1158,58 @@ mod tests {
         assert!(offloaded_val > 10.0);
     }
 
+    #[test]
+    fn test_moe_hint_from_name() {
+        let hint = |name, total| moe_hint_from_name(name, total);
+        assert_eq!(
+            hint("Qwen/Qwen3-Next-80B-A3B-Instruct", 81.3),
+            Some(MoeNameHint {
+                num_experts: None,
+                active_params_b: Some(3.0)
+            })
+        );
+        assert_eq!(
+            hint("Qwen/Qwen1.5-MoE-A2.7B", 14.3)
+                .unwrap()
+                .active_params_b,
+            Some(2.7)
+        );
+        // Mixtral-style: expert count, top-2 routing
+        let mixtral = hint("mistralai/Mixtral-8x7B-Instruct-v0.1", 46.7).unwrap();
+        assert_eq!(mixtral.num_experts, Some(8));
+        assert!((mixtral.active_params_b.unwrap() - 11.675).abs() < 0.01);
+        assert_eq!(
+            hint("allenai/OLMoE-1B-7B-0125", 6.9)
+                .unwrap()
+                .active_params_b,
+            Some(1.0)
+        );
+        // Dense names, including ones with an "a" or "x" in a token
+        assert_eq!(hint("meta-llama/Llama-3.1-8B-Instruct", 8.0), None);
+        assert_eq!(hint("Qwen/Qwen2.5-Coder-7B-AWQ", 7.0), None);
+        assert_eq!(hint("mlx-community/phi-4-4bit", 14.0), None);
+    }
+
+    #[test]
+    fn test_database_infers_moe_from_name() {
+        let db = ModelDatabase::new();
+        let model = db
+            .get_all_models()
+            .iter()
+            .find(|m| m.name == "Qwen/Qwen3-Next-80B-A3B-Instruct")
+            .unwrap();
+        assert!(model.is_moe);
+        assert!((model.active_params_b() - 3.0).abs() < 1e-9);
+        assert!(model.params_b() > 80.0);
+        // Entries with real expert metadata are left as they are
+        let mixtral = db
+            .get_all_models()
+            .iter()
+            .find(|m| m.name == "mistralai/Mixtral-8x7B-Instruct-v0.1")
+            .unwrap();
+        assert_eq!(mixtral.active_parameters, Some(12_900_000_000));
+    }
+
     // ────────────────────────────────────────────────────────────────────
     // UseCase tests
     // ────────────────────────────────────────────────────────────────────
@@ -737,6 +1292,20 @@ mod tests {
         assert!(!models.is_empty());
     }
 
+    #[test]
+    fn test_model_database_from_json() {
+        let json = r#"[{
+            "name": "acme/Tiny-1B", "provider": "acme", "parameter_count": "1B",
+            "min_ram_gb": 1.0, "recommended_ram_gb": 2.0, "min_vram_gb": 1.0,
+            "quantization": "Q4_K_M", "context_length": 4096, "use_case": "General"
+        }]"#;
+        let db = ModelDatabase::from_json(json).unwrap();
+        assert_eq!(db.get_all_models().len(), 1);
+        assert_eq!(db.find_model("tiny")[0].provider, "acme");
+
+        assert!(ModelDatabase::from_json("[{\"name\": \"x\"}]").is_err());
+    }
+
     #[test]
     fn test_find_model() {
         let db = ModelDatabase::new();
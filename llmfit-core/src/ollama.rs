@@ -0,0 +1,264 @@
+//! Look up a model's size through a local Ollama server's `/api/show`, so
+//! a tag like `llama3.1:8b-instruct-q4_K_M` can be fitted without knowing
+//! its parameter count or quantization.
+
+use std::fmt;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::models::{DEFAULT_CONTEXT_LENGTH, LlmModel, format_parameter_count};
+use crate::providers::OllamaProvider;
+
+/// What Ollama knows about an installed model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OllamaModelInfo {
+    /// The tag as asked for, e.g. "llama3.1:8b-instruct-q4_K_M".
+    pub name: String,
+    pub family: Option<String>,
+    pub params_raw: u64,
+    /// GGUF quantization as Ollama labels it, e.g. "Q4_K_M".
+    pub quantization: String,
+    pub context_length: u32,
+    /// Expert count and experts used per token, for MoE models.
+    pub experts: Option<(u32, u32)>,
+}
+
+/// Why a model couldn't be looked up.
+#[derive(Debug)]
+pub enum OllamaError {
+    Unavailable(String), // no server at OLLAMA_HOST
+    NotFound(String),    // the server doesn't have this model
+    Parse(String),       // the response lacked the size fields
+}
+
+impl fmt::Display for OllamaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OllamaError::Unavailable(e) => write!(f, "Ollama is not reachable: {}", e),
+            OllamaError::NotFound(name) => write!(f, "Ollama has no model '{}'", name),
+            OllamaError::Parse(e) => write!(f, "unexpected /api/show response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for OllamaError {}
+
+impl OllamaProvider {
+    /// Query `/api/show` for `name`. Only models the server has pulled can
+    /// be shown.
+    pub fn show_model(&self, name: &str) -> Result<OllamaModelInfo, OllamaError> {
+        let resp = ureq::post(&self.api_url("show"))
+            .config()
+            .timeout_global(Some(Duration::from_secs(5)))
+            .http_status_as_error(false)
+            .build()
+            .send_json(serde_json::json!({ "model": name }))
+            .map_err(|e| OllamaError::Unavailable(e.to_string()))?;
+        if resp.status() == 404 {
+            return Err(OllamaError::NotFound(name.to_string()));
+        }
+        if !resp.status().is_success() {
+            return Err(OllamaError::Parse(format!("HTTP {}", resp.status())));
+        }
+        let json: Value = resp
+            .into_body()
+            .read_json()
+            .map_err(|e| OllamaError::Parse(e.to_string()))?;
+        parse_show_response(name, &json)
+    }
+
+    /// Tags of every model the server has pulled, as `/api/tags` lists
+    /// them.
+    pub fn model_tags(&self) -> Result<Vec<String>, OllamaError> {
+        let resp = ureq::get(&self.api_url("tags"))
+            .config()
+            .timeout_global(Some(Duration::from_secs(5)))
+            .build()
+            .call()
+            .map_err(|e| OllamaError::Unavailable(e.to_string()))?;
+        let json: Value = resp
+            .into_body()
+            .read_json()
+            .map_err(|e| OllamaError::Parse(e.to_string()))?;
+        parse_tags_response(&json)
+    }
+}
+
+/// Model tags from a `/api/tags` response.
+pub fn parse_tags_response(json: &Value) -> Result<Vec<String>, OllamaError> {
+    let models = json["models"]
+        .as_array()
+        .ok_or_else(|| OllamaError::Parse("no model list".to_string()))?;
+    Ok(models
+        .iter()
+        .filter_map(|m| m["name"].as_str().map(str::to_string))
+        .collect())
+}
+
+/// Pull the size fields out of an `/api/show` response. The exact
+/// parameter count and context length come from `model_info` (GGUF
+/// metadata); `details.parameter_size` ("8.0B") is the fallback.
+pub fn parse_show_response(name: &str, json: &Value) -> Result<OllamaModelInfo, OllamaError> {
+    let details = &json["details"];
+    let info = &json["model_info"];
+    let family = details["family"]
+        .as_str()
+        .or_else(|| info["general.architecture"].as_str())
+        .map(str::to_string);
+    // Architecture-specific keys are prefixed, e.g. "llama.context_length"
+    let arch_key = |key: &str| {
+        let arch = info["general.architecture"].as_str()?;
+        info[format!("{}.{}", arch, key)].as_u64()
+    };
+
+    let params_raw = info["general.parameter_count"]
+        .as_u64()
+        .or_else(|| {
+            details["parameter_size"]
+                .as_str()
+                .and_then(parse_parameter_size)
+        })
+        .ok_or_else(|| OllamaError::Parse("no parameter count".to_string()))?;
+    let quantization = details["quantization_level"]
+        .as_str()
+        .filter(|q| !q.is_empty())
+        .ok_or_else(|| OllamaError::Parse("no quantization level".to_string()))?
+        .to_string();
+    let context_length = arch_key("context_length")
+        .and_then(|n| u32::try_from(n).ok())
+        .unwrap_or(DEFAULT_CONTEXT_LENGTH);
+    let experts = match (arch_key("expert_count"), arch_key("expert_used_count")) {
+        (Some(total), Some(used)) if total > 1 => Some((total as u32, used as u32)),
+        _ => None,
+    };
+
+    Ok(OllamaModelInfo {
+        name: name.to_string(),
+        family,
+        params_raw,
+        quantization,
+        context_length,
+        experts,
+    })
+}
+
+/// "8.0B" -> 8_000_000_000, "137M" -> 137_000_000.
+fn parse_parameter_size(s: &str) -> Option<u64> {
+    let s = s.trim().to_uppercase();
+    let (num, scale) = if let Some(n) = s.strip_suffix('B') {
+        (n, 1e9)
+    } else if let Some(n) = s.strip_suffix('M') {
+        (n, 1e6)
+    } else {
+        return None;
+    };
+    let value = num.trim().parse::<f64>().ok()?;
+    (value > 0.0).then(|| (value * scale).round() as u64)
+}
+
+impl OllamaModelInfo {
+    /// As a database-style model for [`crate::fit::ModelFit::analyze`].
+    /// Memory minimums are sized at the model's own quantization and a 4K
+    /// context.
+    pub fn to_llm_model(&self) -> LlmModel {
+        let mut model = LlmModel {
+            name: self.name.clone(),
+            provider: "Ollama".to_string(),
+            parameter_count: format_parameter_count(self.params_raw),
+            parameters_raw: Some(self.params_raw),
+            min_ram_gb: 0.0,
+            recommended_ram_gb: 0.0,
+            min_vram_gb: None,
+            quantization: self.quantization.clone(),
+            context_length: self.context_length,
+            use_case: "General".to_string(),
+            is_moe: self.experts.is_some(),
+            num_experts: self.experts.map(|(total, _)| total),
+            active_experts: self.experts.map(|(_, used)| used),
+            active_parameters: None,
+            release_date: None,
+        };
+        model.size_memory_minimums(&self.quantization);
+        model
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_show_response() {
+        let json = serde_json::json!({
+            "details": {
+                "family": "llama",
+                "parameter_size": "8.0B",
+                "quantization_level": "Q4_K_M"
+            },
+            "model_info": {
+                "general.architecture": "llama",
+                "general.parameter_count": 8_030_261_248u64,
+                "llama.context_length": 131_072
+            }
+        });
+        let info = parse_show_response("llama3.1:8b", &json).unwrap();
+        assert_eq!(info.params_raw, 8_030_261_248);
+        assert_eq!(info.quantization, "Q4_K_M");
+        assert_eq!(info.context_length, 131_072);
+        assert_eq!(info.family.as_deref(), Some("llama"));
+        assert_eq!(info.experts, None);
+
+        let model = info.to_llm_model();
+        assert_eq!(model.parameter_count, "8.0B");
+        assert_eq!(model.quantization, "Q4_K_M");
+        assert!((model.params_b() - 8.03).abs() < 0.01);
+        assert!(model.min_ram_gb > 4.0 && model.min_ram_gb < 7.0);
+    }
+
+    #[test]
+    fn test_parse_show_response_fallbacks() {
+        // Older servers send no model_info: size from details, default context
+        let json = serde_json::json!({
+            "details": { "parameter_size": "137M", "quantization_level": "F16" }
+        });
+        let info = parse_show_response("nomic-embed-text", &json).unwrap();
+        assert_eq!(info.params_raw, 137_000_000);
+        assert_eq!(info.context_length, DEFAULT_CONTEXT_LENGTH);
+        assert_eq!(info.to_llm_model().parameter_count, "137M");
+
+        let moe = serde_json::json!({
+            "details": { "quantization_level": "Q4_0" },
+            "model_info": {
+                "general.architecture": "qwen3moe",
+                "general.parameter_count": 30_532_122_624u64,
+                "qwen3moe.expert_count": 128,
+                "qwen3moe.expert_used_count": 8
+            }
+        });
+        let info = parse_show_response("qwen3:30b-a3b", &moe).unwrap();
+        assert_eq!(info.experts, Some((128, 8)));
+        assert!(info.to_llm_model().is_moe);
+
+        let missing = serde_json::json!({ "details": {} });
+        assert!(matches!(
+            parse_show_response("x", &missing),
+            Err(OllamaError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_tags_response() {
+        let json = serde_json::json!({
+            "models": [
+                { "name": "llama3.1:8b", "size": 4_920_753_328u64 },
+                { "name": "qwen3:30b-a3b" }
+            ]
+        });
+        assert_eq!(
+            parse_tags_response(&json).unwrap(),
+            ["llama3.1:8b", "qwen3:30b-a3b"]
+        );
+        assert!(parse_tags_response(&serde_json::json!({})).is_err());
+    }
+}
@@ -0,0 +1,16 @@
+//! Where llmfit keeps its files.
+
+use std::path::PathBuf;
+
+/// `$HOME`, or `%USERPROFILE%` on Windows.
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// ~/.config/llmfit, holding the config file, themes, saved profiles and the
+/// Hugging Face config cache.
+pub fn config_dir() -> Option<PathBuf> {
+    Some(home_dir()?.join(".config").join("llmfit"))
+}
@@ -0,0 +1,181 @@
+//! Saved hardware profiles: a [`SystemSpecs`] as JSON in
+//! `~/.config/llmfit/profiles/<name>.json`, so fits can be checked against
+//! a machine you aren't sitting at.
+
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::hardware::SystemSpecs;
+
+/// Why a profile couldn't be saved, loaded or listed.
+#[derive(Debug)]
+pub enum ProfileError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    InvalidName(String), // empty, hidden, or not a plain file name
+    NotFound(String),
+    NoConfigDir, // neither HOME nor USERPROFILE is set
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::Io(e) => write!(f, "{}", e),
+            ProfileError::Json(e) => write!(f, "invalid profile: {}", e),
+            ProfileError::InvalidName(name) => write!(
+                f,
+                "invalid profile name '{}': use letters, digits, '-', '_' and '.'",
+                name
+            ),
+            ProfileError::NotFound(name) => write!(f, "no saved profile '{}'", name),
+            ProfileError::NoConfigDir => write!(f, "no home directory to store profiles in"),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ProfileError::Io(e) => Some(e),
+            ProfileError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ProfileError {
+    fn from(e: std::io::Error) -> Self {
+        ProfileError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ProfileError {
+    fn from(e: serde_json::Error) -> Self {
+        ProfileError::Json(e)
+    }
+}
+
+/// ~/.config/llmfit/profiles
+pub fn profiles_dir() -> Option<PathBuf> {
+    Some(crate::paths::config_dir()?.join("profiles"))
+}
+
+/// Names of the saved profiles, sorted.
+pub fn list_profiles() -> Result<Vec<String>, ProfileError> {
+    list_profiles_in(&profiles_dir().ok_or(ProfileError::NoConfigDir)?)
+}
+
+/// [`list_profiles`] in `dir`. A missing directory has no profiles.
+pub fn list_profiles_in(dir: &Path) -> Result<Vec<String>, ProfileError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json")
+            && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+        {
+            names.push(stem.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// `<dir>/<name>.json`, once `name` is known not to escape `dir`.
+fn profile_path(dir: &Path, name: &str) -> Result<PathBuf, ProfileError> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(ProfileError::InvalidName(name.to_string()));
+    }
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+impl SystemSpecs {
+    /// Save these specs as profile `name`, replacing any profile of that
+    /// name. Returns the file written.
+    pub fn save_profile(&self, name: &str) -> Result<PathBuf, ProfileError> {
+        self.save_profile_in(&profiles_dir().ok_or(ProfileError::NoConfigDir)?, name)
+    }
+
+    /// [`SystemSpecs::save_profile`] in `dir`.
+    pub fn save_profile_in(&self, dir: &Path, name: &str) -> Result<PathBuf, ProfileError> {
+        let path = profile_path(dir, name)?;
+        fs::create_dir_all(dir)?;
+        fs::write(&path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(path)
+    }
+
+    /// The specs saved as profile `name`.
+    pub fn load_profile(name: &str) -> Result<Self, ProfileError> {
+        Self::load_profile_in(&profiles_dir().ok_or(ProfileError::NoConfigDir)?, name)
+    }
+
+    /// [`SystemSpecs::load_profile`] from `dir`.
+    pub fn load_profile_in(dir: &Path, name: &str) -> Result<Self, ProfileError> {
+        let path = profile_path(dir, name)?;
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Err(ProfileError::NotFound(name.to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(serde_json::from_str(&text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::GpuBackend;
+
+    #[test]
+    fn test_profile_round_trip() {
+        let dir = std::env::temp_dir().join(format!("llmfit-profiles-{}", std::process::id()));
+        assert!(list_profiles_in(&dir).unwrap().is_empty());
+
+        let a100 = SystemSpecs::new_manual(256.0, Some(80.0), GpuBackend::Cuda, 1);
+        let mac = SystemSpecs::new_manual(64.0, Some(64.0), GpuBackend::Metal, 1);
+        a100.save_profile_in(&dir, "cloud-a100").unwrap();
+        mac.save_profile_in(&dir, "studio").unwrap();
+
+        assert_eq!(list_profiles_in(&dir).unwrap(), ["cloud-a100", "studio"]);
+        assert_eq!(
+            SystemSpecs::load_profile_in(&dir, "cloud-a100").unwrap(),
+            a100
+        );
+        assert_eq!(SystemSpecs::load_profile_in(&dir, "studio").unwrap(), mac);
+        assert!(matches!(
+            SystemSpecs::load_profile_in(&dir, "laptop"),
+            Err(ProfileError::NotFound(_))
+        ));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_profile_names_stay_in_dir() {
+        let dir = Path::new("/tmp/profiles");
+        assert_eq!(
+            profile_path(dir, "rtx-4090_desk.v2").unwrap(),
+            dir.join("rtx-4090_desk.v2.json")
+        );
+        for name in ["", "../etc/passwd", "a/b", ".hidden", "two words"] {
+            assert!(
+                matches!(profile_path(dir, name), Err(ProfileError::InvalidName(_))),
+                "{}",
+                name
+            );
+        }
+    }
+}
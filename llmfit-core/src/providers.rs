@@ -66,7 +66,7 @@ impl OllamaProvider {
     }
 
     /// Build the full API URL for a given endpoint path.
-    fn api_url(&self, path: &str) -> String {
+    pub(crate) fn api_url(&self, path: &str) -> String {
         format!("{}/api/{}", self.base_url.trim_end_matches('/'), path)
     }
 }
@@ -0,0 +1,18 @@
+//! `SystemSpecs::detect()` picks up `LLMFIT_OVERRIDE_*` variables. This
+//! lives in its own test binary because it changes the process environment.
+
+use llmfit_core::hardware::SystemSpecs;
+
+#[test]
+fn test_detect_applies_vram_override() {
+    // SAFETY: the only test in this binary, so no other thread reads the
+    // environment concurrently.
+    unsafe { std::env::set_var("LLMFIT_OVERRIDE_VRAM_GB", "80") };
+
+    let specs = SystemSpecs::detect();
+    assert_eq!(specs.gpu_vram_gb, Some(80.0));
+    assert!(specs.has_gpu);
+
+    // The raw probe ignores it
+    assert_ne!(SystemSpecs::detect_hardware().gpu_vram_gb, Some(80.0));
+}
@@ -0,0 +1,198 @@
+use ratatui::style::Color;
+use std::env;
+
+/// How many colors the terminal can render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor, // 24-bit RGB
+    Colors256, // xterm-256 palette
+    Ansi16,    // basic ANSI colors (linux console, dumb terminals)
+}
+
+impl ColorDepth {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ColorDepth::TrueColor => "24-bit",
+            ColorDepth::Colors256 => "256 colors",
+            ColorDepth::Ansi16 => "16 colors",
+        }
+    }
+}
+
+/// Best guess at the terminal's color depth from `COLORTERM`, `TERM` and
+/// `TERM_PROGRAM`. Unknown terminals are assumed to handle true color.
+pub fn terminal_color_depth() -> ColorDepth {
+    color_depth_from_env(
+        env::var("COLORTERM").ok().as_deref(),
+        env::var("TERM").ok().as_deref(),
+        env::var("TERM_PROGRAM").ok().as_deref(),
+    )
+}
+
+fn color_depth_from_env(
+    colorterm: Option<&str>,
+    term: Option<&str>,
+    term_program: Option<&str>,
+) -> ColorDepth {
+    if let Some(ct) = colorterm {
+        let ct = ct.to_lowercase();
+        if ct == "truecolor" || ct == "24bit" {
+            return ColorDepth::TrueColor;
+        }
+    }
+
+    match term_program {
+        Some("iTerm.app" | "WezTerm" | "vscode" | "Hyper" | "ghostty") => {
+            return ColorDepth::TrueColor;
+        }
+        // Terminal.app only understands the 256-color palette
+        Some("Apple_Terminal") => return ColorDepth::Colors256,
+        _ => {}
+    }
+
+    let Some(term) = term.map(str::to_lowercase) else {
+        return ColorDepth::TrueColor;
+    };
+    if term.contains("truecolor")
+        || term.contains("24bit")
+        || term.contains("direct")
+        || ["kitty", "alacritty", "foot", "wezterm", "ghostty"]
+            .iter()
+            .any(|t| term.contains(t))
+    {
+        ColorDepth::TrueColor
+    } else if term.contains("256color") {
+        ColorDepth::Colors256
+    } else if term == "dumb" || term == "linux" {
+        ColorDepth::Ansi16
+    } else {
+        ColorDepth::TrueColor
+    }
+}
+
+/// Whether `NO_COLOR` asks for output without color (any non-empty value,
+/// per no-color.org).
+pub fn no_color() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+/// Whether the terminal draws on a light or a dark background.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Background {
+    Light,
+    Dark,
+}
+
+/// The terminal's background as advertised in `COLORFGBG`, or `None` when
+/// it isn't set.
+pub fn terminal_background() -> Option<Background> {
+    background_from_colorfgbg(&env::var("COLORFGBG").ok()?)
+}
+
+/// `COLORFGBG` is "fg;bg" (rxvt, Konsole, iTerm2), sometimes with a field
+/// in between; the last field is the background's ANSI index. White (7)
+/// and the bright colors other than dark gray (9-15) are light.
+fn background_from_colorfgbg(value: &str) -> Option<Background> {
+    let bg: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+    Some(match bg {
+        7 | 9..=15 => Background::Light,
+        _ => Background::Dark,
+    })
+}
+
+/// Channel levels of the 6x6x6 color cube (indices 16-231).
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// RGB value of an xterm-256 palette entry in the color cube or gray ramp.
+/// The 16 system colors (0-15) are terminal-defined and have no fixed RGB.
+fn xterm_256_rgb(index: u8) -> Option<(u8, u8, u8)> {
+    match index {
+        0..=15 => None,
+        16..=231 => {
+            let i = index - 16;
+            Some((
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            ))
+        }
+        232..=255 => {
+            let v = 8 + 10 * (index - 232);
+            Some((v, v, v))
+        }
+    }
+}
+
+/// Nearest xterm-256 palette index to an RGB color by Euclidean distance.
+pub fn nearest_xterm_256(r: u8, g: u8, b: u8) -> u8 {
+    (16..=255u8)
+        .min_by_key(|&i| {
+            let (pr, pg, pb) = xterm_256_rgb(i).unwrap_or_default();
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(r, pr) + d(g, pg) + d(b, pb)
+        })
+        .unwrap_or(16)
+}
+
+/// `Color::Rgb` mapped onto the 256-color palette; other colors unchanged.
+pub fn to_256color(color: Color) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => Color::Indexed(nearest_xterm_256(r, g, b)),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colorterm_truecolor_wins() {
+        assert_eq!(
+            color_depth_from_env(Some("truecolor"), Some("xterm-256color"), None),
+            ColorDepth::TrueColor
+        );
+    }
+
+    #[test]
+    fn test_xterm_256color_without_colorterm() {
+        assert_eq!(
+            color_depth_from_env(None, Some("xterm-256color"), None),
+            ColorDepth::Colors256
+        );
+        assert_eq!(
+            color_depth_from_env(None, Some("xterm-256color"), Some("Apple_Terminal")),
+            ColorDepth::Colors256
+        );
+        assert_eq!(
+            color_depth_from_env(None, Some("xterm-256color"), Some("iTerm.app")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            color_depth_from_env(None, Some("linux"), None),
+            ColorDepth::Ansi16
+        );
+    }
+
+    #[test]
+    fn test_background_from_colorfgbg() {
+        assert_eq!(background_from_colorfgbg("0;15"), Some(Background::Light));
+        assert_eq!(
+            background_from_colorfgbg("0;default;7"),
+            Some(Background::Light)
+        );
+        assert_eq!(background_from_colorfgbg("15;0"), Some(Background::Dark));
+        assert_eq!(background_from_colorfgbg("7;8"), Some(Background::Dark));
+        assert_eq!(background_from_colorfgbg("default;default"), None);
+    }
+
+    #[test]
+    fn test_nearest_xterm_256() {
+        assert_eq!(nearest_xterm_256(0, 0, 0), 16);
+        assert_eq!(nearest_xterm_256(255, 255, 255), 231);
+        assert_eq!(nearest_xterm_256(255, 0, 0), 196);
+        // Mid gray lands on the gray ramp, not the cube
+        assert_eq!(nearest_xterm_256(128, 128, 128), 244);
+        assert_eq!(to_256color(Color::Green), Color::Green);
+    }
+}
@@ -0,0 +1,307 @@
+//! Persistent preferences in `~/.config/llmfit/config.toml`, or the file
+//! given with `--config`. Every key is optional:
+//!
+//! ```toml
+//! theme = "Tokyo Night"        # any theme name shown by `t` in the TUI, or
+//!                              # a file name from themes/ without `.toml`;
+//!                              # unset picks Default or Default Light
+//! refresh_rate_ms = 200        # TUI redraw interval, 16-5000
+//! default_context_len = 8192   # context cap when --max-context and
+//!                              # OLLAMA_CONTEXT_LENGTH are unset; 0 = none
+//! show_cpu_models = true       # list models that only run from system RAM
+//! model_db_path = "/path/to/models.json"  # replaces the built-in database
+//! overhead_gb = 1.5             # GPU memory to keep back for the runtime
+//! ```
+//!
+//! A custom palette lives separately in `theme.toml`, next to this file, and
+//! named palettes in `themes/<name>.toml`.
+
+use crate::theme::Theme;
+use llmfit_core::models::ModelDatabase;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Config directory: ~/.config/llmfit
+pub use llmfit_core::config_dir;
+
+/// Redraw interval when neither the flag nor the config file sets one.
+pub const DEFAULT_REFRESH_RATE_MS: u64 = 200;
+/// Below one frame at 60 Hz, redraws only burn CPU.
+pub const MIN_REFRESH_RATE_MS: u64 = 16;
+/// Above this, key presses feel unanswered.
+pub const MAX_REFRESH_RATE_MS: u64 = 5000;
+
+/// Config file named by `--config`, set once at startup.
+static PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// `None` until a theme is chosen; [`Theme::load`] then picks one to
+    /// suit the terminal's background.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<Theme>,
+    /// How long the event loop waits for input before redrawing.
+    pub refresh_rate_ms: u64,
+    /// Context cap (tokens) used when no flag or env var sets one; 0 leaves
+    /// each model at its full context.
+    pub default_context_len: u32,
+    /// Whether the TUI lists models that only fit in system RAM.
+    pub show_cpu_models: bool,
+    /// JSON model list to use instead of the built-in database.
+    pub model_db_path: Option<PathBuf>,
+    /// GPU memory (GB) reserved for the runtime when neither `--overhead`
+    /// nor `LLMFIT_OVERRIDE_OVERHEAD_GB` sets it; `None` uses the backend's
+    /// default.
+    pub overhead_gb: Option<f64>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            theme: None,
+            refresh_rate_ms: DEFAULT_REFRESH_RATE_MS,
+            default_context_len: 0,
+            show_cpu_models: true,
+            model_db_path: None,
+            overhead_gb: None,
+        }
+    }
+}
+
+/// Why the config file couldn't be read or written.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+    NoConfigDir, // neither HOME nor USERPROFILE is set
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "{}", e),
+            ConfigError::Parse(e) => write!(f, "invalid config file: {}", e),
+            ConfigError::Serialize(e) => write!(f, "could not encode config: {}", e),
+            ConfigError::NoConfigDir => write!(f, "no home directory to store the config in"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            ConfigError::Parse(e) => Some(e),
+            ConfigError::Serialize(e) => Some(e),
+            ConfigError::NoConfigDir => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        ConfigError::Parse(e)
+    }
+}
+
+impl From<toml::ser::Error> for ConfigError {
+    fn from(e: toml::ser::Error) -> Self {
+        ConfigError::Serialize(e)
+    }
+}
+
+/// Use `path` instead of ~/.config/llmfit/config.toml for the rest of the
+/// run. Only the first call has any effect.
+pub fn set_path_override(path: PathBuf) {
+    let _ = PATH_OVERRIDE.set(path);
+}
+
+impl Config {
+    /// The `--config` file, else ~/.config/llmfit/config.toml
+    pub fn path() -> Option<PathBuf> {
+        PATH_OVERRIDE
+            .get()
+            .cloned()
+            .or_else(|| config_dir().map(|dir| dir.join("config.toml")))
+    }
+
+    /// Read `path`. A missing file gives the defaults, keeping the theme
+    /// saved by older versions in a bare `theme` file beside it.
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(toml::from_str(&text)?),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Config {
+                theme: legacy_theme(path),
+                ..Config::default()
+            }),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), ConfigError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Load the config file, falling back to defaults. Problems, including
+    /// an out-of-range refresh rate, are reported on stderr.
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Config::default();
+        };
+        let mut config = Self::load_from(&path).unwrap_or_else(|e| {
+            eprintln!("Warning: ignoring {}: {}", path.display(), e);
+            Config::default()
+        });
+        if let Some(warning) = config.set_refresh_rate(config.refresh_rate_ms) {
+            eprintln!("Warning: {} (from {})", warning, path.display());
+        }
+        if let Some(gb) = config.overhead_gb
+            && !(gb.is_finite() && gb >= 0.0)
+        {
+            eprintln!(
+                "Warning: ignoring overhead_gb = {} (from {}); expected a non-negative number",
+                gb,
+                path.display()
+            );
+            config.overhead_gb = None;
+        }
+        config
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let path = Self::path().ok_or(ConfigError::NoConfigDir)?;
+        self.save_to(&path)
+    }
+
+    /// Set the refresh rate, clamped to
+    /// [`MIN_REFRESH_RATE_MS`]..=[`MAX_REFRESH_RATE_MS`]. Returns a warning
+    /// when `ms` was out of range.
+    pub fn set_refresh_rate(&mut self, ms: u64) -> Option<String> {
+        self.refresh_rate_ms = ms.clamp(MIN_REFRESH_RATE_MS, MAX_REFRESH_RATE_MS);
+        if ms < MIN_REFRESH_RATE_MS {
+            Some(format!(
+                "refresh rate {} ms is too fast; using {} ms",
+                ms, MIN_REFRESH_RATE_MS
+            ))
+        } else if ms > MAX_REFRESH_RATE_MS {
+            Some(format!(
+                "refresh rate {} ms would make the TUI unresponsive; using {} ms",
+                ms, MAX_REFRESH_RATE_MS
+            ))
+        } else {
+            None
+        }
+    }
+
+    pub fn refresh_rate(&self) -> Duration {
+        Duration::from_millis(self.refresh_rate_ms)
+    }
+
+    /// `default_context_len`, if set.
+    pub fn context_limit(&self) -> Option<u32> {
+        (self.default_context_len > 0).then_some(self.default_context_len)
+    }
+
+    /// The database at `model_db_path`, else the built-in one. A file that
+    /// can't be read is reported on stderr.
+    pub fn model_database(&self) -> ModelDatabase {
+        let Some(path) = &self.model_db_path else {
+            return ModelDatabase::new();
+        };
+        let loaded = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| ModelDatabase::from_json(&json).map_err(|e| e.to_string()));
+        loaded.unwrap_or_else(|e| {
+            eprintln!(
+                "Warning: ignoring model_db_path {}: {}; using the built-in database",
+                path.display(),
+                e
+            );
+            ModelDatabase::new()
+        })
+    }
+}
+
+/// Theme from the `theme` label file that predates config.toml.
+fn legacy_theme(config_path: &Path) -> Option<Theme> {
+    let label = fs::read_to_string(config_path.with_file_name("theme")).ok()?;
+    Some(Theme::from_label(label.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_refresh_rate_clamps_and_warns() {
+        let mut config = Config::default();
+        assert_eq!(config.set_refresh_rate(100), None);
+        assert_eq!(config.refresh_rate(), Duration::from_millis(100));
+
+        assert!(config.set_refresh_rate(5).unwrap().contains("too fast"));
+        assert_eq!(config.refresh_rate_ms, MIN_REFRESH_RATE_MS);
+
+        assert!(config.set_refresh_rate(60_000).is_some());
+        assert_eq!(config.refresh_rate_ms, MAX_REFRESH_RATE_MS);
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let dir = std::env::temp_dir().join(format!("llmfit-config-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        assert_eq!(Config::load_from(&path).unwrap(), Config::default());
+
+        let config = Config {
+            theme: Some(Theme::TokyoNight),
+            refresh_rate_ms: 500,
+            default_context_len: 8192,
+            show_cpu_models: false,
+            model_db_path: Some(PathBuf::from("/srv/models.json")),
+            overhead_gb: Some(1.5),
+        };
+        config.save_to(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.contains("theme = \"Tokyo Night\""));
+        assert!(text.contains("refresh_rate_ms = 500"));
+        assert!(text.contains("overhead_gb = 1.5"));
+        assert_eq!(Config::load_from(&path).unwrap(), config);
+        assert_eq!(config.context_limit(), Some(8192));
+
+        // Keys left out keep their defaults
+        fs::write(&path, "").unwrap();
+        assert_eq!(Config::load_from(&path).unwrap(), Config::default());
+        assert_eq!(Config::default().context_limit(), None);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_config_migrates_legacy_theme_file() {
+        let dir = std::env::temp_dir().join(format!("llmfit-legacy-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("theme"), "Nord\n").unwrap();
+
+        let config = Config::load_from(&dir.join("config.toml")).unwrap();
+        assert_eq!(config.theme, Some(Theme::Nord));
+        assert_eq!(config.refresh_rate_ms, DEFAULT_REFRESH_RATE_MS);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -1,5 +1,5 @@
 use colored::*;
-use llmfit_core::fit::{FitLevel, ModelFit};
+use llmfit_core::fit::{FitLevel, ModelFit, estimate_tokens_per_second};
 use llmfit_core::hardware::SystemSpecs;
 use llmfit_core::models::LlmModel;
 use tabled::{Table, Tabled, settings::Style};
@@ -92,7 +92,7 @@ pub fn display_model_fits(fits: &[ModelFit]) {
     println!("{}", table);
 }
 
-pub fn display_model_detail(fit: &ModelFit) {
+pub fn display_model_detail(fit: &ModelFit, specs: &SystemSpecs) {
     println!("\n{}", format!("=== {} ===", fit.model.name).bold().cyan());
     println!();
     println!("{}: {}", "Provider".bold(), fit.model.provider);
@@ -127,6 +127,11 @@ pub fn display_model_detail(fit: &ModelFit) {
         fit.score_components.context
     );
     println!("  Estimated Speed: {:.1} tok/s", fit.estimated_tps);
+    println!(
+        "  Bandwidth Bound: ~{:.1} tok/s ({})",
+        estimate_tokens_per_second(specs, &fit.model, fit.run_mode),
+        fit.run_mode_text()
+    );
     println!();
 
     println!("{}", "Resource Requirements:".bold().underline());
@@ -176,6 +181,14 @@ pub fn display_model_detail(fit: &ModelFit) {
         fit.fit_text().color(fit_color)
     );
     println!("  Run Mode: {}", fit.run_mode_text());
+    if let Some(layers) = fit.gpu_layers {
+        println!(
+            "  GPU Layers: {}/{} (--n-gpu-layers {})",
+            layers,
+            fit.model.estimated_layer_count(),
+            layers
+        );
+    }
     println!(
         "  Memory Utilization: {:.1}% ({:.1} / {:.1} GB)",
         fit.utilization_pct, fit.memory_required_gb, fit.memory_available_gb
@@ -233,9 +246,15 @@ pub fn display_search_results(models: &[&LlmModel], query: &str) {
 // JSON output for machine consumption (OpenClaw skills, scripts, etc.)
 // ────────────────────────────────────────────────────────────────────
 
+/// Version of the `--json` document layout. Bumped only when a field is
+/// removed, renamed or changes type; new fields don't bump it.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
 /// Serialize system specs to JSON and print to stdout.
 pub fn display_json_system(specs: &SystemSpecs) {
     let output = serde_json::json!({
+        "schema_version": JSON_SCHEMA_VERSION,
+        "llmfit_version": env!("CARGO_PKG_VERSION"),
         "system": system_json(specs),
     });
     println!(
@@ -246,17 +265,25 @@ pub fn display_json_system(specs: &SystemSpecs) {
 
 /// Serialize system specs + model fits to JSON and print to stdout.
 pub fn display_json_fits(specs: &SystemSpecs, fits: &[ModelFit]) {
-    let models: Vec<serde_json::Value> = fits.iter().map(fit_to_json).collect();
-    let output = serde_json::json!({
-        "system": system_json(specs),
-        "models": models,
-    });
     println!(
         "{}",
-        serde_json::to_string_pretty(&output).expect("JSON serialization failed")
+        serde_json::to_string_pretty(&results_json(specs, fits))
+            .expect("JSON serialization failed")
     );
 }
 
+/// `{"schema_version": 1, "llmfit_version": ..., "system": ..., "models":
+/// [...]}`, as printed by `--json` and written by `--output`.
+pub fn results_json(specs: &SystemSpecs, fits: &[ModelFit]) -> serde_json::Value {
+    let models: Vec<serde_json::Value> = fits.iter().map(fit_to_json).collect();
+    serde_json::json!({
+        "schema_version": JSON_SCHEMA_VERSION,
+        "llmfit_version": env!("CARGO_PKG_VERSION"),
+        "system": system_json(specs),
+        "models": models,
+    })
+}
+
 fn system_json(specs: &SystemSpecs) -> serde_json::Value {
     let gpus_json: Vec<serde_json::Value> = specs
         .gpus
@@ -268,6 +295,7 @@ fn system_json(specs: &SystemSpecs) -> serde_json::Value {
                 "backend": g.backend.label(),
                 "count": g.count,
                 "unified_memory": g.unified_memory,
+                "class": g.class.label(),
             })
         })
         .collect();
@@ -275,14 +303,40 @@ fn system_json(specs: &SystemSpecs) -> serde_json::Value {
     serde_json::json!({
         "total_ram_gb": round2(specs.total_ram_gb),
         "available_ram_gb": round2(specs.available_ram_gb),
+        "swap_total_gb": round2(specs.swap_total_gb),
+        "swap_available_gb": round2(specs.swap_available_gb),
+        "disk_available_gb": specs.disk_available_gb.map(round2),
+        "nvme_read_bandwidth_gb_s": specs.nvme_read_bandwidth_gb_s.map(round2),
+        "mlock_limit_gb": specs.mlock_limit_gb.map(round2),
         "cpu_cores": specs.total_cpu_cores,
         "cpu_name": specs.cpu_name,
+        "cpu_features": specs.cpu_features,
+        "cpu_inference_path": specs.cpu_inference_path(),
         "has_gpu": specs.has_gpu,
         "gpu_vram_gb": specs.gpu_vram_gb.map(round2),
+        "gpu_vram_gb_per_device": specs.gpu_vram_gb_per_device.iter().map(|v| round2(*v)).collect::<Vec<_>>(),
+        "gpu_vram_available_gb": specs.gpu_vram_available_gb.map(round2),
+        "gpu_vram_used_gb": specs.gpu_vram_used_gb.map(round2),
+        "vram_contended": specs.vram_contended(),
         "gpu_name": specs.gpu_name,
         "gpu_count": specs.gpu_count,
+        "gpu_link_topology": specs.gpu_link_topology.label(),
+        "nvlink": specs.gpu_link_topology.has_nvlink(),
+        "gpu_compute_capability": specs.compute_capability_label(),
+        "supports_flash_attention_2": specs.supports_flash_attention_2(),
+        "rocm_version": specs.rocm_version,
+        "gpu_driver_version": specs.gpu_driver_version,
+        "gpu_temperature_c": specs.gpu_temperature_c,
+        "container_env": specs.container_env,
         "unified_memory": specs.unified_memory,
         "backend": specs.backend.label(),
+        "apple_chip_variant": specs.apple_chip_variant.map(|v| v.label()),
+        "jetson_variant": specs.jetson_variant.map(|v| v.label()),
+        "gpu_core_count": specs.gpu_core_count,
+        "pcie_gen": specs.pcie_gen,
+        "pcie_width": specs.pcie_width,
+        "numa_nodes": specs.numa_nodes,
+        "memory_bandwidth_gbps": specs.memory_bandwidth_gbps.map(round1),
         "gpus": gpus_json,
     })
 }
@@ -300,6 +354,8 @@ fn fit_to_json(fit: &ModelFit) -> serde_json::Value {
         "is_moe": fit.model.is_moe,
         "fit_level": fit.fit_text(),
         "run_mode": fit.run_mode_text(),
+        "gpu_layers": fit.gpu_layers,
+        "mmap_tokens_per_sec": fit.mmap_tokens_per_sec.map(round2),
         "score": round1(fit.score),
         "score_components": {
             "quality": round1(fit.score_components.quality),
@@ -0,0 +1,488 @@
+use llmfit_core::fit::{FitLevel, ModelFit};
+use llmfit_core::hardware::SystemSpecs;
+use llmfit_core::models;
+use std::fmt;
+use std::path::Path;
+
+use crate::display::results_json;
+
+/// File formats fit results can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,     // same document as --json: {"system": ..., "models": [...]}
+    Toml,     // the JSON document as TOML; null fields are left out
+    Csv,      // one row per model, memory columns in GB
+    Markdown, // system header plus a model table, for GitHub issues
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 4] = [
+        ExportFormat::Json,
+        ExportFormat::Toml,
+        ExportFormat::Csv,
+        ExportFormat::Markdown,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Toml => "toml",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "markdown",
+        }
+    }
+
+    /// File extension for this format, without the dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Toml => "toml",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+        }
+    }
+
+    /// Cycle to the next format (the TUI export prompt's Tab key).
+    pub fn next(&self) -> Self {
+        match self {
+            ExportFormat::Json => ExportFormat::Toml,
+            ExportFormat::Toml => ExportFormat::Csv,
+            ExportFormat::Csv => ExportFormat::Markdown,
+            ExportFormat::Markdown => ExportFormat::Json,
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "toml" => Ok(ExportFormat::Toml),
+            "csv" => Ok(ExportFormat::Csv),
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            _ => Err(format!(
+                "unknown format '{}', expected one of: {}",
+                s,
+                ExportFormat::ALL.map(|f| f.label()).join(", ")
+            )),
+        }
+    }
+}
+
+/// Why results couldn't be exported.
+#[derive(Debug)]
+pub enum ExportError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Toml(toml::ser::Error),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(e) => write!(f, "{}", e),
+            ExportError::Json(e) => write!(f, "could not encode JSON: {}", e),
+            ExportError::Toml(e) => write!(f, "could not encode TOML: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExportError::Io(e) => Some(e),
+            ExportError::Json(e) => Some(e),
+            ExportError::Toml(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ExportError {
+    fn from(e: serde_json::Error) -> Self {
+        ExportError::Json(e)
+    }
+}
+
+impl From<toml::ser::Error> for ExportError {
+    fn from(e: toml::ser::Error) -> Self {
+        ExportError::Toml(e)
+    }
+}
+
+/// Write `specs` and `fits` to `path` in `format`, replacing any existing file.
+pub fn export_results(
+    specs: &SystemSpecs,
+    fits: &[ModelFit],
+    path: &Path,
+    format: ExportFormat,
+) -> Result<(), ExportError> {
+    std::fs::write(path, render_results(specs, fits, format)?)?;
+    Ok(())
+}
+
+/// The exported document as a string.
+pub fn render_results(
+    specs: &SystemSpecs,
+    fits: &[ModelFit],
+    format: ExportFormat,
+) -> Result<String, ExportError> {
+    Ok(match format {
+        ExportFormat::Json => {
+            let json = results_json(specs, fits);
+            serde_json::to_string_pretty(&json)? + "\n"
+        }
+        ExportFormat::Toml => toml::to_string(&strip_nulls(results_json(specs, fits)))?,
+        ExportFormat::Csv => render_csv(specs, fits),
+        ExportFormat::Markdown => render_markdown(specs, fits),
+    })
+}
+
+/// TOML has no null, so drop null fields and array entries.
+fn strip_nulls(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, strip_nulls(v)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .filter(|v| !v.is_null())
+                .map(strip_nulls)
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+const CSV_HEADER: &str = "model_name,params_b,quantization,weight_vram_gb,kvcache_vram_gb_4k,\
+total_vram_gb,available_vram_gb,fit_level,estimated_toks_per_sec,backend";
+
+/// Context length the `kvcache_vram_gb_4k` column is sized at.
+const CSV_KV_CONTEXT: u32 = 4_096;
+
+/// One row per model, RFC 4180 style: CRLF line endings, fields quoted only
+/// when they need it. `total_vram_gb` is the estimate the fit level was
+/// judged on; `available_vram_gb` is the VRAM it was judged against, after
+/// runtime overhead and memory other processes hold, and is empty on a
+/// machine without a GPU.
+fn render_csv(specs: &SystemSpecs, fits: &[ModelFit]) -> String {
+    let available_vram = specs
+        .available_gpu_memory_gb()
+        .map(|vram| format!("{:.2}", vram))
+        .unwrap_or_default();
+    let backend = csv_field(specs.backend.label());
+
+    let mut out = String::from(CSV_HEADER);
+    out.push_str("\r\n");
+    for fit in fits {
+        let params_b = fit.model.params_b();
+        let fields = [
+            csv_field(&fit.model.name),
+            format!("{:.2}", params_b),
+            csv_field(&fit.best_quant),
+            format!("{:.2}", params_b * models::quant_bpp(&fit.best_quant)),
+            format!("{:.2}", fit.model.kv_cache_gb(CSV_KV_CONTEXT)),
+            format!("{:.2}", fit.memory_required_gb),
+            available_vram.clone(),
+            fit.fit_text().to_string(),
+            format!("{:.1}", fit.estimated_tps),
+            backend.clone(),
+        ];
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Fit level with an emoji, so the verdict reads at a glance in a rendered
+/// issue or doc.
+fn markdown_fit_level(level: FitLevel) -> &'static str {
+    match level {
+        FitLevel::Perfect => "✅ Perfect",
+        FitLevel::Good => "✅ Good",
+        FitLevel::Marginal => "⚠️ Marginal",
+        FitLevel::TooTight => "❌ Too Tight",
+    }
+}
+
+/// Escape characters that would break out of a Markdown table cell.
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|")
+}
+
+/// CPU, RAM and GPU lines heading the Markdown and plain-text reports, as
+/// (label, value) pairs.
+fn system_summary(specs: &SystemSpecs) -> [(&'static str, String); 3] {
+    let gpu = if specs.has_gpu {
+        let name = specs.gpu_name.as_deref().unwrap_or("Unknown GPU");
+        let count = if specs.gpu_count > 1 {
+            format!("{}x ", specs.gpu_count)
+        } else {
+            String::new()
+        };
+        let vram = match specs.gpu_vram_gb {
+            Some(vram) if specs.unified_memory => format!("{:.1} GB shared", vram),
+            Some(vram) => format!("{:.1} GB VRAM", vram),
+            None => "VRAM unknown".to_string(),
+        };
+        format!("{}{} ({}, {})", count, name, vram, specs.backend.label())
+    } else {
+        "none".to_string()
+    };
+    [
+        (
+            "CPU",
+            format!("{} ({} cores)", specs.cpu_name, specs.total_cpu_cores),
+        ),
+        (
+            "RAM",
+            format!(
+                "{:.1} GB total, {:.1} GB available",
+                specs.total_ram_gb, specs.available_ram_gb
+            ),
+        ),
+        ("GPU", gpu),
+    ]
+}
+
+fn render_markdown(specs: &SystemSpecs, fits: &[ModelFit]) -> String {
+    let mut out = String::from("### System\n\n");
+    for (label, value) in system_summary(specs) {
+        out.push_str(&format!("- **{}:** {}\n", label, markdown_cell(&value)));
+    }
+
+    // Without a GPU the estimate is system RAM, so say so
+    let memory_header = if specs.has_gpu {
+        "Est. VRAM"
+    } else {
+        "Est. RAM"
+    };
+    out.push_str(&format!(
+        "\n### Models\n\n\
+         | Model | Params | Quant | {} | Fit Level | Est. Tok/s |\n\
+         |---|---:|---|---:|---|---:|\n",
+        memory_header
+    ));
+    for fit in fits {
+        out.push_str(&format!(
+            "| {} | {} | {} | {:.1} GB | {} | {:.1} |\n",
+            markdown_cell(&fit.model.name),
+            fit.model.parameter_count,
+            fit.best_quant,
+            fit.memory_required_gb,
+            markdown_fit_level(fit.fit_level),
+            fit.estimated_tps
+        ));
+    }
+    out
+}
+
+/// Width of every column but the model name in [`render_plain`], with the
+/// two-space gaps between columns.
+const PLAIN_FIXED_WIDTH: usize = 7 + 8 + 10 + 10 + 7 + 5 * 2;
+
+/// The Markdown report as an aligned, uncolored text table for terminals
+/// and logs (`--no-tui`). The model column shrinks, truncating names, to
+/// keep rows within `width` columns where it can.
+pub fn render_plain(specs: &SystemSpecs, fits: &[ModelFit], width: usize) -> String {
+    let mut out = String::from("System\n");
+    for (label, value) in system_summary(specs) {
+        out.push_str(&format!("  {}: {}\n", label, value));
+    }
+
+    let memory_header = if specs.has_gpu {
+        "Est. VRAM"
+    } else {
+        "Est. RAM"
+    };
+    let name_width = width.saturating_sub(PLAIN_FIXED_WIDTH).max(12);
+    let row = |name: &str, params: &str, quant: &str, mem: &str, fit: &str, tps: &str| {
+        format!(
+            "{:<nw$}  {:>7}  {:<8}  {:>10}  {:<10}  {:>7}\n",
+            truncate_name(name, name_width),
+            params,
+            quant,
+            mem,
+            fit,
+            tps,
+            nw = name_width
+        )
+    };
+    out.push('\n');
+    out.push_str(&row(
+        "Model",
+        "Params",
+        "Quant",
+        memory_header,
+        "Fit",
+        "Tok/s",
+    ));
+    out.push_str(&"-".repeat(name_width + PLAIN_FIXED_WIDTH));
+    out.push('\n');
+    for fit in fits {
+        out.push_str(&row(
+            &fit.model.name,
+            &fit.model.parameter_count,
+            &fit.best_quant,
+            &format!("{:.1} GB", fit.memory_required_gb),
+            fit.fit_level.label(),
+            &format!("{:.1}", fit.estimated_tps),
+        ));
+    }
+    out
+}
+
+/// `name` cut to `width` characters, ending in "…" when shortened.
+fn truncate_name(name: &str, width: usize) -> String {
+    if name.chars().count() <= width {
+        return name.to_string();
+    }
+    let mut cut: String = name.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llmfit_core::hardware::GpuBackend;
+    use llmfit_core::models::{LlmModel, ModelDatabase};
+
+    fn sample() -> (SystemSpecs, Vec<ModelFit>) {
+        let specs = SystemSpecs::mock(12.0, 32.0, GpuBackend::Cuda);
+        let db = ModelDatabase::new();
+        let fits = db.get_all_models()[..3]
+            .iter()
+            .map(|m| ModelFit::analyze(m, &specs))
+            .collect();
+        (specs, fits)
+    }
+
+    #[test]
+    fn test_export_formats_round_trip() {
+        let (specs, fits) = sample();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_results(&specs, &fits, ExportFormat::Json).unwrap())
+                .unwrap();
+        assert_eq!(json["schema_version"], crate::display::JSON_SCHEMA_VERSION);
+        assert_eq!(json["models"].as_array().unwrap().len(), 3);
+        assert_eq!(json["system"]["total_ram_gb"], 32.0);
+
+        let toml_doc: toml::Value =
+            toml::from_str(&render_results(&specs, &fits, ExportFormat::Toml).unwrap()).unwrap();
+        assert_eq!(toml_doc["models"].as_array().unwrap().len(), 3);
+
+        let csv = render_results(&specs, &fits, ExportFormat::Csv).unwrap();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.starts_with("model_name,params_b,quantization,"));
+
+        let md = render_results(&specs, &fits, ExportFormat::Markdown).unwrap();
+        assert!(md.contains("**GPU:** Manual GPU (12.0 GB VRAM, CUDA)"));
+        assert!(md.contains("| Model | Params | Quant | Est. VRAM | Fit Level | Est. Tok/s |"));
+        assert_eq!(md.lines().filter(|l| l.starts_with("| ")).count(), 4);
+    }
+
+    #[test]
+    fn test_markdown_fit_level_emoji() {
+        assert_eq!(markdown_fit_level(FitLevel::Good), "✅ Good");
+        assert_eq!(markdown_fit_level(FitLevel::Marginal), "⚠️ Marginal");
+        assert_eq!(markdown_fit_level(FitLevel::TooTight), "❌ Too Tight");
+        assert_eq!(markdown_cell("a|b"), "a\\|b");
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\r\nlines"), "\"two\r\nlines\"");
+    }
+
+    #[test]
+    fn test_csv_export_fixture() {
+        let specs = SystemSpecs::mock(24.0, 64.0, GpuBackend::Cuda);
+        let model = LlmModel {
+            name: "Acme, Inc. Chat 7B".to_string(),
+            provider: "Acme".to_string(),
+            parameter_count: "7B".to_string(),
+            parameters_raw: Some(7_000_000_000),
+            min_ram_gb: 6.0,
+            recommended_ram_gb: 10.0,
+            min_vram_gb: Some(5.0),
+            quantization: "Q4_K_M".to_string(),
+            context_length: 8192,
+            use_case: "Chat".to_string(),
+            is_moe: false,
+            num_experts: None,
+            active_experts: None,
+            active_parameters: None,
+            release_date: None,
+        };
+        let mut fit = ModelFit::analyze(&model, &specs);
+        // Pin the estimates so the row doesn't move with the heuristics
+        fit.best_quant = "Q4_K_M".to_string();
+        fit.memory_required_gb = 5.25;
+        fit.fit_level = FitLevel::Perfect;
+        fit.estimated_tps = 42.0;
+
+        let path = std::env::temp_dir().join(format!("llmfit-export-{}.csv", std::process::id()));
+        export_results(&specs, &[fit], &path, ExportFormat::Csv).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let lines: Vec<&str> = csv.split_terminator("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], CSV_HEADER);
+        // 24 GB card less 1 GB of CUDA runtime overhead
+        assert_eq!(
+            lines[1],
+            "\"Acme, Inc. Chat 7B\",7.00,Q4_K_M,4.06,0.23,5.25,23.00,Perfect,42.0,CUDA"
+        );
+    }
+
+    #[test]
+    fn test_render_plain_fits_width() {
+        let (specs, fits) = sample();
+        let text = render_plain(&specs, &fits, 80);
+        assert!(text.contains("  GPU: Manual GPU (12.0 GB VRAM, CUDA)\n"));
+        let table: Vec<&str> = text
+            .lines()
+            .skip_while(|l| !l.starts_with("Model"))
+            .collect();
+        assert_eq!(table.len(), 2 + fits.len());
+        assert!(table.iter().all(|l| l.chars().count() <= 80));
+        assert!(table[0].contains("Est. VRAM"));
+        assert!(!text.contains('\x1b'));
+
+        assert_eq!(truncate_name("Llama 3.1 8B", 12), "Llama 3.1 8B");
+        assert_eq!(truncate_name("Llama 3.1 70B Instruct", 12), "Llama 3.1 7…");
+    }
+
+    #[test]
+    fn test_export_format_from_str() {
+        assert_eq!("MD".parse::<ExportFormat>(), Ok(ExportFormat::Markdown));
+        assert!("yaml".parse::<ExportFormat>().is_err());
+    }
+}
@@ -0,0 +1,241 @@
+//! Past hardware detections, kept in `~/.local/share/llmfit/history.jsonl`:
+//! one JSON record per line, oldest first, at most [`MAX_ENTRIES`].
+
+use llmfit_core::hardware::SystemSpecs;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Detections kept; older ones drop off the front.
+pub const MAX_ENTRIES: usize = 50;
+
+/// One hardware detection run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub specs: SystemSpecs,
+}
+
+impl HistoryEntry {
+    /// An entry for `specs` stamped with the current time.
+    pub fn now(specs: SystemSpecs) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        HistoryEntry { timestamp, specs }
+    }
+
+    /// "2026-03-01 14:05 UTC"
+    pub fn timestamp_label(&self) -> String {
+        format_timestamp(self.timestamp)
+    }
+
+    /// Primary GPU name with its card count, or "none".
+    pub fn gpu_label(&self) -> String {
+        match &self.specs.gpu_name {
+            Some(name) if self.specs.has_gpu && self.specs.gpu_count > 1 => {
+                format!("{}x {}", self.specs.gpu_count, name)
+            }
+            Some(name) if self.specs.has_gpu => name.clone(),
+            _ => "none".to_string(),
+        }
+    }
+}
+
+/// Why the history couldn't be read or written.
+#[derive(Debug)]
+pub enum HistoryError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    NoDataDir, // neither XDG_DATA_HOME nor HOME/USERPROFILE is set
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::Io(e) => write!(f, "{}", e),
+            HistoryError::Json(e) => write!(f, "could not encode history entry: {}", e),
+            HistoryError::NoDataDir => write!(f, "no home directory to store history in"),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HistoryError::Io(e) => Some(e),
+            HistoryError::Json(e) => Some(e),
+            HistoryError::NoDataDir => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for HistoryError {
+    fn from(e: std::io::Error) -> Self {
+        HistoryError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for HistoryError {
+    fn from(e: serde_json::Error) -> Self {
+        HistoryError::Json(e)
+    }
+}
+
+/// `$XDG_DATA_HOME/llmfit/history.jsonl`, else
+/// `~/.local/share/llmfit/history.jsonl`.
+pub fn history_path() -> Option<PathBuf> {
+    let data_dir = std::env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| Some(llmfit_core::paths::home_dir()?.join(".local").join("share")))?;
+    Some(data_dir.join("llmfit").join("history.jsonl"))
+}
+
+/// Entries in `path`, oldest first. A missing file is an empty history;
+/// lines that don't parse (e.g. from an older llmfit) are skipped.
+pub fn load(path: &Path) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Append `entry` to `path`, dropping the oldest entries beyond
+/// [`MAX_ENTRIES`]. Returns the history as written.
+pub fn append(path: &Path, entry: HistoryEntry) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let mut entries = load(path)?;
+    entries.push(entry);
+    if entries.len() > MAX_ENTRIES {
+        entries.drain(..entries.len() - MAX_ENTRIES);
+    }
+
+    let mut text = String::new();
+    for entry in &entries {
+        text.push_str(&serde_json::to_string(entry)?);
+        text.push('\n');
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, text)?;
+    Ok(entries)
+}
+
+/// Record a detection in the default history file.
+pub fn record(specs: &SystemSpecs) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let path = history_path().ok_or(HistoryError::NoDataDir)?;
+    append(&path, HistoryEntry::now(specs.clone()))
+}
+
+/// The default history file's entries; empty if it can't be read.
+pub fn load_default() -> Vec<HistoryEntry> {
+    history_path()
+        .and_then(|path| load(&path).ok())
+        .unwrap_or_default()
+}
+
+/// Format Unix seconds as a UTC date and time, without pulling in a date
+/// crate.
+fn format_timestamp(secs: u64) -> String {
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60
+    )
+}
+
+/// Days since 1970-01-01 to (year, month, day) in the proleptic Gregorian
+/// calendar (Howard Hinnant's `civil_from_days`).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llmfit_core::hardware::GpuBackend;
+
+    fn temp_history(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("llmfit-history-{}-{}", name, std::process::id()))
+            .join("history.jsonl")
+    }
+
+    #[test]
+    fn test_history_round_trip_and_fifo_cap() {
+        let path = temp_history("fifo");
+        let _ = fs::remove_file(&path);
+        let specs = SystemSpecs::mock(12.0, 32.0, GpuBackend::Cuda);
+
+        for i in 0..(MAX_ENTRIES as u64 + 3) {
+            let entry = HistoryEntry {
+                timestamp: i,
+                specs: specs.clone(),
+            };
+            append(&path, entry).unwrap();
+        }
+        let entries = load(&path).unwrap();
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        // The three oldest dropped off the front
+        assert_eq!(entries[0].timestamp, 3);
+        assert_eq!(entries.last().unwrap().specs, specs);
+        assert_eq!(entries[0].gpu_label(), "Manual GPU");
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_history_skips_bad_lines_and_missing_file() {
+        let path = temp_history("bad");
+        assert!(load(&path).unwrap().is_empty());
+
+        let specs = SystemSpecs::mock(0.0, 16.0, GpuBackend::CpuX86);
+        append(&path, HistoryEntry::now(specs)).unwrap();
+        let mut text = fs::read_to_string(&path).unwrap();
+        text.push_str("{not json\n");
+        fs::write(&path, text).unwrap();
+
+        let entries = load(&path).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].gpu_label(), "none");
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_timestamp(951_868_800), "2000-03-01 00:00 UTC");
+        // Leap day, 13:45
+        assert_eq!(
+            format_timestamp(1_709_164_800 + 13 * 3600 + 45 * 60),
+            "2024-02-29 13:45 UTC"
+        );
+    }
+}
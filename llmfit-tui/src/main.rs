@@ -1,13 +1,27 @@
+mod color_support;
+mod config;
 mod display;
+mod export;
+mod history;
+mod memory_history;
+mod mouse;
+mod profiles;
 mod theme;
 mod tui_app;
 mod tui_events;
 mod tui_ui;
+mod widgets;
 
 use clap::{Parser, Subcommand};
-use llmfit_core::fit::ModelFit;
+use config::Config;
+use export::ExportFormat;
+use llmfit_core::fit::{FitLevel, ModelFit, RunMode};
 use llmfit_core::hardware::SystemSpecs;
-use llmfit_core::models::ModelDatabase;
+use llmfit_core::huggingface;
+use llmfit_core::models::{LlmModel, ModelDatabase};
+use llmfit_core::profile;
+use llmfit_core::providers::OllamaProvider;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(name = "llmfit")]
@@ -29,19 +43,70 @@ struct Cli {
     #[arg(long)]
     cli: bool,
 
+    /// Print fit results as a plain text table and exit, for SSH sessions
+    /// and scripts. Exits with the best listed model's verdict: 0 if one
+    /// fits well (Good or Perfect), 1 if the best is only Marginal, 2 if
+    /// none fit. Honours --perfect, so `--no-tui --perfect` exits 2 when no
+    /// model is a perfect fit.
+    #[arg(long = "no-tui", visible_alias = "plain")]
+    no_tui: bool,
+
+    /// Table width for --no-tui, in columns (default: terminal width)
+    #[arg(long, value_name = "COLS")]
+    width: Option<usize>,
+
     /// Output results as JSON (for tool integration)
     #[arg(long)]
     json: bool,
 
+    /// Write fit results to a file instead of printing them.
+    /// Without a subcommand, exports every model like `fit` does.
+    #[arg(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+
+    /// File format for --output: json (default), toml, csv, markdown.
+    /// `batch` prints in this format, defaulting to csv.
+    #[arg(long, value_name = "FORMAT")]
+    format: Option<ExportFormat>,
+
     /// Override GPU VRAM size (e.g. "32G", "32000M", "1.5T").
     /// Useful when GPU memory autodetection fails.
     #[arg(long, value_name = "SIZE")]
     memory: Option<String>,
 
+    /// GPU memory (GB) to keep back for the runtime's context, compute
+    /// buffers and fragmentation. Defaults by backend (CUDA/ROCm 1.0,
+    /// Metal 0.25). Also read from LLMFIT_OVERRIDE_OVERHEAD_GB, then the
+    /// config file's overhead_gb.
+    #[arg(long, value_name = "GB")]
+    overhead: Option<f64>,
+
     /// Cap context length used for memory estimation (tokens).
-    /// Falls back to OLLAMA_CONTEXT_LENGTH if not set.
+    /// Falls back to OLLAMA_CONTEXT_LENGTH, then the config file's
+    /// default_context_len.
     #[arg(long, value_name = "TOKENS", value_parser = clap::value_parser!(u32).range(1..))]
     max_context: Option<u32>,
+
+    /// Enable mouse support in the TUI: click a row to select it,
+    /// double-click for details, click the theme box to cycle themes.
+    /// Also enabled by LLMFIT_MOUSE=1. Disables terminal text selection.
+    #[arg(long)]
+    mouse: bool,
+
+    /// How often the TUI redraws while idle, in milliseconds (16-5000,
+    /// default 200). Saved to ~/.config/llmfit/config.toml for later runs.
+    #[arg(long, value_name = "MS")]
+    refresh_rate: Option<u64>,
+
+    /// Read and save preferences in this file instead of
+    /// ~/.config/llmfit/config.toml
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Fit against a saved hardware profile (see `llmfit profile`)
+    /// instead of this machine
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -75,6 +140,42 @@ enum Commands {
         model: String,
     },
 
+    /// Check whether one model fits, for CI gates. Exits 0 if it fits in
+    /// GPU memory (or RAM on a machine without a GPU), 1 if it only runs
+    /// by offloading to system RAM, 2 if it doesn't fit, 3 if the model
+    /// can't be found. The verdict goes to stderr; stdout carries only
+    /// --json output.
+    Check {
+        /// Model name, Hugging Face repo or Ollama tag
+        model: String,
+
+        /// Memory the model may use (e.g. "16G"): GPU memory on a GPU
+        /// machine, system RAM otherwise
+        #[arg(long, value_name = "SIZE")]
+        budget: Option<String>,
+    },
+
+    /// Fit a list of models and print one row per model, as CSV unless
+    /// --format says otherwise. Models that can't be found are skipped
+    /// with a warning.
+    Batch {
+        /// File with one model name, Hugging Face repo or Ollama tag per
+        /// line ("-" for stdin). Blank lines and `#` comments are skipped.
+        #[arg(required_unless_present = "ollama")]
+        file: Option<PathBuf>,
+
+        /// Fit every model pulled into the local Ollama instead
+        #[arg(long, conflicts_with = "file")]
+        ollama: bool,
+    },
+
+    /// Save and list hardware profiles in ~/.config/llmfit/profiles, for
+    /// use with --profile
+    Profile {
+        #[command(subcommand)]
+        action: ProfileCommand,
+    },
+
     /// Recommend top models for your hardware (JSON-friendly)
     Recommend {
         /// Limit number of recommendations
@@ -99,32 +200,89 @@ enum Commands {
     },
 }
 
-/// Detect system specs with optional GPU memory override.
-fn detect_specs(memory_override: &Option<String>) -> SystemSpecs {
-    let specs = SystemSpecs::detect();
-    if let Some(mem_str) = memory_override {
-        match llmfit_core::hardware::parse_memory_size(mem_str) {
-            Some(gb) => specs.with_gpu_memory_override(gb),
-            None => {
-                eprintln!(
-                    "Warning: could not parse --memory value '{}'. Expected format: 32G, 32000M, 1.5T",
-                    mem_str
-                );
-                specs
+#[derive(Subcommand)]
+enum ProfileCommand {
+    /// Save this machine's specs (after --memory, --overhead and
+    /// LLMFIT_OVERRIDE_* are applied) under NAME
+    Save {
+        /// Letters, digits, '-', '_' and '.'
+        name: String,
+    },
+
+    /// List saved profiles
+    List,
+}
+
+/// Hardware overrides given on the command line.
+#[derive(Clone)]
+struct SpecOverrides {
+    /// `--profile`: specs to use instead of detecting the hardware.
+    profile: Option<SystemSpecs>,
+    memory: Option<String>,
+    overhead_gb: Option<f64>,
+    /// `overhead_gb` from the config file, below the environment.
+    config_overhead_gb: Option<f64>,
+}
+
+/// Detect system specs with optional GPU memory and overhead overrides.
+/// `LLMFIT_OVERRIDE_*` environment overrides are applied first, so
+/// `--memory` wins over `LLMFIT_OVERRIDE_VRAM_GB` and `--overhead` over
+/// `LLMFIT_OVERRIDE_OVERHEAD_GB`. A saved profile replaces detection and
+/// the environment overrides, but not the flags. Warnings go to stderr.
+fn detect_specs(overrides: &SpecOverrides) -> SystemSpecs {
+    let (specs, warnings) = detect_specs_with_warnings(overrides, false);
+    for warning in warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    specs
+}
+
+/// [`detect_specs`], returning its warnings instead of printing them, for
+/// the TUI to show once the terminal is restored. `record_history` adds the
+/// detected hardware to the scan history; only the TUI, which browses it,
+/// asks for that.
+fn detect_specs_with_warnings(
+    overrides: &SpecOverrides,
+    record_history: bool,
+) -> (SystemSpecs, Vec<String>) {
+    let mut warnings = Vec::new();
+    let mut specs = match &overrides.profile {
+        Some(profile) => profile.clone(),
+        None => {
+            let mut specs = SystemSpecs::detect_hardware();
+            // History tracks the real hardware, before any overrides
+            if record_history && let Err(e) = history::record(&specs) {
+                warnings.push(format!("could not record scan history: {}", e));
+            }
+            if let Some(gb) = overrides.config_overhead_gb {
+                specs = specs.with_overhead_override(gb);
             }
+            warnings.extend(specs.apply_env_overrides());
+            specs
         }
-    } else {
-        specs
+    };
+    if let Some(mem_str) = &overrides.memory {
+        match llmfit_core::hardware::parse_memory_size(mem_str) {
+            Some(gb) => specs = specs.with_gpu_memory_override(gb),
+            None => warnings.push(format!(
+                "could not parse --memory value '{}'. Expected format: 32G, 32000M, 1.5T",
+                mem_str
+            )),
+        }
+    }
+    if let Some(gb) = overrides.overhead_gb {
+        specs = specs.with_overhead_override(gb);
     }
+    (specs, warnings)
 }
 
-fn resolve_context_limit(max_context: Option<u32>) -> Option<u32> {
+fn resolve_context_limit(max_context: Option<u32>, config: &Config) -> Option<u32> {
     if max_context.is_some() {
         return max_context;
     }
 
     let Ok(raw) = std::env::var("OLLAMA_CONTEXT_LENGTH") else {
-        return None;
+        return config.context_limit();
     };
     match raw.trim().parse::<u32>() {
         Ok(v) if v > 0 => Some(v),
@@ -133,33 +291,78 @@ fn resolve_context_limit(max_context: Option<u32>) -> Option<u32> {
                 "Warning: could not parse OLLAMA_CONTEXT_LENGTH='{}'. Expected a positive integer.",
                 raw
             );
-            None
+            config.context_limit()
+        }
+    }
+}
+
+/// The models to fit and the context cap to fit them at.
+struct Catalog {
+    db: ModelDatabase,
+    context_limit: Option<u32>,
+}
+
+impl Catalog {
+    fn fits(&self, specs: &SystemSpecs) -> Vec<ModelFit> {
+        self.db
+            .get_all_models()
+            .iter()
+            .map(|m| ModelFit::analyze_with_context_limit(m, specs, self.context_limit))
+            .collect()
+    }
+}
+
+/// Where `fit`, `recommend` and `info` send their results.
+#[derive(Clone, Copy)]
+enum Output<'a> {
+    Table,
+    Json,
+    File(&'a Path, ExportFormat), // --output
+}
+
+impl<'a> Output<'a> {
+    fn new(json: bool, file: Option<&'a Path>, format: ExportFormat) -> Self {
+        match file {
+            Some(path) => Output::File(path, format),
+            None if json => Output::Json,
+            None => Output::Table,
+        }
+    }
+}
+
+/// Write fits for `--output`, exiting with an error if the file can't be written.
+fn write_export(specs: &SystemSpecs, fits: &[ModelFit], path: &Path, format: ExportFormat) {
+    match export::export_results(specs, fits, path, format) {
+        Ok(()) => eprintln!(
+            "Wrote {} models to {} ({})",
+            fits.len(),
+            path.display(),
+            format.label()
+        ),
+        Err(e) => {
+            eprintln!("Error writing {}: {}", path.display(), e);
+            std::process::exit(1);
         }
     }
 }
 
 fn run_fit(
+    catalog: &Catalog,
     perfect: bool,
     limit: Option<usize>,
-    json: bool,
-    memory_override: &Option<String>,
-    context_limit: Option<u32>,
+    output: Output,
+    overrides: &SpecOverrides,
 ) {
-    let specs = detect_specs(memory_override);
-    let db = ModelDatabase::new();
+    let specs = detect_specs(overrides);
 
-    if !json {
+    if matches!(output, Output::Table) {
         specs.display();
     }
 
-    let mut fits: Vec<ModelFit> = db
-        .get_all_models()
-        .iter()
-        .map(|m| ModelFit::analyze_with_context_limit(m, &specs, context_limit))
-        .collect();
+    let mut fits = catalog.fits(&specs);
 
     if perfect {
-        fits.retain(|f| f.fit_level == llmfit_core::fit::FitLevel::Perfect);
+        fits.retain(|f| f.fit_level == FitLevel::Perfect);
     }
 
     fits = llmfit_core::fit::rank_models_by_fit(fits);
@@ -168,90 +371,415 @@ fn run_fit(
         fits.truncate(n);
     }
 
-    if json {
-        display::display_json_fits(&specs, &fits);
+    match output {
+        Output::File(path, format) => write_export(&specs, &fits, path, format),
+        Output::Json => display::display_json_fits(&specs, &fits),
+        Output::Table => display::display_model_fits(&fits),
+    }
+}
+
+/// `--no-tui`: print the ranked fits as plain text and return the exit
+/// status for the best model left after `--perfect`. `-n` only trims the
+/// table.
+fn run_plain(
+    catalog: &Catalog,
+    perfect: bool,
+    limit: Option<usize>,
+    width: Option<usize>,
+    overrides: &SpecOverrides,
+) -> i32 {
+    let specs = detect_specs(overrides);
+
+    let mut fits = catalog.fits(&specs);
+    if perfect {
+        fits.retain(|f| f.fit_level == FitLevel::Perfect);
+    }
+    let code = if fits.iter().any(|f| f.fit_level.at_least(FitLevel::Good)) {
+        0
+    } else if fits
+        .iter()
+        .any(|f| f.fit_level.at_least(FitLevel::Marginal))
+    {
+        1
     } else {
-        display::display_model_fits(&fits);
+        2
+    };
+    fits = llmfit_core::fit::rank_models_by_fit(fits);
+    if let Some(n) = limit {
+        fits.truncate(n);
     }
+
+    let width = width
+        .or_else(|| {
+            crossterm::terminal::size()
+                .ok()
+                .map(|(cols, _)| cols as usize)
+        })
+        .unwrap_or(100);
+    print!("{}", export::render_plain(&specs, &fits, width));
+    code
 }
 
-fn run_tui(memory_override: &Option<String>, context_limit: Option<u32>) -> std::io::Result<()> {
+fn run_tui(
+    mut config: Config,
+    catalog: &Catalog,
+    overrides: &SpecOverrides,
+    mouse: bool,
+    refresh_rate: Option<u64>,
+) -> std::io::Result<()> {
+    if let Some(ms) = refresh_rate {
+        if let Some(warning) = config.set_refresh_rate(ms) {
+            eprintln!("Warning: {}", warning);
+        }
+        if let Err(e) = config.save() {
+            eprintln!("Warning: could not save config: {}", e);
+        }
+    }
+
     // Setup terminal
     crossterm::terminal::enable_raw_mode()?;
     let mut stdout = std::io::stdout();
-    crossterm::execute!(
-        stdout,
-        crossterm::terminal::EnterAlternateScreen,
-        crossterm::event::EnableMouseCapture
-    )?;
+    crossterm::execute!(stdout, crossterm::terminal::EnterAlternateScreen)?;
+    if mouse {
+        crossterm::execute!(stdout, crossterm::event::EnableMouseCapture)?;
+    }
 
     let backend = ratatui::backend::CrosstermBackend::new(stdout);
     let mut terminal = ratatui::Terminal::new(backend)?;
 
-    // Create app state
-    let specs = detect_specs(memory_override);
-    let mut app = tui_app::App::with_specs_and_context(specs, context_limit);
+    // Detect hardware in the background, animating a spinner meanwhile
+    let tc = tui_app::startup_theme_colors();
+    let detect_overrides = overrides.clone();
+    let mut detection =
+        tui_app::Detection::spawn(move || detect_specs_with_warnings(&detect_overrides, true));
+    // Printing now would draw over the alternate screen
+    let mut detection_warnings = Vec::new();
+    let specs = loop {
+        if let Some((specs, warnings)) = detection.poll() {
+            detection_warnings = warnings;
+            break Some(specs);
+        }
+        terminal.draw(|frame| tui_ui::draw_detection(frame, &detection, &tc))?;
+        if tui_events::handle_detection_events(&detection)? {
+            break None;
+        }
+    };
 
-    // Main loop
-    loop {
-        terminal.draw(|frame| {
-            tui_ui::draw(frame, &mut app);
-        })?;
+    if let Some(specs) = specs {
+        // Create app state
+        let mut app = tui_app::App::with_database(&catalog.db, specs, catalog.context_limit);
+        app.history = history::load_default();
+        app.config = config;
+        app.apply_filters();
+        // A saved profile describes another machine; its memory isn't ours
+        if overrides.profile.is_none() {
+            app.start_memory_sampling();
+        }
 
-        tui_events::handle_events(&mut app)?;
+        // Main loop
+        loop {
+            terminal.draw(|frame| {
+                tui_ui::draw(frame, &mut app);
+            })?;
 
-        if app.should_quit {
-            break;
+            tui_events::handle_events(&mut app)?;
+
+            if app.should_quit {
+                break;
+            }
         }
     }
 
     // Restore terminal
     crossterm::terminal::disable_raw_mode()?;
+    if mouse {
+        crossterm::execute!(
+            terminal.backend_mut(),
+            crossterm::event::DisableMouseCapture
+        )?;
+    }
     crossterm::execute!(
         terminal.backend_mut(),
-        crossterm::terminal::LeaveAlternateScreen,
-        crossterm::event::DisableMouseCapture
+        crossterm::terminal::LeaveAlternateScreen
     )?;
     terminal.show_cursor()?;
+    for warning in detection_warnings {
+        eprintln!("Warning: {}", warning);
+    }
 
     Ok(())
 }
 
+/// Why `info` or `check` couldn't pick a model.
+enum LookupError {
+    Ambiguous(Vec<String>),
+    NotFound {
+        name: String,
+        reason: Option<String>,
+    },
+}
+
+impl std::fmt::Display for LookupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LookupError::Ambiguous(names) => {
+                writeln!(f, "\nMultiple models found. Please be more specific:")?;
+                for name in names {
+                    writeln!(f, "  - {}", name)?;
+                }
+                Ok(())
+            }
+            LookupError::NotFound { name, reason } => {
+                write!(f, "\nNo model found matching '{}'", name)?;
+                match reason {
+                    Some(reason) => write!(f, "\n({})", reason),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// Find `name` in the database, preferring an exact match over substring
+/// matches. Not in the database: an "org/name" is looked up on Hugging
+/// Face, anything else in a local Ollama.
+fn lookup_model(db: &ModelDatabase, name: &str) -> Result<LlmModel, LookupError> {
+    let results = db.find_model(name);
+    if let Some(exact) = results.iter().find(|m| m.name.eq_ignore_ascii_case(name)) {
+        return Ok((*exact).clone());
+    }
+    match results.as_slice() {
+        [entry] => return Ok((*entry).clone()),
+        [] => {}
+        _ => {
+            return Err(LookupError::Ambiguous(
+                results.iter().map(|m| m.name.clone()).collect(),
+            ));
+        }
+    }
+
+    let lookup = if name.contains('/') {
+        huggingface::resolve_model(name)
+            .map(|spec| spec.to_llm_model())
+            .map_err(|e| e.to_string())
+    } else {
+        OllamaProvider::new()
+            .show_model(name)
+            .map(|info| info.to_llm_model())
+            .map_err(|e| e.to_string())
+    };
+    lookup.map_err(|e| LookupError::NotFound {
+        name: name.to_string(),
+        reason: name.contains([':', '/']).then_some(e),
+    })
+}
+
+/// `check`: fit one model and return its exit status, printing the
+/// verdict on stderr.
+fn run_check(
+    catalog: &Catalog,
+    name: &str,
+    budget: Option<&str>,
+    output: Output,
+    overrides: &SpecOverrides,
+) -> i32 {
+    let mut specs = detect_specs(overrides);
+    if let Some(raw) = budget {
+        let Some(gb) = llmfit_core::hardware::parse_memory_size(raw) else {
+            eprintln!(
+                "Error: could not parse --budget value '{}'. Expected format: 16G, 16000M",
+                raw
+            );
+            return CHECK_ERROR;
+        };
+        specs = if specs.has_gpu {
+            specs.with_gpu_memory_override(gb)
+        } else {
+            specs.with_ram_override(gb)
+        };
+    }
+    let model = match lookup_model(&catalog.db, name) {
+        Ok(model) => model,
+        Err(e) => {
+            eprintln!("{}", e.to_string().trim_start());
+            return CHECK_ERROR;
+        }
+    };
+
+    let fit = ModelFit::analyze_with_context_limit(&model, &specs, catalog.context_limit);
+    let code = check_exit_code(&fit, &specs);
+    let verdict = match code {
+        0 => "fits",
+        1 => "fits only by offloading to system RAM",
+        _ => "does not fit",
+    };
+    eprintln!(
+        "{} {} ({}, {}, {:.1} of {:.1} GB)",
+        fit.model.name,
+        verdict,
+        fit.fit_text(),
+        fit.run_mode_text(),
+        fit.memory_required_gb,
+        fit.memory_available_gb
+    );
+    match output {
+        Output::File(path, format) => write_export(&specs, &[fit], path, format),
+        Output::Json => display::display_json_fits(&specs, &[fit]),
+        Output::Table => {}
+    }
+    code
+}
+
+/// Names listed in a `batch` file: one per line, ignoring blank lines and
+/// `#` comments.
+fn parse_model_list(text: &str) -> Vec<&str> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// `batch`: fit each listed model, in the order given, and print or export
+/// the rows. Returns 1 when none of the models could be found.
+fn run_batch(
+    catalog: &Catalog,
+    file: Option<&Path>,
+    format: ExportFormat,
+    output: Option<&Path>,
+    overrides: &SpecOverrides,
+) -> i32 {
+    let models: Vec<Result<LlmModel, String>> = match file {
+        Some(path) => {
+            let text = if path == Path::new("-") {
+                std::io::read_to_string(std::io::stdin())
+            } else {
+                std::fs::read_to_string(path)
+            };
+            let text = match text {
+                Ok(text) => text,
+                Err(e) => {
+                    eprintln!("Error reading {}: {}", path.display(), e);
+                    return 1;
+                }
+            };
+            parse_model_list(&text)
+                .into_iter()
+                .map(|name| {
+                    lookup_model(&catalog.db, name)
+                        .map_err(|e| format!("'{}': {}", name, e.to_string().trim()))
+                })
+                .collect()
+        }
+        None => {
+            let ollama = OllamaProvider::new();
+            let tags = match ollama.model_tags() {
+                Ok(tags) => tags,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return 1;
+                }
+            };
+            tags.iter()
+                .map(|tag| {
+                    ollama
+                        .show_model(tag)
+                        .map(|info| info.to_llm_model())
+                        .map_err(|e| format!("'{}': {}", tag, e))
+                })
+                .collect()
+        }
+    };
+
+    let specs = detect_specs(overrides);
+    let mut fits = Vec::new();
+    for model in models {
+        match model {
+            Ok(model) => fits.push(ModelFit::analyze_with_context_limit(
+                &model,
+                &specs,
+                catalog.context_limit,
+            )),
+            Err(e) => eprintln!("Warning: skipping {}", e),
+        }
+    }
+    if fits.is_empty() {
+        eprintln!("Error: none of the listed models could be found");
+        return 1;
+    }
+
+    match output {
+        Some(path) => write_export(&specs, &fits, path, format),
+        None => match export::render_results(&specs, &fits, format) {
+            Ok(text) => print!("{}", text),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return 1;
+            }
+        },
+    }
+    0
+}
+
+/// `profile save` / `profile list`.
+fn run_profile(action: ProfileCommand, overrides: &SpecOverrides) {
+    match action {
+        ProfileCommand::Save { name } => {
+            let specs = detect_specs(overrides);
+            match specs.save_profile(&name) {
+                Ok(path) => eprintln!("Saved profile '{}' to {}", name, path.display()),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        ProfileCommand::List => match profile::list_profiles() {
+            Ok(names) if names.is_empty() => {
+                eprintln!("No saved profiles; create one with `llmfit profile save NAME`.")
+            }
+            Ok(names) => {
+                for name in names {
+                    println!("{}", name);
+                }
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+/// `check` status when the model or budget couldn't be resolved.
+const CHECK_ERROR: i32 = 3;
+
+/// 0 when the model runs from its machine's fastest memory pool, 1 when it
+/// needs system RAM as overflow, 2 when it doesn't fit at all.
+fn check_exit_code(fit: &ModelFit, specs: &SystemSpecs) -> i32 {
+    match (fit.fit_level, fit.run_mode) {
+        (FitLevel::TooTight, _) => 2,
+        (_, RunMode::Gpu) => 0,
+        (_, RunMode::CpuOnly) if !specs.has_gpu => 0,
+        _ => 1,
+    }
+}
+
 fn run_recommend(
+    catalog: &Catalog,
     limit: usize,
     use_case: Option<String>,
     min_fit: String,
     runtime_filter: String,
-    json: bool,
-    memory_override: &Option<String>,
-    context_limit: Option<u32>,
+    output: Output,
+    overrides: &SpecOverrides,
 ) {
-    let specs = detect_specs(memory_override);
-    let db = ModelDatabase::new();
+    let specs = detect_specs(overrides);
 
-    let mut fits: Vec<ModelFit> = db
-        .get_all_models()
-        .iter()
-        .map(|m| ModelFit::analyze_with_context_limit(m, &specs, context_limit))
-        .collect();
+    let mut fits = catalog.fits(&specs);
 
     // Filter by minimum fit level
-    let min_level = match min_fit.to_lowercase().as_str() {
-        "perfect" => llmfit_core::fit::FitLevel::Perfect,
-        "good" => llmfit_core::fit::FitLevel::Good,
-        "marginal" => llmfit_core::fit::FitLevel::Marginal,
-        _ => llmfit_core::fit::FitLevel::Marginal,
-    };
-    fits.retain(|f| match (min_level, f.fit_level) {
-        (llmfit_core::fit::FitLevel::Marginal, llmfit_core::fit::FitLevel::TooTight) => false,
-        (
-            llmfit_core::fit::FitLevel::Good,
-            llmfit_core::fit::FitLevel::TooTight | llmfit_core::fit::FitLevel::Marginal,
-        ) => false,
-        (llmfit_core::fit::FitLevel::Perfect, llmfit_core::fit::FitLevel::Perfect) => true,
-        (llmfit_core::fit::FitLevel::Perfect, _) => false,
-        _ => true,
-    });
+    let min_level = min_fit.parse::<FitLevel>().unwrap_or(FitLevel::Marginal);
+    fits.retain(|f| f.fit_level.at_least(min_level));
 
     // Filter by runtime
     match runtime_filter.to_lowercase().as_str() {
@@ -281,25 +809,55 @@ fn run_recommend(
     fits = llmfit_core::fit::rank_models_by_fit(fits);
     fits.truncate(limit);
 
-    if json {
-        display::display_json_fits(&specs, &fits);
-    } else {
-        if !fits.is_empty() {
-            specs.display();
+    match output {
+        Output::File(path, format) => write_export(&specs, &fits, path, format),
+        Output::Json => display::display_json_fits(&specs, &fits),
+        Output::Table => {
+            if !fits.is_empty() {
+                specs.display();
+            }
+            display::display_model_fits(&fits);
         }
-        display::display_model_fits(&fits);
     }
 }
 
 fn main() {
     let cli = Cli::parse();
-    let context_limit = resolve_context_limit(cli.max_context);
+    if color_support::no_color() {
+        colored::control::set_override(false);
+    }
+    if let Some(path) = &cli.config {
+        config::set_path_override(path.clone());
+    }
+    let config = Config::load();
+    let catalog = Catalog {
+        db: config.model_database(),
+        context_limit: resolve_context_limit(cli.max_context, &config),
+    };
+    let format = cli.format.unwrap_or(ExportFormat::Json);
+    let output = Output::new(cli.json, cli.output.as_deref(), format);
+    let profile = cli.profile.as_deref().map(|name| {
+        SystemSpecs::load_profile(name).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            let saved = profile::list_profiles().unwrap_or_default();
+            if !saved.is_empty() {
+                eprintln!("Saved profiles: {}", saved.join(", "));
+            }
+            std::process::exit(1);
+        })
+    });
+    let overrides = SpecOverrides {
+        profile,
+        memory: cli.memory.clone(),
+        overhead_gb: cli.overhead,
+        config_overhead_gb: config.overhead_gb,
+    };
 
     // If a subcommand is given, use classic CLI mode
     if let Some(command) = cli.command {
         match command {
             Commands::System => {
-                let specs = detect_specs(&cli.memory);
+                let specs = detect_specs(&overrides);
                 if cli.json {
                     display::display_json_system(&specs);
                 } else {
@@ -308,46 +866,55 @@ fn main() {
             }
 
             Commands::List => {
-                let db = ModelDatabase::new();
-                display::display_all_models(db.get_all_models());
+                display::display_all_models(catalog.db.get_all_models());
             }
 
             Commands::Fit { perfect, limit } => {
-                run_fit(perfect, limit, cli.json, &cli.memory, context_limit);
+                run_fit(&catalog, perfect, limit, output, &overrides);
             }
 
             Commands::Search { query } => {
-                let db = ModelDatabase::new();
-                let results = db.find_model(&query);
+                let results = catalog.db.find_model(&query);
                 display::display_search_results(&results, &query);
             }
 
             Commands::Info { model } => {
-                let db = ModelDatabase::new();
-                let specs = detect_specs(&cli.memory);
-                let results = db.find_model(&model);
-
-                if results.is_empty() {
-                    println!("\nNo model found matching '{}'", model);
-                    return;
-                }
-
-                if results.len() > 1 {
-                    println!("\nMultiple models found. Please be more specific:");
-                    for m in results {
-                        println!("  - {}", m.name);
+                let specs = detect_specs(&overrides);
+                let entry = match lookup_model(&catalog.db, &model) {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        println!("{}", e);
+                        return;
                     }
-                    return;
+                };
+
+                let fit =
+                    ModelFit::analyze_with_context_limit(&entry, &specs, catalog.context_limit);
+                match output {
+                    Output::File(path, format) => write_export(&specs, &[fit], path, format),
+                    Output::Json => display::display_json_fits(&specs, &[fit]),
+                    Output::Table => display::display_model_detail(&fit, &specs),
                 }
+            }
 
-                let fit = ModelFit::analyze_with_context_limit(results[0], &specs, context_limit);
-                if cli.json {
-                    display::display_json_fits(&specs, &[fit]);
-                } else {
-                    display::display_model_detail(&fit);
-                }
+            Commands::Check { model, budget } => {
+                let code = run_check(&catalog, &model, budget.as_deref(), output, &overrides);
+                std::process::exit(code);
+            }
+
+            Commands::Batch { file, ollama: _ } => {
+                let code = run_batch(
+                    &catalog,
+                    file.as_deref(),
+                    cli.format.unwrap_or(ExportFormat::Csv),
+                    cli.output.as_deref(),
+                    &overrides,
+                );
+                std::process::exit(code);
             }
 
+            Commands::Profile { action } => run_profile(action, &overrides),
+
             Commands::Recommend {
                 limit,
                 use_case,
@@ -356,27 +923,33 @@ fn main() {
                 json,
             } => {
                 run_recommend(
+                    &catalog,
                     limit,
                     use_case,
                     min_fit,
                     runtime,
-                    json,
-                    &cli.memory,
-                    context_limit,
+                    Output::new(json, cli.output.as_deref(), format),
+                    &overrides,
                 );
             }
         }
         return;
     }
 
-    // If --cli flag, use classic fit output
-    if cli.cli {
-        run_fit(cli.perfect, cli.limit, cli.json, &cli.memory, context_limit);
+    if cli.no_tui {
+        let code = run_plain(&catalog, cli.perfect, cli.limit, cli.width, &overrides);
+        std::process::exit(code);
+    }
+
+    // If --cli flag (or --output), use classic fit output
+    if cli.cli || cli.output.is_some() {
+        run_fit(&catalog, cli.perfect, cli.limit, output, &overrides);
         return;
     }
 
     // Default: launch TUI
-    if let Err(e) = run_tui(&cli.memory, context_limit) {
+    let mouse = cli.mouse || std::env::var("LLMFIT_MOUSE").is_ok_and(|v| v.trim() == "1");
+    if let Err(e) = run_tui(config, &catalog, &overrides, mouse, cli.refresh_rate) {
         eprintln!("Error running TUI: {}", e);
         std::process::exit(1);
     }
@@ -0,0 +1,118 @@
+//! Free RAM and VRAM sampled once a second while the TUI runs, for the
+//! memory panel toggled with `m`.
+
+use std::collections::VecDeque;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use llmfit_core::hardware::SystemSpecs;
+
+/// Samples kept: one minute at [`SAMPLE_INTERVAL`].
+pub const HISTORY_LEN: usize = 60;
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Free memory at one point in time, in GB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemorySample {
+    pub ram_available_gb: f64,
+    /// `None` when the GPU tools don't report usage.
+    pub vram_available_gb: Option<f64>,
+}
+
+/// The last [`HISTORY_LEN`] samples of each series, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryHistory {
+    ram: VecDeque<f64>,
+    vram: VecDeque<f64>,
+}
+
+impl MemoryHistory {
+    pub fn push(&mut self, sample: MemorySample) {
+        push_capped(&mut self.ram, sample.ram_available_gb);
+        if let Some(vram) = sample.vram_available_gb {
+            push_capped(&mut self.vram, vram);
+        }
+    }
+
+    pub fn ram(&self) -> Vec<f64> {
+        self.ram.iter().copied().collect()
+    }
+
+    /// Empty on machines where free VRAM can't be read.
+    pub fn vram(&self) -> Vec<f64> {
+        self.vram.iter().copied().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ram.is_empty()
+    }
+}
+
+fn push_capped(series: &mut VecDeque<f64>, value: f64) {
+    if series.len() == HISTORY_LEN {
+        series.pop_front();
+    }
+    series.push_back(value);
+}
+
+/// Background thread sending a [`MemorySample`] every [`SAMPLE_INTERVAL`].
+/// It stops at the first sample after the sampler is dropped.
+pub struct MemorySampler {
+    receiver: mpsc::Receiver<MemorySample>,
+}
+
+impl MemorySampler {
+    /// Sample the machine `specs` was detected on. VRAM is only re-queried
+    /// when detection could read it, so overridden or unsupported GPUs
+    /// don't run nvidia-smi every second.
+    pub fn spawn(specs: SystemSpecs) -> Self {
+        let track_vram = specs.gpu_vram_available_gb.is_some();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut specs = specs;
+            loop {
+                specs.refresh_ram();
+                if track_vram {
+                    specs.refresh_vram_available();
+                }
+                let sample = MemorySample {
+                    ram_available_gb: specs.available_ram_gb,
+                    vram_available_gb: specs.gpu_vram_available_gb,
+                };
+                if tx.send(sample).is_err() {
+                    break;
+                }
+                std::thread::sleep(SAMPLE_INTERVAL);
+            }
+        });
+        MemorySampler { receiver: rx }
+    }
+
+    /// Samples that arrived since the last call, oldest first.
+    pub fn drain(&self) -> Vec<MemorySample> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_history_keeps_last_minute() {
+        let mut history = MemoryHistory::default();
+        assert!(history.is_empty());
+        for i in 0..HISTORY_LEN + 5 {
+            history.push(MemorySample {
+                ram_available_gb: i as f64,
+                vram_available_gb: (i % 2 == 0).then_some(i as f64),
+            });
+        }
+        let ram = history.ram();
+        assert_eq!(ram.len(), HISTORY_LEN);
+        assert_eq!(ram[0], 5.0);
+        assert_eq!(ram.last(), Some(&(HISTORY_LEN as f64 + 4.0)));
+        // Samples without a VRAM reading are skipped, not zero-filled
+        assert_eq!(history.vram().len(), (HISTORY_LEN + 5).div_ceil(2));
+    }
+}
@@ -0,0 +1,99 @@
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+use std::time::{Duration, Instant};
+
+use crate::tui_app::{App, InputMode};
+
+/// Two clicks on the same row within this window count as a double-click.
+const DOUBLE_CLICK_WINDOW: Duration = Duration::from_millis(400);
+
+/// Lines between the top of the table block and the first model row
+/// (border + header).
+const TABLE_HEADER_ROWS: u16 = 2;
+
+/// Handle a mouse event. Only active with `--mouse` / `LLMFIT_MOUSE=1`,
+/// since capturing the mouse disables the terminal's own text selection.
+/// Returns true if the event changed anything.
+pub fn handle_mouse_event(event: MouseEvent, app: &mut App) -> bool {
+    if app.input_mode != InputMode::Normal
+        || app.detail_popup
+        || app.show_help
+        || app.show_compare
+        || app.show_history
+    {
+        return false;
+    }
+    match event.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            handle_click(app, Position::new(event.column, event.row))
+        }
+        MouseEventKind::ScrollUp if !app.show_detail => {
+            app.move_up();
+            true
+        }
+        MouseEventKind::ScrollDown if !app.show_detail => {
+            app.move_down();
+            true
+        }
+        _ => false,
+    }
+}
+
+fn handle_click(app: &mut App, pos: Position) -> bool {
+    if app.theme_area.contains(pos) {
+        app.cycle_theme();
+        return true;
+    }
+    if app.show_detail {
+        return false;
+    }
+    let Some(index) = table_row_at(app.table_area, app.scroll_offset, pos)
+        .filter(|&i| i < app.filtered_fits.len())
+    else {
+        return false;
+    };
+
+    let now = Instant::now();
+    let double_click = app
+        .last_click
+        .is_some_and(|(at, row)| row == index && now.duration_since(at) <= DOUBLE_CLICK_WINDOW);
+
+    app.confirm_download = false;
+    app.selected_row = index;
+    if double_click {
+        app.detail_popup = true;
+        app.last_click = None;
+    } else {
+        app.last_click = Some((now, index));
+    }
+    true
+}
+
+/// Index into `filtered_fits` of the table row under `pos`, given the table's
+/// last drawn area and scroll offset.
+fn table_row_at(area: Rect, offset: usize, pos: Position) -> Option<usize> {
+    let first = area.y + TABLE_HEADER_ROWS;
+    let bottom_border = area.bottom().saturating_sub(1);
+    if !area.contains(pos) || pos.y < first || pos.y >= bottom_border {
+        return None;
+    }
+    Some(offset + (pos.y - first) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_row_at() {
+        let area = Rect::new(0, 6, 80, 20);
+        // Border and header rows aren't models
+        assert_eq!(table_row_at(area, 0, Position::new(10, 6)), None);
+        assert_eq!(table_row_at(area, 0, Position::new(10, 7)), None);
+        assert_eq!(table_row_at(area, 0, Position::new(10, 8)), Some(0));
+        assert_eq!(table_row_at(area, 5, Position::new(10, 10)), Some(7));
+        // Bottom border and outside the table
+        assert_eq!(table_row_at(area, 0, Position::new(10, 25)), None);
+        assert_eq!(table_row_at(area, 0, Position::new(10, 30)), None);
+    }
+}
@@ -0,0 +1,77 @@
+//! Reference machines for the TUI compare view, loaded from the presets in
+//! `data/profiles.toml`.
+
+use llmfit_core::hardware::{GpuBackend, SystemSpecs};
+use serde::Deserialize;
+
+const PROFILES_TOML: &str = include_str!("../data/profiles.toml");
+
+/// A preset machine to compare the local one against.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReferenceProfile {
+    pub name: String,
+    /// GPU model name, used to look up memory bandwidth for speed estimates.
+    #[serde(default)]
+    pub gpu: Option<String>,
+    /// VRAM per card; `None` for a CPU-only machine.
+    #[serde(default)]
+    pub vram_gb: Option<f64>,
+    #[serde(default = "default_gpu_count")]
+    pub gpu_count: u32,
+    pub backend: GpuBackend,
+    pub ram_gb: f64,
+}
+
+fn default_gpu_count() -> u32 {
+    1
+}
+
+#[derive(Deserialize)]
+struct ProfilesFile {
+    profile: Vec<ReferenceProfile>,
+}
+
+impl ReferenceProfile {
+    /// The profile as a hand-described [`SystemSpecs`].
+    pub fn specs(&self) -> SystemSpecs {
+        let specs =
+            SystemSpecs::new_manual(self.ram_gb, self.vram_gb, self.backend, self.gpu_count);
+        match &self.gpu {
+            Some(name) if specs.has_gpu => specs.with_gpu_name_override(name),
+            _ => specs,
+        }
+    }
+}
+
+/// The built-in reference profiles, in file order.
+pub fn reference_profiles() -> Vec<ReferenceProfile> {
+    toml::from_str::<ProfilesFile>(PROFILES_TOML)
+        .expect("Failed to parse embedded profiles.toml")
+        .profile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_profiles_parse() {
+        let profiles = reference_profiles();
+        assert!(profiles.len() >= 3);
+
+        let rtx4090 = profiles.iter().find(|p| p.name == "RTX 4090 24GB").unwrap();
+        let specs = rtx4090.specs();
+        assert_eq!(specs.gpu_vram_gb, Some(24.0));
+        assert_eq!(specs.gpu_name.as_deref(), Some("NVIDIA GeForce RTX 4090"));
+        assert_eq!(specs.backend, GpuBackend::Cuda);
+
+        let m2 = profiles
+            .iter()
+            .find(|p| p.name == "M2 Ultra 192GB")
+            .unwrap();
+        assert!(m2.specs().unified_memory);
+
+        let cpu = profiles.iter().find(|p| p.vram_gb.is_none()).unwrap();
+        assert!(!cpu.specs().has_gpu);
+    }
+}
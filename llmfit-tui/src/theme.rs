@@ -1,137 +1,413 @@
+use crate::color_support::{Background, no_color, terminal_background, to_256color};
+use crate::config::{Config, ConfigError, config_dir};
+use llmfit_core::fit::FitLevel;
 use ratatui::style::Color;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Available color themes for the TUI.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum Theme {
+    #[default]
     Default,
+    DefaultLight, // Default with accents that stay readable on a light background
     Dracula,
     Solarized,
     Nord,
     Monokai,
     Gruvbox,
+    TokyoNight,
+    CatppuccinMocha,
+    CatppuccinLatte,
+    HighContrast,             // 16 ANSI colors only, with shape hints; for accessibility
+    OkabeIto,                 // colorblind-safe Okabe-Ito palette, with shape hints
+    Mono,                     // terminal default colors only; forced by NO_COLOR
+    Custom(Box<ThemeColors>), // loaded from ~/.config/llmfit/theme.toml
+    Named(String, Box<ThemeColors>), // ~/.config/llmfit/themes/<name>.toml
+}
+
+/// Why a custom theme couldn't be loaded.
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    InvalidName(String), // empty, hidden, or not a plain file name
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Io(e) => write!(f, "{}", e),
+            ThemeError::Parse(e) => write!(f, "invalid theme file: {}", e),
+            ThemeError::InvalidName(name) => write!(f, "invalid theme name '{}'", name),
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ThemeError::Io(e) => Some(e),
+            ThemeError::Parse(e) => Some(e),
+            ThemeError::InvalidName(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ThemeError {
+    fn from(e: std::io::Error) -> Self {
+        ThemeError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for ThemeError {
+    fn from(e: toml::de::Error) -> Self {
+        ThemeError::Parse(e)
+    }
 }
 
 impl Theme {
-    pub fn label(&self) -> &'static str {
+    pub fn label(&self) -> &str {
         match self {
             Theme::Default => "Default",
+            Theme::DefaultLight => "Default Light",
             Theme::Dracula => "Dracula",
             Theme::Solarized => "Solarized",
             Theme::Nord => "Nord",
             Theme::Monokai => "Monokai",
             Theme::Gruvbox => "Gruvbox",
+            Theme::TokyoNight => "Tokyo Night",
+            Theme::CatppuccinMocha => "Catppuccin Mocha",
+            Theme::CatppuccinLatte => "Catppuccin Latte",
+            Theme::HighContrast => "High Contrast",
+            Theme::OkabeIto => "Okabe-Ito",
+            Theme::Mono => "Mono",
+            Theme::Custom(_) => "Custom",
+            Theme::Named(name, _) => name,
         }
     }
 
     pub fn next(&self) -> Self {
         match self {
-            Theme::Default => Theme::Dracula,
+            Theme::Default => Theme::DefaultLight,
+            Theme::DefaultLight => Theme::Dracula,
             Theme::Dracula => Theme::Solarized,
             Theme::Solarized => Theme::Nord,
             Theme::Nord => Theme::Monokai,
             Theme::Monokai => Theme::Gruvbox,
-            Theme::Gruvbox => Theme::Default,
+            Theme::Gruvbox => Theme::TokyoNight,
+            Theme::TokyoNight => Theme::CatppuccinMocha,
+            Theme::CatppuccinMocha => Theme::CatppuccinLatte,
+            Theme::CatppuccinLatte => Theme::HighContrast,
+            Theme::HighContrast => Theme::OkabeIto,
+            Theme::OkabeIto => Theme::Mono,
+            Theme::Mono => Theme::Default,
+            Theme::Custom(_) | Theme::Named(..) => Theme::Default,
         }
     }
 
     pub fn colors(&self) -> ThemeColors {
         match self {
             Theme::Default => default_colors(),
+            Theme::DefaultLight => default_light_colors(),
             Theme::Dracula => dracula_colors(),
             Theme::Solarized => solarized_colors(),
             Theme::Nord => nord_colors(),
             Theme::Monokai => monokai_colors(),
             Theme::Gruvbox => gruvbox_colors(),
+            Theme::TokyoNight => tokyo_night_colors(),
+            Theme::CatppuccinMocha => catppuccin_mocha_colors(),
+            Theme::CatppuccinLatte => catppuccin_latte_colors(),
+            Theme::HighContrast => high_contrast_colors(),
+            Theme::OkabeIto => okabe_ito_colors(),
+            Theme::Mono => mono_colors(),
+            Theme::Custom(colors) | Theme::Named(_, colors) => **colors,
         }
     }
 
-    /// Path to the config file: ~/.config/llmfit/theme
-    fn config_path() -> Option<PathBuf> {
-        let home = std::env::var("HOME")
-            .or_else(|_| std::env::var("USERPROFILE"))
-            .ok()?;
-        Some(
-            PathBuf::from(home)
-                .join(".config")
-                .join("llmfit")
-                .join("theme"),
-        )
+    /// Whether status indicators should use distinct shapes (✓ ⚠ ✗) rather
+    /// than relying on color alone.
+    pub fn shape_hints(&self) -> bool {
+        matches!(self, Theme::HighContrast | Theme::OkabeIto | Theme::Mono)
     }
 
-    /// Save the current theme to disk.
-    pub fn save(&self) {
-        if let Some(path) = Self::config_path() {
-            if let Some(parent) = path.parent() {
-                let _ = fs::create_dir_all(parent);
-            }
-            let _ = fs::write(&path, self.label());
+    /// Path to a user-defined theme: ~/.config/llmfit/theme.toml
+    fn custom_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("theme.toml"))
+    }
+
+    /// Directory of named themes: ~/.config/llmfit/themes
+    fn themes_dir() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("themes"))
+    }
+
+    /// Save the current theme as `theme` in the config file, keeping its
+    /// other settings. A config file that doesn't parse is left alone.
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let path = Config::path().ok_or(ConfigError::NoConfigDir)?;
+        let mut config = Config::load_from(&path)?;
+        config.theme = Some(self.clone());
+        config.save_to(&path)
+    }
+
+    /// Load a theme from a TOML file of [`ThemeColors`] fields, e.g.
+    /// `bg = "#282a36"`. Colors may be hex, a name ("dark gray") or a
+    /// 256-color index; fields left out keep the Default theme's color.
+    pub fn load_from_toml(path: &Path) -> Result<Self, ThemeError> {
+        Ok(Theme::Custom(Box::new(read_colors(path)?)))
+    }
+
+    /// Load `<dir>/<name>.toml` as theme `name`; see
+    /// [`Theme::load_from_toml`] for the format. The colors are read once
+    /// here and kept in the variant.
+    pub fn load_named_in(dir: &Path, name: &str) -> Result<Self, ThemeError> {
+        let valid = !name.is_empty()
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ' '));
+        if !valid {
+            return Err(ThemeError::InvalidName(name.to_string()));
         }
+        let colors = read_colors(&dir.join(format!("{}.toml", name)))?;
+        Ok(Theme::Named(name.to_string(), Box::new(colors)))
     }
 
-    /// Load the saved theme from disk, falling back to Default.
+    /// Default, or Default Light when `COLORFGBG` says the terminal has a
+    /// light background.
+    pub fn auto() -> Self {
+        match terminal_background() {
+            Some(Background::Light) => Theme::DefaultLight,
+            _ => Theme::Default,
+        }
+    }
+
+    /// Mono when `NO_COLOR` is set, else ~/.config/llmfit/theme.toml if
+    /// present, else the config file's theme, else [`Theme::auto`]. A broken
+    /// theme.toml is reported on stderr.
     pub fn load() -> Self {
-        Self::config_path()
-            .and_then(|path| fs::read_to_string(path).ok())
-            .map(|s| Self::from_label(s.trim()))
-            .unwrap_or(Theme::Default)
+        if no_color() {
+            return Theme::Mono;
+        }
+        if let Some(path) = Self::custom_path().filter(|p| p.exists()) {
+            match Self::load_from_toml(&path) {
+                Ok(theme) => return theme,
+                Err(e) => eprintln!("Warning: ignoring {}: {}", path.display(), e),
+            }
+        }
+        Config::path()
+            .and_then(|path| Config::load_from(&path).ok())
+            .and_then(|config| config.theme)
+            .unwrap_or_else(Self::auto)
+    }
+
+    /// The built-in theme labelled `s`, or Default for any other label.
+    /// Named themes from themes/ are looked up by [`Theme::resolve_in`].
+    pub fn from_label(s: &str) -> Self {
+        Self::builtin(s).unwrap_or_default()
     }
 
-    fn from_label(s: &str) -> Self {
-        match s {
+    fn builtin(s: &str) -> Option<Self> {
+        let theme = match s {
+            "Default" => Theme::Default,
+            "Default Light" => Theme::DefaultLight,
             "Dracula" => Theme::Dracula,
             "Solarized" => Theme::Solarized,
             "Nord" => Theme::Nord,
             "Monokai" => Theme::Monokai,
             "Gruvbox" => Theme::Gruvbox,
-            _ => Theme::Default,
+            "Tokyo Night" => Theme::TokyoNight,
+            "Catppuccin Mocha" => Theme::CatppuccinMocha,
+            "Catppuccin Latte" => Theme::CatppuccinLatte,
+            "High Contrast" => Theme::HighContrast,
+            "Okabe-Ito" => Theme::OkabeIto,
+            "Mono" => Theme::Mono,
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// The theme labelled `label`: a built-in one, else `<dir>/<label>.toml`
+    /// via [`Theme::load_named_in`].
+    pub fn resolve_in(dir: &Path, label: &str) -> Result<Self, ThemeError> {
+        match Self::builtin(label) {
+            Some(theme) => Ok(theme),
+            None => Self::load_named_in(dir, label),
+        }
+    }
+}
+
+fn read_colors(path: &Path) -> Result<ThemeColors, ThemeError> {
+    Ok(toml::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Themes are stored by label, e.g. `theme = "Tokyo Night"`.
+impl Serialize for Theme {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.label())
+    }
+}
+
+impl<'de> Deserialize<'de> for Theme {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let label = String::deserialize(deserializer)?;
+        let label = label.trim();
+        let Some(dir) = Theme::themes_dir() else {
+            return Ok(Theme::from_label(label));
+        };
+        match Theme::resolve_in(&dir, label) {
+            Ok(theme) => Ok(theme),
+            // A named theme whose file is gone falls back to Default
+            Err(ThemeError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Theme::Default)
+            }
+            Err(ThemeError::InvalidName(_)) => Ok(Theme::Default),
+            Err(e) => Err(serde::de::Error::custom(format!(
+                "theme '{}': {}",
+                label, e
+            ))),
         }
     }
 }
 
 /// All semantic colors used throughout the TUI, mapped from each theme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct ThemeColors {
     // General
+    #[serde(deserialize_with = "deserialize_color")]
     pub bg: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub fg: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub muted: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub border: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub title: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub highlight_bg: Color,
 
     // Accent colors
+    #[serde(deserialize_with = "deserialize_color")]
     pub accent: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub accent_secondary: Color,
 
     // Status colors
+    #[serde(deserialize_with = "deserialize_color")]
     pub good: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub warning: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub error: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub info: Color,
 
     // Score colors
+    #[serde(deserialize_with = "deserialize_color")]
     pub score_high: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub score_mid: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub score_low: Color,
 
     // Fit levels
+    #[serde(deserialize_with = "deserialize_color")]
     pub fit_perfect: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub fit_good: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub fit_marginal: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub fit_tight: Color,
 
     // Run modes
+    #[serde(deserialize_with = "deserialize_color")]
     pub mode_gpu: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub mode_moe: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub mode_offload: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub mode_cpu: Color,
 
     // Status bar
+    #[serde(deserialize_with = "deserialize_color")]
     pub status_bg: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub status_fg: Color,
 }
 
+impl Default for ThemeColors {
+    fn default() -> Self {
+        default_colors()
+    }
+}
+
+impl ThemeColors {
+    /// Color a fit level is drawn in, in the table and the detail views.
+    pub fn fit_color(&self, level: FitLevel) -> Color {
+        match level {
+            FitLevel::Perfect => self.fit_perfect,
+            FitLevel::Good => self.fit_good,
+            FitLevel::Marginal => self.fit_marginal,
+            FitLevel::TooTight => self.fit_tight,
+        }
+    }
+
+    /// Map every RGB color to its nearest xterm-256 entry, for terminals
+    /// without true-color support.
+    pub fn to_256color_palette(self) -> ThemeColors {
+        ThemeColors {
+            bg: to_256color(self.bg),
+            fg: to_256color(self.fg),
+            muted: to_256color(self.muted),
+            border: to_256color(self.border),
+            title: to_256color(self.title),
+            highlight_bg: to_256color(self.highlight_bg),
+            accent: to_256color(self.accent),
+            accent_secondary: to_256color(self.accent_secondary),
+            good: to_256color(self.good),
+            warning: to_256color(self.warning),
+            error: to_256color(self.error),
+            info: to_256color(self.info),
+            score_high: to_256color(self.score_high),
+            score_mid: to_256color(self.score_mid),
+            score_low: to_256color(self.score_low),
+            fit_perfect: to_256color(self.fit_perfect),
+            fit_good: to_256color(self.fit_good),
+            fit_marginal: to_256color(self.fit_marginal),
+            fit_tight: to_256color(self.fit_tight),
+            mode_gpu: to_256color(self.mode_gpu),
+            mode_moe: to_256color(self.mode_moe),
+            mode_offload: to_256color(self.mode_offload),
+            mode_cpu: to_256color(self.mode_cpu),
+            status_bg: to_256color(self.status_bg),
+            status_fg: to_256color(self.status_fg),
+        }
+    }
+}
+
+/// A color as written in a theme file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColorSpec {
+    Rgb([u8; 3]),
+    Text(String),
+}
+
+/// Parse a color from an `[r, g, b]` triple or with ratatui's `FromStr`
+/// ("#282a36", "light blue", "238").
+fn deserialize_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    match ColorSpec::deserialize(deserializer)? {
+        ColorSpec::Rgb([r, g, b]) => Ok(Color::Rgb(r, g, b)),
+        ColorSpec::Text(s) => s.parse().map_err(serde::de::Error::custom),
+    }
+}
+
 fn default_colors() -> ThemeColors {
     // Default theme uses Color::Reset for fg so it inherits the terminal's
     // foreground color, making it work on both light and dark terminals.
@@ -171,6 +447,45 @@ fn default_colors() -> ThemeColors {
     }
 }
 
+fn default_light_colors() -> ThemeColors {
+    // Default Light: still inherits the terminal's fg/bg, but swaps the
+    // bright accents (yellow, light blue) that wash out on white for the
+    // darker ANSI colors.
+    ThemeColors {
+        bg: Color::Reset,
+        fg: Color::Reset,
+        muted: Color::DarkGray,
+        border: Color::DarkGray,
+        title: Color::Blue,
+        highlight_bg: Color::Gray,
+
+        accent: Color::Blue,
+        accent_secondary: Color::Magenta,
+
+        good: Color::Green,
+        warning: Color::Magenta,
+        error: Color::Red,
+        info: Color::Blue,
+
+        score_high: Color::Green,
+        score_mid: Color::Blue,
+        score_low: Color::Red,
+
+        fit_perfect: Color::Green,
+        fit_good: Color::Blue,
+        fit_marginal: Color::Magenta,
+        fit_tight: Color::Red,
+
+        mode_gpu: Color::Green,
+        mode_moe: Color::Blue,
+        mode_offload: Color::Magenta,
+        mode_cpu: Color::DarkGray,
+
+        status_bg: Color::Blue,
+        status_fg: Color::White,
+    }
+}
+
 fn dracula_colors() -> ThemeColors {
     // Dracula: dark purple bg, pastel accents
     ThemeColors {
@@ -355,3 +670,416 @@ fn gruvbox_colors() -> ThemeColors {
         status_fg: Color::Rgb(40, 40, 40),
     }
 }
+
+fn tokyo_night_colors() -> ThemeColors {
+    // Tokyo Night (night variant): deep blue-purple bg
+    ThemeColors {
+        bg: Color::Rgb(26, 27, 38),
+        fg: Color::Rgb(192, 202, 245),
+        muted: Color::Rgb(86, 95, 137),
+        border: Color::Rgb(59, 66, 97),
+        title: Color::Rgb(158, 206, 106),
+        highlight_bg: Color::Rgb(41, 46, 66),
+
+        accent: Color::Rgb(122, 162, 247),
+        accent_secondary: Color::Rgb(224, 175, 104),
+
+        good: Color::Rgb(158, 206, 106),
+        warning: Color::Rgb(224, 175, 104),
+        error: Color::Rgb(247, 118, 142),
+        info: Color::Rgb(125, 207, 255),
+
+        score_high: Color::Rgb(158, 206, 106),
+        score_mid: Color::Rgb(224, 175, 104),
+        score_low: Color::Rgb(247, 118, 142),
+
+        fit_perfect: Color::Rgb(158, 206, 106),
+        fit_good: Color::Rgb(224, 175, 104),
+        fit_marginal: Color::Rgb(187, 154, 247),
+        fit_tight: Color::Rgb(247, 118, 142),
+
+        mode_gpu: Color::Rgb(158, 206, 106),
+        mode_moe: Color::Rgb(125, 207, 255),
+        mode_offload: Color::Rgb(224, 175, 104),
+        mode_cpu: Color::Rgb(86, 95, 137),
+
+        status_bg: Color::Rgb(122, 162, 247),
+        status_fg: Color::Rgb(26, 27, 38),
+    }
+}
+
+fn catppuccin_mocha_colors() -> ThemeColors {
+    // Catppuccin Mocha: darkest flavor, mauve accent
+    ThemeColors {
+        bg: Color::Rgb(30, 30, 46),           // base
+        fg: Color::Rgb(205, 214, 244),        // text
+        muted: Color::Rgb(108, 112, 134),     // overlay0
+        border: Color::Rgb(69, 71, 90),       // surface1
+        title: Color::Rgb(166, 227, 161),     // green
+        highlight_bg: Color::Rgb(49, 50, 68), // surface0
+
+        accent: Color::Rgb(203, 166, 247),           // mauve
+        accent_secondary: Color::Rgb(249, 226, 175), // yellow
+
+        good: Color::Rgb(166, 227, 161),
+        warning: Color::Rgb(249, 226, 175),
+        error: Color::Rgb(243, 139, 168), // red
+        info: Color::Rgb(137, 180, 250),  // blue
+
+        score_high: Color::Rgb(166, 227, 161),
+        score_mid: Color::Rgb(249, 226, 175),
+        score_low: Color::Rgb(243, 139, 168),
+
+        fit_perfect: Color::Rgb(166, 227, 161),
+        fit_good: Color::Rgb(249, 226, 175),
+        fit_marginal: Color::Rgb(250, 179, 135), // peach
+        fit_tight: Color::Rgb(243, 139, 168),
+
+        mode_gpu: Color::Rgb(166, 227, 161),
+        mode_moe: Color::Rgb(148, 226, 213), // teal
+        mode_offload: Color::Rgb(249, 226, 175),
+        mode_cpu: Color::Rgb(108, 112, 134),
+
+        status_bg: Color::Rgb(203, 166, 247),
+        status_fg: Color::Rgb(17, 17, 27), // crust
+    }
+}
+
+fn catppuccin_latte_colors() -> ThemeColors {
+    // Catppuccin Latte: the light flavor, for light terminals
+    ThemeColors {
+        bg: Color::Rgb(239, 241, 245),           // base
+        fg: Color::Rgb(76, 79, 105),             // text
+        muted: Color::Rgb(156, 160, 176),        // overlay0
+        border: Color::Rgb(188, 192, 204),       // surface1
+        title: Color::Rgb(64, 160, 43),          // green
+        highlight_bg: Color::Rgb(204, 208, 218), // surface0
+
+        accent: Color::Rgb(136, 57, 239),           // mauve
+        accent_secondary: Color::Rgb(223, 142, 29), // yellow
+
+        good: Color::Rgb(64, 160, 43),
+        warning: Color::Rgb(223, 142, 29),
+        error: Color::Rgb(210, 15, 57), // red
+        info: Color::Rgb(30, 102, 245), // blue
+
+        score_high: Color::Rgb(64, 160, 43),
+        score_mid: Color::Rgb(223, 142, 29),
+        score_low: Color::Rgb(210, 15, 57),
+
+        fit_perfect: Color::Rgb(64, 160, 43),
+        fit_good: Color::Rgb(223, 142, 29),
+        fit_marginal: Color::Rgb(254, 100, 11), // peach
+        fit_tight: Color::Rgb(210, 15, 57),
+
+        mode_gpu: Color::Rgb(64, 160, 43),
+        mode_moe: Color::Rgb(23, 146, 153), // teal
+        mode_offload: Color::Rgb(223, 142, 29),
+        mode_cpu: Color::Rgb(156, 160, 176),
+
+        status_bg: Color::Rgb(136, 57, 239),
+        status_fg: Color::Rgb(239, 241, 245), // base
+    }
+}
+
+fn high_contrast_colors() -> ThemeColors {
+    // High Contrast: 16 ANSI colors only, so it renders the same on any
+    // terminal. Bright variants on black keep good / warning / error apart
+    // for color-vision deficiencies; shapes carry the meaning as well.
+    ThemeColors {
+        bg: Color::Black,
+        fg: Color::White,
+        muted: Color::Gray,
+        border: Color::White,
+        title: Color::LightGreen,
+        highlight_bg: Color::Blue,
+
+        accent: Color::LightYellow,
+        accent_secondary: Color::LightBlue,
+
+        good: Color::LightGreen,
+        warning: Color::LightYellow,
+        error: Color::LightRed,
+        info: Color::LightBlue,
+
+        score_high: Color::LightGreen,
+        score_mid: Color::LightYellow,
+        score_low: Color::LightRed,
+
+        fit_perfect: Color::LightGreen,
+        fit_good: Color::Green,
+        fit_marginal: Color::LightYellow,
+        fit_tight: Color::LightRed,
+
+        mode_gpu: Color::LightGreen,
+        mode_moe: Color::LightBlue,
+        mode_offload: Color::LightYellow,
+        mode_cpu: Color::White,
+
+        status_bg: Color::White,
+        status_fg: Color::Black,
+    }
+}
+
+fn okabe_ito_colors() -> ThemeColors {
+    // Okabe-Ito: the eight colors chosen by Okabe & Ito to stay distinct
+    // under protanopia, deuteranopia and tritanopia. Fit levels run from
+    // sky blue through green and yellow to vermillion, so neighbours differ
+    // in lightness as well as hue.
+    ThemeColors {
+        bg: Color::Rgb(0x00, 0x00, 0x00),
+        fg: Color::Rgb(0xff, 0xff, 0xff),
+        muted: Color::Rgb(0x99, 0x99, 0x99), // grey
+        border: Color::Rgb(0x99, 0x99, 0x99),
+        title: Color::Rgb(0x56, 0xb4, 0xe9),        // sky blue
+        highlight_bg: Color::Rgb(0x00, 0x72, 0xb2), // blue
+
+        accent: Color::Rgb(0xe6, 0x9f, 0x00),           // orange
+        accent_secondary: Color::Rgb(0xcc, 0x79, 0xa7), // reddish purple
+
+        good: Color::Rgb(0x00, 0x9e, 0x73),    // bluish green
+        warning: Color::Rgb(0xe6, 0x9f, 0x00), // orange
+        error: Color::Rgb(0xd5, 0x5e, 0x00),   // vermillion
+        info: Color::Rgb(0x56, 0xb4, 0xe9),
+
+        score_high: Color::Rgb(0x56, 0xb4, 0xe9),
+        score_mid: Color::Rgb(0xf0, 0xe4, 0x42), // yellow
+        score_low: Color::Rgb(0xd5, 0x5e, 0x00),
+
+        fit_perfect: Color::Rgb(0x56, 0xb4, 0xe9),
+        fit_good: Color::Rgb(0x00, 0x9e, 0x73),
+        fit_marginal: Color::Rgb(0xf0, 0xe4, 0x42),
+        fit_tight: Color::Rgb(0xd5, 0x5e, 0x00),
+
+        mode_gpu: Color::Rgb(0x00, 0x9e, 0x73),
+        mode_moe: Color::Rgb(0xcc, 0x79, 0xa7),
+        mode_offload: Color::Rgb(0xe6, 0x9f, 0x00),
+        mode_cpu: Color::Rgb(0x99, 0x99, 0x99),
+
+        status_bg: Color::Rgb(0x00, 0x72, 0xb2),
+        status_fg: Color::Rgb(0xff, 0xff, 0xff),
+    }
+}
+
+fn mono_colors() -> ThemeColors {
+    // Mono: no color at all beyond the terminal's own fg/bg and dim gray.
+    // Fit levels rely on the ✓ ⚠ ✗ markers and their text labels; the
+    // selected row keeps its ▶ marker and bold text.
+    ThemeColors {
+        bg: Color::Reset,
+        fg: Color::Reset,
+        muted: Color::DarkGray,
+        border: Color::DarkGray,
+        title: Color::Reset,
+        highlight_bg: Color::Reset,
+
+        accent: Color::White,
+        accent_secondary: Color::Reset,
+
+        good: Color::Reset,
+        warning: Color::White,
+        error: Color::White,
+        info: Color::Reset,
+
+        score_high: Color::White,
+        score_mid: Color::Reset,
+        score_low: Color::DarkGray,
+
+        fit_perfect: Color::White,
+        fit_good: Color::Reset,
+        fit_marginal: Color::Reset,
+        fit_tight: Color::DarkGray,
+
+        mode_gpu: Color::Reset,
+        mode_moe: Color::Reset,
+        mode_offload: Color::Reset,
+        mode_cpu: Color::DarkGray,
+
+        status_bg: Color::Reset,
+        status_fg: Color::Reset,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokyo_night_colors() {
+        let tc = Theme::TokyoNight.colors();
+        assert_eq!(tc.bg, Color::Rgb(0x1a, 0x1b, 0x26));
+        assert_eq!(tc.good, Color::Rgb(0x9e, 0xce, 0x6a));
+        assert_eq!(tc.error, Color::Rgb(0xf7, 0x76, 0x8e));
+        assert_eq!(tc.accent, Color::Rgb(0x7a, 0xa2, 0xf7));
+    }
+
+    #[test]
+    fn test_256color_palette_has_no_rgb() {
+        let tc = Theme::TokyoNight.colors().to_256color_palette();
+        assert!(!matches!(tc.bg, Color::Rgb(..)));
+        assert!(matches!(tc.accent, Color::Indexed(_)));
+        assert_eq!(
+            Theme::HighContrast.colors().to_256color_palette(),
+            Theme::HighContrast.colors()
+        );
+    }
+
+    #[test]
+    fn test_high_contrast_uses_ansi_colors_only() {
+        let tc = Theme::HighContrast.colors();
+        let all = [
+            tc.bg,
+            tc.fg,
+            tc.muted,
+            tc.border,
+            tc.title,
+            tc.highlight_bg,
+            tc.accent,
+            tc.accent_secondary,
+            tc.good,
+            tc.warning,
+            tc.error,
+            tc.info,
+            tc.score_high,
+            tc.score_mid,
+            tc.score_low,
+            tc.fit_perfect,
+            tc.fit_good,
+            tc.fit_marginal,
+            tc.fit_tight,
+            tc.mode_gpu,
+            tc.mode_moe,
+            tc.mode_offload,
+            tc.mode_cpu,
+            tc.status_bg,
+            tc.status_fg,
+        ];
+        assert!(
+            all.iter()
+                .all(|c| !matches!(c, Color::Rgb(..) | Color::Indexed(_)))
+        );
+        assert_ne!(tc.score_high, tc.score_mid);
+        assert_ne!(tc.score_mid, tc.score_low);
+        assert!(Theme::HighContrast.shape_hints());
+    }
+
+    #[test]
+    fn test_named_theme_from_themes_dir() {
+        let dir = std::env::temp_dir().join(format!("llmfit-themes-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("my term.toml"),
+            "bg = [40, 42, 54]\nfg = \"#f8f8f2\"\nfit_perfect = \"light green\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("broken.toml"), "bg = [300, 0, 0]\n").unwrap();
+
+        let theme = Theme::resolve_in(&dir, "my term").unwrap();
+        assert_eq!(theme.label(), "my term");
+        let tc = theme.colors();
+        assert_eq!(tc.bg, Color::Rgb(40, 42, 54));
+        assert_eq!(tc.fg, Color::Rgb(0xf8, 0xf8, 0xf2));
+        assert_eq!(tc.fit_perfect, Color::LightGreen);
+        assert_eq!(tc.accent, default_colors().accent);
+        assert_eq!(theme.next(), Theme::Default);
+
+        assert_eq!(Theme::from_label("my term"), Theme::Default);
+        assert_eq!(Theme::resolve_in(&dir, "Nord").unwrap(), Theme::Nord);
+        assert!(matches!(
+            Theme::resolve_in(&dir, "broken"),
+            Err(ThemeError::Parse(_))
+        ));
+        assert!(matches!(
+            Theme::resolve_in(&dir, "missing"),
+            Err(ThemeError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound
+        ));
+        assert!(matches!(
+            Theme::load_named_in(&dir, "../theme"),
+            Err(ThemeError::InvalidName(_))
+        ));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_okabe_ito_fit_levels_are_distinct() {
+        let tc = Theme::OkabeIto.colors();
+        let fits = [tc.fit_perfect, tc.fit_good, tc.fit_marginal, tc.fit_tight];
+        for (i, a) in fits.iter().enumerate() {
+            assert!(fits[i + 1..].iter().all(|b| a != b));
+        }
+        assert_eq!(tc.fit_tight, Color::Rgb(0xd5, 0x5e, 0x00));
+        assert!(Theme::OkabeIto.shape_hints());
+        assert_eq!(Theme::HighContrast.next(), Theme::OkabeIto);
+        assert_eq!(Theme::from_label("Okabe-Ito"), Theme::OkabeIto);
+    }
+
+    #[test]
+    fn test_default_light_avoids_pale_accents() {
+        let tc = Theme::DefaultLight.colors();
+        let pale = [
+            Color::Yellow,
+            Color::LightYellow,
+            Color::LightBlue,
+            Color::Cyan,
+        ];
+        for color in [
+            tc.fit_good,
+            tc.warning,
+            tc.highlight_bg,
+            tc.accent,
+            tc.score_mid,
+        ] {
+            assert!(!pale.contains(&color), "{:?}", color);
+        }
+        assert_eq!(Theme::Default.next(), Theme::DefaultLight);
+        assert_eq!(Theme::from_label("Default Light"), Theme::DefaultLight);
+    }
+
+    #[test]
+    fn test_mono_has_no_color() {
+        let tc = Theme::Mono.colors();
+        let all = [
+            tc.bg,
+            tc.fg,
+            tc.muted,
+            tc.border,
+            tc.title,
+            tc.highlight_bg,
+            tc.accent,
+            tc.accent_secondary,
+            tc.good,
+            tc.warning,
+            tc.error,
+            tc.info,
+            tc.score_high,
+            tc.score_mid,
+            tc.score_low,
+            tc.fit_perfect,
+            tc.fit_good,
+            tc.fit_marginal,
+            tc.fit_tight,
+            tc.mode_gpu,
+            tc.mode_moe,
+            tc.mode_offload,
+            tc.mode_cpu,
+            tc.status_bg,
+            tc.status_fg,
+        ];
+        assert!(
+            all.iter()
+                .all(|c| matches!(c, Color::Reset | Color::White | Color::DarkGray))
+        );
+        assert!(Theme::Mono.shape_hints());
+        assert_eq!(Theme::from_label("Mono"), Theme::Mono);
+    }
+
+    #[test]
+    fn test_tokyo_night_in_rotation() {
+        assert_eq!(Theme::Gruvbox.next(), Theme::TokyoNight);
+        assert_eq!(
+            Theme::from_label(Theme::TokyoNight.label()),
+            Theme::TokyoNight
+        );
+    }
+}
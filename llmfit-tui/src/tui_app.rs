@@ -1,20 +1,34 @@
-use llmfit_core::fit::{FitLevel, ModelFit, SortColumn};
+use llmfit_core::fit::{FitLevel, ModelFit, RunMode, SortColumn};
 use llmfit_core::hardware::SystemSpecs;
 use llmfit_core::models::ModelDatabase;
 use llmfit_core::providers::{
     self, MlxProvider, ModelProvider, OllamaProvider, PullEvent, PullHandle,
 };
 
+use ratatui::layout::Rect;
 use std::collections::HashSet;
+use std::path::Path;
 use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
-use crate::theme::Theme;
+use crate::color_support::{ColorDepth, terminal_color_depth};
+use crate::config::Config;
+use crate::export::{self, ExportFormat};
+use crate::history::HistoryEntry;
+use crate::memory_history::{MemoryHistory, MemorySampler};
+use crate::profiles::{ReferenceProfile, reference_profiles};
+use crate::theme::{Theme, ThemeColors};
+
+/// File name offered when pressing `e`, relative to the working directory.
+const DEFAULT_EXPORT_PATH: &str = "llmfit-results.json";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputMode {
     Normal,
     Search,
     ProviderPopup,
+    Export, // typing a file name for `e`
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -51,6 +65,95 @@ impl FitFilter {
     }
 }
 
+/// How often the detection spinner advances a frame.
+pub const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Where startup hardware detection has got to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DetectionState {
+    InProgress,
+    Complete,
+    Failed(String),
+}
+
+/// Hardware detection running on a background thread, so the TUI can show
+/// a spinner instead of a blank screen while `nvidia-smi` and friends run.
+/// The worker hands back its warnings with the specs rather than printing
+/// them over the TUI.
+pub struct Detection {
+    pub state: DetectionState,
+    receiver: mpsc::Receiver<(SystemSpecs, Vec<String>)>,
+    worker: Option<JoinHandle<()>>,
+    started: Instant,
+}
+
+impl Detection {
+    /// Run `detect` on a new thread; poll for the result with [`Self::poll`].
+    pub fn spawn<F>(detect: F) -> Self
+    where
+        F: FnOnce() -> (SystemSpecs, Vec<String>) + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let worker = std::thread::spawn(move || {
+            let _ = tx.send(detect());
+        });
+        Detection {
+            state: DetectionState::InProgress,
+            receiver: rx,
+            worker: Some(worker),
+            started: Instant::now(),
+        }
+    }
+
+    /// Check for a result without blocking. Returns the specs and warnings
+    /// once, when detection completes; a worker that dies without sending
+    /// moves the state to `Failed` with its panic message.
+    pub fn poll(&mut self) -> Option<(SystemSpecs, Vec<String>)> {
+        if self.state != DetectionState::InProgress {
+            return None;
+        }
+        match self.receiver.try_recv() {
+            Ok(detected) => {
+                self.state = DetectionState::Complete;
+                Some(detected)
+            }
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                let reason = match self.worker.take().map(JoinHandle::join) {
+                    Some(Err(payload)) => payload
+                        .downcast_ref::<&str>()
+                        .map(|s| s.to_string())
+                        .or_else(|| payload.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "detection thread panicked".to_string()),
+                    _ => "detection thread exited without a result".to_string(),
+                };
+                self.state = DetectionState::Failed(reason);
+                None
+            }
+        }
+    }
+
+    /// Spinner frame to draw, advancing every [`SPINNER_INTERVAL`].
+    pub fn frame(&self) -> usize {
+        (self.started.elapsed().as_millis() / SPINNER_INTERVAL.as_millis()) as usize
+    }
+}
+
+/// Colours for the configured theme, reduced to 256 colours when the
+/// terminal can't do truecolor.
+pub fn startup_theme_colors() -> ThemeColors {
+    adapt_colors(&Theme::load(), terminal_color_depth())
+}
+
+fn adapt_colors(theme: &Theme, depth: ColorDepth) -> ThemeColors {
+    let tc = theme.colors();
+    if depth == ColorDepth::Colors256 {
+        tc.to_256color_palette()
+    } else {
+        tc
+    }
+}
+
 pub struct App {
     pub should_quit: bool,
     pub input_mode: InputMode,
@@ -71,9 +174,44 @@ pub struct App {
 
     // Table state
     pub selected_row: usize,
+    /// First model row shown in the table; kept in view of `selected_row`
+    /// each time the table is drawn.
+    pub scroll_offset: usize,
+    /// Model rows visible in the table on the last draw.
+    pub viewport_rows: usize,
 
     // Detail view
     pub show_detail: bool,
+    /// Fit summary popup over the table for the selected model.
+    pub detail_popup: bool,
+
+    // Keybinding help overlay
+    pub show_help: bool,
+
+    // Hardware compare view: this machine vs a reference profile
+    pub show_compare: bool,
+    pub reference_profiles: Vec<ReferenceProfile>,
+    pub compare_profile: usize,
+    /// Set when comparing against a past scan (an index into `history`)
+    /// instead of a reference profile.
+    pub compare_history: Option<usize>,
+    /// `--max-context` cap the fits were analyzed with, reapplied to the
+    /// reference machine so both columns are sized alike.
+    pub context_limit: Option<u32>,
+
+    // Hardware scan history (oldest first), listed newest first with `h`
+    pub show_history: bool,
+    pub history: Vec<HistoryEntry>,
+    pub history_cursor: usize,
+
+    // Free memory panel (`m`)
+    pub show_memory: bool,
+    pub memory_history: MemoryHistory,
+    memory_sampler: Option<MemorySampler>,
+
+    // Export prompt
+    pub export_path: String,
+    pub export_format: ExportFormat,
 
     // Provider popup
     pub provider_cursor: usize,
@@ -96,18 +234,32 @@ pub struct App {
     /// When true, the next 'd' press will confirm and start the download.
     pub confirm_download: bool,
 
+    // Settings from ~/.config/llmfit/config.toml and --refresh-rate
+    pub config: Config,
+
     // Theme
     pub theme: Theme,
+    pub color_depth: ColorDepth,
+
+    // Mouse hit-testing: areas recorded on the last draw
+    pub table_area: Rect,
+    pub theme_area: Rect,
+    /// Time and row of the last click, for double-click detection.
+    pub last_click: Option<(Instant, usize)>,
 }
 
 impl App {
+    /// App for `specs` with the built-in database and no context cap.
+    #[allow(dead_code)]
     pub fn with_specs(specs: SystemSpecs) -> Self {
-        Self::with_specs_and_context(specs, None)
+        Self::with_database(&ModelDatabase::new(), specs, None)
     }
 
-    pub fn with_specs_and_context(specs: SystemSpecs, context_limit: Option<u32>) -> Self {
-        let db = ModelDatabase::new();
-
+    pub fn with_database(
+        db: &ModelDatabase,
+        specs: SystemSpecs,
+        context_limit: Option<u32>,
+    ) -> Self {
         // Detect Ollama
         let ollama = OllamaProvider::new();
         let ollama_available = ollama.is_available();
@@ -169,7 +321,24 @@ impl App {
             installed_first: false,
             sort_column: SortColumn::Score,
             selected_row: 0,
+            scroll_offset: 0,
+            viewport_rows: 10,
             show_detail: false,
+            detail_popup: false,
+            show_help: false,
+            show_compare: false,
+            reference_profiles: reference_profiles(),
+            compare_profile: 0,
+            compare_history: None,
+            context_limit,
+            show_history: false,
+            history: Vec::new(),
+            history_cursor: 0,
+            show_memory: false,
+            memory_history: MemoryHistory::default(),
+            memory_sampler: None,
+            export_path: DEFAULT_EXPORT_PATH.to_string(),
+            export_format: ExportFormat::Json,
             provider_cursor: 0,
             ollama_available,
             ollama_installed,
@@ -183,7 +352,12 @@ impl App {
             pull_model_name: None,
             tick_count: 0,
             confirm_download: false,
+            config: Config::default(),
             theme: Theme::load(),
+            color_depth: terminal_color_depth(),
+            table_area: Rect::default(),
+            theme_area: Rect::default(),
+            last_click: None,
         };
 
         app.apply_filters();
@@ -232,7 +406,9 @@ impl App {
                     FitFilter::Runnable => fit.fit_level != FitLevel::TooTight,
                 };
 
-                matches_search && matches_provider && matches_fit
+                let matches_mode = self.config.show_cpu_models || fit.run_mode != RunMode::CpuOnly;
+
+                matches_search && matches_provider && matches_fit && matches_mode
             })
             .map(|(i, _)| i)
             .collect();
@@ -265,25 +441,34 @@ impl App {
         }
     }
 
+    /// Rows moved by PgUp/PgDn: one full viewport.
+    fn page_size(&self) -> usize {
+        self.viewport_rows.max(1)
+    }
+
     pub fn page_up(&mut self) {
         self.confirm_download = false;
-        self.selected_row = self.selected_row.saturating_sub(10);
+        self.selected_row = self.selected_row.saturating_sub(self.page_size());
     }
 
     pub fn page_down(&mut self) {
         self.confirm_download = false;
         if !self.filtered_fits.is_empty() {
-            self.selected_row = (self.selected_row + 10).min(self.filtered_fits.len() - 1);
+            self.selected_row =
+                (self.selected_row + self.page_size()).min(self.filtered_fits.len() - 1);
         }
     }
 
     pub fn half_page_up(&mut self) {
-        self.selected_row = self.selected_row.saturating_sub(5);
+        self.selected_row = self
+            .selected_row
+            .saturating_sub(self.page_size().div_ceil(2));
     }
 
     pub fn half_page_down(&mut self) {
         if !self.filtered_fits.is_empty() {
-            self.selected_row = (self.selected_row + 5).min(self.filtered_fits.len() - 1);
+            self.selected_row = (self.selected_row + self.page_size().div_ceil(2))
+                .min(self.filtered_fits.len() - 1);
         }
     }
 
@@ -307,9 +492,76 @@ impl App {
         self.re_sort();
     }
 
+    /// Colors of the current theme, downgraded to the 256-color palette
+    /// when the terminal can't render RGB.
+    pub fn theme_colors(&self) -> ThemeColors {
+        adapt_colors(&self.theme, self.color_depth)
+    }
+
     pub fn cycle_theme(&mut self) {
         self.theme = self.theme.next();
-        self.theme.save();
+        self.config.theme = Some(self.theme.clone());
+        if let Err(e) = self.theme.save() {
+            self.pull_status = Some(format!("Could not save theme: {}", e));
+        }
+    }
+
+    pub fn start_export(&mut self) {
+        self.input_mode = InputMode::Export;
+    }
+
+    pub fn cancel_export(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn export_input(&mut self, c: char) {
+        self.export_path.push(c);
+    }
+
+    pub fn export_backspace(&mut self) {
+        self.export_path.pop();
+    }
+
+    /// Switch to the next export format, swapping the file extension along
+    /// with it when the name ends in another format's extension.
+    pub fn cycle_export_format(&mut self) {
+        let old = self.export_format;
+        self.export_format = old.next();
+        if let Some(stem) = self
+            .export_path
+            .strip_suffix(old.extension())
+            .filter(|stem| stem.ends_with('.'))
+        {
+            self.export_path = format!("{}{}", stem, self.export_format.extension());
+        }
+    }
+
+    /// Write the models currently shown (filters applied, in table order)
+    /// to `export_path` in `export_format` and report the outcome in the
+    /// status bar.
+    pub fn confirm_export(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let path = self.export_path.trim();
+        if path.is_empty() {
+            self.pull_status = Some("Export cancelled: no file name".to_string());
+            return;
+        }
+        let fits: Vec<ModelFit> = self
+            .filtered_fits
+            .iter()
+            .map(|&idx| self.all_fits[idx].clone())
+            .collect();
+        self.pull_status = Some(
+            match export::export_results(&self.specs, &fits, Path::new(path), self.export_format) {
+                Ok(()) => format!(
+                    "Exported {} models to {} ({})",
+                    fits.len(),
+                    path,
+                    self.export_format.label()
+                ),
+                Err(e) => format!("Export failed: {}", e),
+            },
+        );
     }
 
     pub fn enter_search(&mut self) {
@@ -320,6 +572,13 @@ impl App {
         self.input_mode = InputMode::Normal;
     }
 
+    /// Leave search mode and drop the filter (Esc), as opposed to
+    /// [`App::exit_search`] which keeps it applied (Enter).
+    pub fn cancel_search(&mut self) {
+        self.clear_search();
+        self.exit_search();
+    }
+
     pub fn search_input(&mut self, c: char) {
         self.search_query.insert(self.cursor_position, c);
         self.cursor_position += 1;
@@ -351,6 +610,110 @@ impl App {
         self.show_detail = !self.show_detail;
     }
 
+    pub fn open_detail_popup(&mut self) {
+        if self.selected_fit().is_some() {
+            self.detail_popup = true;
+        }
+    }
+
+    pub fn close_detail_popup(&mut self) {
+        self.detail_popup = false;
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+    }
+
+    pub fn open_compare(&mut self) {
+        if self.selected_fit().is_some() {
+            self.show_compare = true;
+        }
+    }
+
+    pub fn close_compare(&mut self) {
+        self.show_compare = false;
+        self.compare_history = None;
+    }
+
+    pub fn next_compare_profile(&mut self) {
+        if self.compare_history.take().is_some() {
+            return;
+        }
+        if !self.reference_profiles.is_empty() {
+            self.compare_profile = (self.compare_profile + 1) % self.reference_profiles.len();
+        }
+    }
+
+    pub fn prev_compare_profile(&mut self) {
+        if self.compare_history.take().is_some() {
+            return;
+        }
+        if !self.reference_profiles.is_empty() {
+            self.compare_profile = self
+                .compare_profile
+                .checked_sub(1)
+                .unwrap_or(self.reference_profiles.len() - 1);
+        }
+    }
+
+    /// The compare view's right-hand machine (a past scan if one was picked
+    /// from the history, else the reference profile) as (title, specs),
+    /// with the selected model analyzed on it.
+    pub fn compare_reference(&self) -> Option<(String, SystemSpecs, ModelFit)> {
+        let fit = self.selected_fit()?;
+        let (title, specs) = match self.compare_history {
+            Some(idx) => {
+                let entry = self.history.get(idx)?;
+                (
+                    format!("Scan {}", entry.timestamp_label()),
+                    entry.specs.clone(),
+                )
+            }
+            None => {
+                let profile = self.reference_profiles.get(self.compare_profile)?;
+                (profile.name.clone(), profile.specs())
+            }
+        };
+        let reference =
+            ModelFit::analyze_with_context_limit(&fit.model, &specs, self.context_limit);
+        Some((title, specs, reference))
+    }
+
+    pub fn open_history(&mut self) {
+        self.show_history = true;
+        self.history_cursor = 0;
+    }
+
+    pub fn close_history(&mut self) {
+        self.show_history = false;
+    }
+
+    pub fn history_up(&mut self) {
+        self.history_cursor = self.history_cursor.saturating_sub(1);
+    }
+
+    pub fn history_down(&mut self) {
+        if self.history_cursor + 1 < self.history.len() {
+            self.history_cursor += 1;
+        }
+    }
+
+    /// Index into `history` of the highlighted row; the list is newest first.
+    pub fn history_selected(&self) -> Option<usize> {
+        self.history.len().checked_sub(1 + self.history_cursor)
+    }
+
+    /// Open the compare view against the highlighted past scan.
+    pub fn compare_history_entry(&mut self) {
+        if let Some(idx) = self.history_selected()
+            && self.selected_fit().is_some()
+        {
+            self.compare_history = Some(idx);
+            self.show_history = false;
+            self.show_compare = true;
+        }
+    }
+
     pub fn open_provider_popup(&mut self) {
         self.input_mode = InputMode::ProviderPopup;
         // Don't reset cursor -- keep it where it was last time
@@ -505,6 +868,24 @@ impl App {
         }
     }
 
+    /// Start sampling free RAM and VRAM for the memory panel.
+    pub fn start_memory_sampling(&mut self) {
+        self.memory_sampler = Some(MemorySampler::spawn(self.specs.clone()));
+    }
+
+    /// Move new memory samples into the history. Called each TUI tick.
+    pub fn tick_memory(&mut self) {
+        if let Some(sampler) = &self.memory_sampler {
+            for sample in sampler.drain() {
+                self.memory_history.push(sample);
+            }
+        }
+    }
+
+    pub fn toggle_memory_panel(&mut self) {
+        self.show_memory = !self.show_memory;
+    }
+
     /// Re-query all providers for installed models and update all_fits.
     pub fn refresh_installed(&mut self) {
         self.ollama_installed = self.ollama.installed_models();
@@ -516,3 +897,131 @@ impl App {
         self.re_sort();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llmfit_core::hardware::GpuBackend;
+
+    fn wait_for(detection: &mut Detection) -> Option<(SystemSpecs, Vec<String>)> {
+        for _ in 0..200 {
+            if let Some(detected) = detection.poll() {
+                return Some(detected);
+            }
+            if detection.state != DetectionState::InProgress {
+                return None;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        panic!("detection did not finish");
+    }
+
+    #[test]
+    fn test_detection_completes_once() {
+        let mut detection = Detection::spawn(|| {
+            let specs = SystemSpecs::new_manual(16.0, None, GpuBackend::CpuX86, 0);
+            (specs, vec!["no history".to_string()])
+        });
+        let (specs, warnings) = wait_for(&mut detection).unwrap();
+        assert_eq!(specs.total_ram_gb, 16.0);
+        assert_eq!(warnings, ["no history"]);
+        assert_eq!(detection.state, DetectionState::Complete);
+        assert!(detection.poll().is_none());
+    }
+
+    #[test]
+    fn test_detection_reports_panic() {
+        let mut detection = Detection::spawn(|| panic!("no GPU tools"));
+        assert!(wait_for(&mut detection).is_none());
+        assert_eq!(
+            detection.state,
+            DetectionState::Failed("no GPU tools".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cycle_export_format_swaps_extension() {
+        let specs = SystemSpecs::new_manual(16.0, None, GpuBackend::CpuX86, 0);
+        let mut app = App::with_specs(specs);
+        assert_eq!(app.export_path, "llmfit-results.json");
+        app.cycle_export_format();
+        assert_eq!(app.export_format, ExportFormat::Toml);
+        assert_eq!(app.export_path, "llmfit-results.toml");
+        app.cycle_export_format();
+        app.cycle_export_format();
+        assert_eq!(app.export_path, "llmfit-results.md");
+        // A name the user typed without a matching extension is left alone
+        app.export_path = "report".to_string();
+        app.cycle_export_format();
+        assert_eq!(app.export_format, ExportFormat::Json);
+        assert_eq!(app.export_path, "report");
+    }
+
+    #[test]
+    fn test_show_cpu_models_filters_cpu_only_fits() {
+        // Without a GPU every model runs from system RAM
+        let specs = SystemSpecs::new_manual(64.0, None, GpuBackend::CpuX86, 0);
+        let mut app = App::with_specs(specs);
+        assert!(!app.filtered_fits.is_empty());
+        assert!(app.all_fits.iter().all(|f| f.run_mode == RunMode::CpuOnly));
+
+        app.config.show_cpu_models = false;
+        app.apply_filters();
+        assert!(app.filtered_fits.is_empty());
+    }
+
+    #[test]
+    fn test_compare_profile_cycles_and_analyzes_selected_model() {
+        let specs = SystemSpecs::mock(8.0, 16.0, GpuBackend::Cuda);
+        let mut app = App::with_specs(specs);
+        let count = app.reference_profiles.len();
+
+        app.prev_compare_profile();
+        assert_eq!(app.compare_profile, count - 1);
+        app.next_compare_profile();
+        assert_eq!(app.compare_profile, 0);
+
+        app.open_compare();
+        assert!(app.show_compare);
+        let selected = app.selected_fit().unwrap().model.name.clone();
+        let (title, ref_specs, reference) = app.compare_reference().unwrap();
+        assert_eq!(title, app.reference_profiles[0].name);
+        assert_eq!(ref_specs.total_ram_gb, app.reference_profiles[0].ram_gb);
+        assert_eq!(reference.model.name, selected);
+    }
+
+    #[test]
+    fn test_history_entry_opens_compare_view() {
+        let specs = SystemSpecs::mock(8.0, 16.0, GpuBackend::Cuda);
+        let docked = SystemSpecs::mock(24.0, 16.0, GpuBackend::Cuda);
+        let mut app = App::with_specs(specs.clone());
+        app.history = vec![
+            HistoryEntry {
+                timestamp: 0,
+                specs: docked.clone(),
+            },
+            HistoryEntry {
+                timestamp: 86_400,
+                specs,
+            },
+        ];
+
+        app.open_history();
+        // Newest first: row 0 is the last entry
+        assert_eq!(app.history_selected(), Some(1));
+        app.history_down();
+        app.history_down();
+        assert_eq!(app.history_selected(), Some(0));
+
+        app.compare_history_entry();
+        assert!(app.show_compare && !app.show_history);
+        let (title, ref_specs, _) = app.compare_reference().unwrap();
+        assert_eq!(title, "Scan 1970-01-01 00:00 UTC");
+        assert_eq!(ref_specs, docked);
+
+        // Switching profile leaves the past scan for the presets
+        app.next_compare_profile();
+        assert_eq!(app.compare_history, None);
+        assert_eq!(app.compare_profile, 0);
+    }
+}
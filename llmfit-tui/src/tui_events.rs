@@ -1,28 +1,61 @@
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use std::time::Duration;
 
-use crate::tui_app::{App, InputMode};
+use crate::mouse;
+use crate::tui_app::{App, Detection, DetectionState, InputMode, SPINNER_INTERVAL};
 
 /// Poll for and handle events. Returns true if an event was processed.
 pub fn handle_events(app: &mut App) -> std::io::Result<bool> {
     // Always tick the pull progress (non-blocking)
     app.tick_pull();
+    app.tick_memory();
 
-    if event::poll(Duration::from_millis(50))?
-        && let Event::Key(key) = event::read()?
-    {
-        // Only handle Press events (ignore Release on some platforms)
-        if key.kind != KeyEventKind::Press {
-            return Ok(false);
-        }
-        match app.input_mode {
-            InputMode::Normal => handle_normal_mode(app, key),
-            InputMode::Search => handle_search_mode(app, key),
-            InputMode::ProviderPopup => handle_provider_popup_mode(app, key),
+    if !event::poll(app.config.refresh_rate())? {
+        return Ok(false);
+    }
+    match event::read()? {
+        Event::Key(key) => {
+            // Only handle Press events (ignore Release on some platforms)
+            if key.kind != KeyEventKind::Press {
+                return Ok(false);
+            }
+            match app.input_mode {
+                InputMode::Normal if app.show_help => handle_help_overlay(app, key),
+                InputMode::Normal if app.detail_popup => handle_detail_popup(app, key),
+                InputMode::Normal if app.show_compare => handle_compare_view(app, key),
+                InputMode::Normal if app.show_history => handle_history_view(app, key),
+                InputMode::Normal => handle_normal_mode(app, key),
+                InputMode::Search => handle_search_mode(app, key),
+                InputMode::ProviderPopup => handle_provider_popup_mode(app, key),
+                InputMode::Export => handle_export_mode(app, key),
+            }
+            Ok(true)
         }
-        return Ok(true);
+        // Only arrives when mouse capture was enabled with --mouse
+        Event::Mouse(event) => Ok(mouse::handle_mouse_event(event, app)),
+        _ => Ok(false),
     }
-    Ok(false)
+}
+
+/// Wait up to one spinner frame for a key while hardware detection runs.
+/// Returns true if the user asked to quit: q, Esc or Ctrl-C while
+/// detecting, any key once detection has failed.
+pub fn handle_detection_events(detection: &Detection) -> std::io::Result<bool> {
+    if !event::poll(SPINNER_INTERVAL)? {
+        return Ok(false);
+    }
+    let Event::Key(key) = event::read()? else {
+        return Ok(false);
+    };
+    if key.kind != KeyEventKind::Press {
+        return Ok(false);
+    }
+    Ok(match detection.state {
+        DetectionState::Failed(_) => true,
+        _ => {
+            matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+        }
+    })
 }
 
 fn handle_normal_mode(app: &mut App, key: KeyEvent) {
@@ -61,6 +94,19 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
         // Theme
         KeyCode::Char('t') => app.cycle_theme(),
 
+        // Keybinding help
+        KeyCode::Char('?') => app.toggle_help(),
+
+        // Export visible results: prompt for a filename and format (JSON/TOML/CSV/Markdown)
+        KeyCode::Char('e') => app.start_export(),
+
+        // Compare against reference hardware
+        KeyCode::Char('c') => app.open_compare(),
+        KeyCode::Char('h') => app.open_history(),
+
+        // Free RAM / VRAM sparklines
+        KeyCode::Char('m') => app.toggle_memory_panel(),
+
         // Provider popup
         KeyCode::Char('p') => app.open_provider_popup(),
 
@@ -95,16 +141,86 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
         // Refresh installed models
         KeyCode::Char('r') if app.ollama_available || app.mlx_available => app.refresh_installed(),
 
-        // Detail view
-        KeyCode::Enter => app.toggle_detail(),
+        // Detail view: Enter returns from the full pane, otherwise opens the popup
+        KeyCode::Enter if app.show_detail => app.toggle_detail(),
+        KeyCode::Enter | KeyCode::Char(' ') => app.open_detail_popup(),
+
+        _ => {}
+    }
+}
+
+fn handle_help_overlay(app: &mut App, key: KeyEvent) {
+    if matches!(
+        key.code,
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?')
+    ) {
+        app.toggle_help();
+    }
+}
+
+fn handle_detail_popup(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char(' ') => app.close_detail_popup(),
+
+        // Expand into the full-pane detail view
+        KeyCode::Enter => {
+            app.close_detail_popup();
+            app.show_detail = true;
+        }
+
+        // Step through models without closing
+        KeyCode::Up | KeyCode::Char('k') => app.move_up(),
+        KeyCode::Down | KeyCode::Char('j') => app.move_down(),
+
+        _ => {}
+    }
+}
+
+fn handle_compare_view(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('c') => app.close_compare(),
+
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::BackTab => app.prev_compare_profile(),
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => app.next_compare_profile(),
+
+        KeyCode::Up | KeyCode::Char('k') => app.move_up(),
+        KeyCode::Down | KeyCode::Char('j') => app.move_down(),
+
+        _ => {}
+    }
+}
+
+fn handle_history_view(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') => app.close_history(),
 
+        KeyCode::Up | KeyCode::Char('k') => app.history_up(),
+        KeyCode::Down | KeyCode::Char('j') => app.history_down(),
+
+        KeyCode::Enter | KeyCode::Char('c') => app.compare_history_entry(),
+
+        _ => {}
+    }
+}
+
+fn handle_export_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => app.confirm_export(),
+        KeyCode::Esc => app.cancel_export(),
+        KeyCode::Backspace => app.export_backspace(),
+        KeyCode::Tab => app.cycle_export_format(),
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.export_path.clear();
+        }
+        KeyCode::Char(c) => app.export_input(c),
         _ => {}
     }
 }
 
 fn handle_search_mode(app: &mut App, key: KeyEvent) {
     match key.code {
-        KeyCode::Esc | KeyCode::Enter => app.exit_search(),
+        KeyCode::Enter => app.exit_search(),
+        KeyCode::Esc => app.cancel_search(),
 
         KeyCode::Backspace => app.search_backspace(),
         KeyCode::Delete => app.search_delete(),
@@ -1,23 +1,89 @@
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{
-        Block, Borders, Cell, Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, Table, TableState, Wrap,
-    },
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, Wrap},
 };
 
 use crate::theme::ThemeColors;
-use crate::tui_app::{App, FitFilter, InputMode};
+use crate::tui_app::{App, Detection, DetectionState, FitFilter, InputMode};
+use crate::widgets::compare::{CompareColumn, HardwareCompare};
+use crate::widgets::help_overlay::HelpOverlay;
+use crate::widgets::history::HistoryView;
+use crate::widgets::model_detail::ModelDetail;
+use crate::widgets::model_list::{self, ModelList};
+use crate::widgets::sparkline::MemoryPanel;
 use llmfit_core::fit::FitLevel;
+use llmfit_core::fit::RunMode;
 use llmfit_core::fit::SortColumn;
+use llmfit_core::gpu_specs;
 use llmfit_core::hardware::is_running_in_wsl;
+use llmfit_core::models;
 use llmfit_core::providers;
 
+/// Startup screen while hardware detection runs: a centered spinner, or the
+/// failure reason, above a one-line status bar.
+pub fn draw_detection(frame: &mut Frame, detection: &Detection, tc: &ThemeColors) {
+    if tc.bg != Color::Reset {
+        let bg_block = Block::default().style(Style::default().bg(tc.bg));
+        frame.render_widget(bg_block, frame.area());
+    }
+
+    let outer = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(frame.area());
+    let middle = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ])
+        .split(outer[0])[1];
+
+    let (body, status, keys) = match &detection.state {
+        DetectionState::Failed(reason) => (
+            vec![
+                Line::from(Span::styled(
+                    format!("Hardware detection failed: {}", reason),
+                    Style::default().fg(tc.error).bold(),
+                )),
+                Line::from(Span::styled(
+                    "Press any key to quit",
+                    Style::default().fg(tc.muted),
+                )),
+            ],
+            "FAILED",
+            "  Hardware detection failed",
+        ),
+        _ => (
+            vec![Line::from(vec![
+                Span::styled(
+                    format!("{} ", SPINNER[detection.frame() % SPINNER.len()]),
+                    Style::default().fg(tc.accent).bold(),
+                ),
+                Span::styled("Detecting hardware…", Style::default().fg(tc.fg)),
+            ])],
+            "LOADING",
+            "  Detecting hardware…  q:quit",
+        ),
+    };
+    frame.render_widget(Paragraph::new(body).alignment(Alignment::Center), middle);
+
+    let status_line = Line::from(vec![
+        Span::styled(
+            format!(" {} ", status),
+            Style::default().fg(tc.status_fg).bg(tc.status_bg).bold(),
+        ),
+        Span::styled(keys, Style::default().fg(tc.muted)),
+    ]);
+    frame.render_widget(Paragraph::new(status_line), outer[1]);
+}
+
 pub fn draw(frame: &mut Frame, app: &mut App) {
-    let tc = app.theme.colors();
+    let tc = app.theme_colors();
 
     // Fill background if theme specifies one
     if tc.bg != Color::Reset {
@@ -38,18 +104,70 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
     draw_system_bar(frame, app, outer[0], &tc);
     draw_search_and_filters(frame, app, outer[1], &tc);
 
+    let main = if app.show_memory {
+        // 32 braille cells inside the border hold a minute of samples
+        let [main, side] =
+            Layout::horizontal([Constraint::Min(40), Constraint::Length(34)]).areas(outer[2]);
+        frame.render_widget(MemoryPanel::new(&app.memory_history, tc), side);
+        main
+    } else {
+        outer[2]
+    };
+
     if app.show_detail {
-        draw_detail(frame, app, outer[2], &tc);
+        draw_detail(frame, app, main, &tc);
     } else {
-        draw_table(frame, app, outer[2], &tc);
+        draw_table(frame, app, main, &tc);
     }
 
     draw_status_bar(frame, app, outer[3], &tc);
 
+    if app.detail_popup
+        && let Some(fit) = app.selected_fit()
+    {
+        frame.render_widget(ModelDetail::new(fit, app.specs.backend, tc), frame.area());
+    }
+
+    if app.show_compare
+        && let Some(fit) = app.selected_fit()
+        && let Some((title, ref_specs, ref_fit)) = app.compare_reference()
+    {
+        frame.render_widget(
+            HardwareCompare::new(
+                CompareColumn {
+                    title: "This machine",
+                    specs: &app.specs,
+                    fit,
+                },
+                CompareColumn {
+                    title: &title,
+                    specs: &ref_specs,
+                    fit: &ref_fit,
+                },
+                tc,
+            ),
+            frame.area(),
+        );
+    }
+
+    if app.show_history {
+        frame.render_widget(
+            HistoryView::new(&app.history, app.history_cursor, tc),
+            frame.area(),
+        );
+    }
+
     // Draw provider popup on top if active
     if app.input_mode == InputMode::ProviderPopup {
         draw_provider_popup(frame, app, &tc);
     }
+
+    if app.show_help {
+        frame.render_widget(
+            HelpOverlay::new(&app.theme, app.color_depth, tc),
+            frame.area(),
+        );
+    }
 }
 
 fn draw_system_bar(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
@@ -116,7 +234,7 @@ fn draw_system_bar(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
         tc.muted
     };
 
-    let text = Line::from(vec![
+    let mut spans = vec![
         Span::styled(" CPU: ", Style::default().fg(tc.muted)),
         Span::styled(
             format!(
@@ -138,11 +256,20 @@ fn draw_system_bar(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
         ),
         Span::styled("  │  ", Style::default().fg(tc.muted)),
         Span::styled(gpu_info, Style::default().fg(tc.accent_secondary)),
+    ];
+    if let Some(temp) = app.specs.gpu_temperature_c {
+        spans.push(Span::styled(
+            format!(" {:.0}°C", temp),
+            Style::default().fg(temperature_color(temp, tc)),
+        ));
+    }
+    spans.extend([
         Span::styled("  │  ", Style::default().fg(tc.muted)),
         Span::styled(ollama_info, Style::default().fg(ollama_color)),
         Span::styled("  │  ", Style::default().fg(tc.muted)),
         Span::styled(mlx_info, Style::default().fg(mlx_color)),
     ]);
+    let text = Line::from(spans);
 
     let block = Block::default()
         .borders(Borders::ALL)
@@ -154,7 +281,19 @@ fn draw_system_bar(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
     frame.render_widget(paragraph, area);
 }
 
-fn draw_search_and_filters(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
+/// GPU temperature color: good below 70°C, warning up to 85°C, error
+/// above.
+fn temperature_color(temp: f32, tc: &ThemeColors) -> Color {
+    if temp < 70.0 {
+        tc.good
+    } else if temp <= 85.0 {
+        tc.warning
+    } else {
+        tc.error
+    }
+}
+
+fn draw_search_and_filters(frame: &mut Frame, app: &mut App, area: Rect, tc: &ThemeColors) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
@@ -162,14 +301,16 @@ fn draw_search_and_filters(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeC
             Constraint::Length(24), // provider summary
             Constraint::Length(18), // sort column
             Constraint::Length(20), // fit filter
-            Constraint::Length(16), // theme
+            Constraint::Length(20), // theme
         ])
         .split(area);
 
     // Search box
     let search_style = match app.input_mode {
         InputMode::Search => Style::default().fg(tc.accent_secondary),
-        InputMode::Normal | InputMode::ProviderPopup => Style::default().fg(tc.muted),
+        InputMode::Normal | InputMode::ProviderPopup | InputMode::Export => {
+            Style::default().fg(tc.muted)
+        }
     };
 
     let search_text = if app.search_query.is_empty() && app.input_mode == InputMode::Normal {
@@ -273,30 +414,52 @@ fn draw_search_and_filters(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeC
     )))
     .block(theme_block);
     frame.render_widget(theme_text, chunks[4]);
+    app.theme_area = chunks[4];
 }
 
-fn fit_color(level: FitLevel, tc: &ThemeColors) -> Color {
-    match level {
-        FitLevel::Perfect => tc.fit_perfect,
-        FitLevel::Good => tc.fit_good,
-        FitLevel::Marginal => tc.fit_marginal,
-        FitLevel::TooTight => tc.fit_tight,
+/// Split `text` into spans, styling every case-insensitive occurrence of a
+/// search term with `matched` and the rest with `base`.
+fn highlight_matches<'a>(text: &'a str, query: &str, base: Style, matched: Style) -> Line<'a> {
+    // ASCII lowercasing keeps byte offsets aligned with `text`
+    let haystack = text.to_ascii_lowercase();
+    let mut hit = vec![false; text.len()];
+    for term in query.split_whitespace() {
+        let term = term.to_ascii_lowercase();
+        for (start, _) in haystack.match_indices(term.as_str()) {
+            hit[start..start + term.len()].fill(true);
+        }
+    }
+
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for end in 1..=text.len() {
+        if end == text.len() || (text.is_char_boundary(end) && hit[end] != hit[start]) {
+            let style = if hit[start] { matched } else { base };
+            spans.push(Span::styled(&text[start..end], style));
+            start = end;
+        }
     }
+    Line::from(spans)
 }
 
-fn fit_indicator(level: FitLevel) -> &'static str {
+/// Fit marker; with `shapes` (High Contrast, Okabe-Ito, Mono) the level is readable
+/// without color.
+fn fit_indicator(level: FitLevel, shapes: bool) -> &'static str {
+    if !shapes {
+        return "●";
+    }
     match level {
-        FitLevel::Perfect => "●",
-        FitLevel::Good => "●",
-        FitLevel::Marginal => "●",
-        FitLevel::TooTight => "●",
+        FitLevel::Perfect | FitLevel::Good => "✓",
+        FitLevel::Marginal => "⚠",
+        FitLevel::TooTight => "✗",
     }
 }
 
+const SPINNER: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
 /// Build a compact animated download indicator for the "Inst" column.
 fn pull_indicator(percent: Option<f64>, tick: u64) -> String {
-    const SPINNER: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
-    let spin = SPINNER[(tick as usize / 3) % SPINNER.len()];
+    let spin = SPINNER[tick as usize % SPINNER.len()];
 
     match percent {
         Some(pct) => {
@@ -342,12 +505,19 @@ fn draw_table(frame: &mut Frame, app: &mut App, area: Rect, tc: &ThemeColors) {
     });
     let header = Row::new(header_cells).height(1);
 
-    let rows: Vec<Row> = app
-        .filtered_fits
+    // Only build rows for the visible window
+    let total = app.filtered_fits.len();
+    let viewport = model_list::viewport_rows(area);
+    app.viewport_rows = viewport;
+    app.scroll_offset =
+        model_list::scroll_offset_for(app.selected_row, app.scroll_offset, viewport, total);
+    let visible_end = (app.scroll_offset + viewport).min(total);
+
+    let rows: Vec<Row> = app.filtered_fits[app.scroll_offset..visible_end]
         .iter()
         .map(|&idx| {
             let fit = &app.all_fits[idx];
-            let color = fit_color(fit.fit_level, tc);
+            let color = tc.fit_color(fit.fit_level);
 
             let mode_color = match fit.run_mode {
                 llmfit_core::fit::RunMode::Gpu => tc.mode_gpu,
@@ -404,9 +574,15 @@ fn draw_table(frame: &mut Frame, app: &mut App, area: Rect, tc: &ThemeColors) {
             };
 
             Row::new(vec![
-                Cell::from(fit_indicator(fit.fit_level)).style(Style::default().fg(color)),
+                Cell::from(fit_indicator(fit.fit_level, app.theme.shape_hints()))
+                    .style(Style::default().fg(color)),
                 Cell::from(installed_icon).style(Style::default().fg(installed_color)),
-                Cell::from(fit.model.name.clone()).style(Style::default().fg(tc.fg)),
+                Cell::from(highlight_matches(
+                    &fit.model.name,
+                    &app.search_query,
+                    Style::default().fg(tc.fg),
+                    Style::default().fg(tc.accent).add_modifier(Modifier::BOLD),
+                )),
                 Cell::from(fit.model.provider.clone()).style(Style::default().fg(tc.muted)),
                 Cell::from(fit.model.parameter_count.clone()).style(Style::default().fg(tc.fg)),
                 Cell::from(format!("{:.0}", fit.score)).style(Style::default().fg(score_color)),
@@ -472,25 +648,11 @@ fn draw_table(frame: &mut Frame, app: &mut App, area: Rect, tc: &ThemeColors) {
         )
         .highlight_symbol("▶ ");
 
-    let mut state = TableState::default();
-    if !app.filtered_fits.is_empty() {
-        state.select(Some(app.selected_row));
-    }
-
-    frame.render_stateful_widget(table, area, &mut state);
-
-    // Scrollbar
-    if app.filtered_fits.len() > (area.height as usize).saturating_sub(3) {
-        let mut scrollbar_state =
-            ScrollbarState::new(app.filtered_fits.len()).position(app.selected_row);
-        frame.render_stateful_widget(
-            Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(Some("↑"))
-                .end_symbol(Some("↓")),
-            area,
-            &mut scrollbar_state,
-        );
-    }
+    let list = ModelList::new(table, total, app.scroll_offset)
+        .selected((total > 0).then_some(app.selected_row))
+        .indicator_style(Style::default().fg(tc.accent));
+    frame.render_widget(list, area);
+    app.table_area = area;
 }
 
 fn draw_detail(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
@@ -505,7 +667,7 @@ fn draw_detail(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
         }
     };
 
-    let color = fit_color(fit.fit_level, tc);
+    let color = tc.fit_color(fit.fit_level);
 
     let mut lines = vec![
         Line::from(""),
@@ -649,6 +811,28 @@ fn draw_detail(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
         ]),
     ]);
 
+    // Bandwidth ceiling: only meaningful when all weights live on the GPU
+    if fit.run_mode == RunMode::Gpu && app.specs.has_gpu {
+        let params_read_b = fit
+            .model
+            .active_parameters
+            .map(|p| p as f64 / 1_000_000_000.0)
+            .unwrap_or_else(|| fit.model.params_b());
+        let weights_gb = params_read_b * models::quant_bpp(&fit.best_quant);
+        let roofline = gpu_specs::estimate_gpu_tokens_per_sec(
+            weights_gb,
+            app.specs.backend,
+            app.specs.gpu_name.as_deref().unwrap_or(""),
+        );
+        lines.push(Line::from(vec![
+            Span::styled("  Bandwidth:   ", Style::default().fg(tc.muted)),
+            Span::styled(
+                format!("~{:.0} tok/s ceiling (memory-bound)", roofline),
+                Style::default().fg(tc.fg),
+            ),
+        ]));
+    }
+
     // MoE Architecture section
     if fit.model.is_moe {
         lines.push(Line::from(""));
@@ -729,13 +913,30 @@ fn draw_detail(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
         Line::from(vec![
             Span::styled("  Fit Level:   ", Style::default().fg(tc.muted)),
             Span::styled(
-                format!("{} {}", fit_indicator(fit.fit_level), fit.fit_text()),
+                format!(
+                    "{} {}",
+                    fit_indicator(fit.fit_level, app.theme.shape_hints()),
+                    fit.fit_text()
+                ),
                 Style::default().fg(color).bold(),
             ),
         ]),
         Line::from(vec![
             Span::styled("  Run Mode:    ", Style::default().fg(tc.muted)),
             Span::styled(fit.run_mode_text(), Style::default().fg(tc.fg).bold()),
+            Span::styled(
+                fit.gpu_layers
+                    .map(|layers| {
+                        format!(
+                            "  ({}/{} layers on GPU, --n-gpu-layers {})",
+                            layers,
+                            fit.model.estimated_layer_count(),
+                            layers
+                        )
+                    })
+                    .unwrap_or_default(),
+                Style::default().fg(tc.mode_offload),
+            ),
         ]),
         Line::from(""),
         Line::from(Span::styled(
@@ -905,6 +1106,19 @@ fn draw_provider_popup(frame: &mut Frame, app: &App, tc: &ThemeColors) {
     frame.render_widget(paragraph, popup_area);
 }
 
+fn export_prompt(app: &App) -> String {
+    format!(
+        "  Save {} to: {}█  Tab:format  Enter:save  Esc:cancel  Ctrl-U:clear",
+        app.export_format.label(),
+        app.export_path
+    )
+}
+
+const HELP_KEYS: &str = "  ?/Esc/q:close help";
+const DETAIL_POPUP_KEYS: &str = "  ↑↓/jk:next model  Enter:full view  Esc/q:close";
+const COMPARE_KEYS: &str = "  ←→/hl:reference profile  ↑↓/jk:next model  Esc/q/c:close";
+const HISTORY_KEYS: &str = "  ↑↓/jk:select scan  Enter/c:compare with this machine  Esc/q/h:close";
+
 fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
     // If a download is in progress, show the progress bar
     if let Some(status) = &app.pull_status {
@@ -915,11 +1129,17 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
         };
 
         let (keys, mode_text) = match app.input_mode {
+            InputMode::Normal if app.show_help => (HELP_KEYS.to_string(), "HELP"),
+            InputMode::Normal if app.detail_popup => (DETAIL_POPUP_KEYS.to_string(), "DETAIL"),
+            InputMode::Normal if app.show_compare => (COMPARE_KEYS.to_string(), "COMPARE"),
+            InputMode::Normal if app.show_history => (HISTORY_KEYS.to_string(), "HISTORY"),
             InputMode::Normal => {
                 let detail_key = if app.show_detail {
                     "Enter:table"
+                } else if app.selected_fit().is_some() {
+                    "Enter/Space:details"
                 } else {
-                    "Enter:detail"
+                    ""
                 };
                 let ollama_keys = if app.ollama_available {
                     let installed_key = if app.installed_first {
@@ -933,20 +1153,21 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
                 };
                 (
                     format!(
-                        " ↑↓/jk:nav  {}  /:search  f:fit  s:sort  t:theme{}  p:providers  q:quit",
+                        " ↑↓/jk:nav  {}  /:search  f:fit  s:sort  t:theme{}  p:providers  e:export  c:compare  h:history  ?:help  q:quit",
                         detail_key, ollama_keys,
                     ),
                     "NORMAL",
                 )
             }
             InputMode::Search => (
-                "  Type to search  Esc:done  Ctrl-U:clear".to_string(),
+                "  Type to search  Enter:done  Esc:clear  Ctrl-U:clear".to_string(),
                 "SEARCH",
             ),
             InputMode::ProviderPopup => (
                 "  ↑↓/jk:navigate  Space:toggle  a:all/none  Esc:close".to_string(),
                 "PROVIDERS",
             ),
+            InputMode::Export => (export_prompt(app), "EXPORT"),
         };
 
         let chunks = Layout::default()
@@ -982,11 +1203,17 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
     }
 
     let (keys, mode_text) = match app.input_mode {
+        InputMode::Normal if app.show_help => (HELP_KEYS.to_string(), "HELP"),
+        InputMode::Normal if app.detail_popup => (DETAIL_POPUP_KEYS.to_string(), "DETAIL"),
+        InputMode::Normal if app.show_compare => (COMPARE_KEYS.to_string(), "COMPARE"),
+        InputMode::Normal if app.show_history => (HISTORY_KEYS.to_string(), "HISTORY"),
         InputMode::Normal => {
             let detail_key = if app.show_detail {
                 "Enter:table"
+            } else if app.selected_fit().is_some() {
+                "Enter/Space:details"
             } else {
-                "Enter:detail"
+                ""
             };
             let any_provider = app.ollama_available || app.mlx_available;
             let ollama_keys = if any_provider {
@@ -1001,20 +1228,21 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
             };
             (
                 format!(
-                    " ↑↓/jk:nav  {}  /:search  f:fit  s:sort  t:theme{}  p:providers  q:quit",
+                    " ↑↓/jk:nav  {}  /:search  f:fit  s:sort  t:theme{}  p:providers  e:export  c:compare  h:history  ?:help  q:quit",
                     detail_key, ollama_keys,
                 ),
                 "NORMAL",
             )
         }
         InputMode::Search => (
-            "  Type to search  Esc:done  Ctrl-U:clear".to_string(),
+            "  Type to search  Enter:done  Esc:clear  Ctrl-U:clear".to_string(),
             "SEARCH",
         ),
         InputMode::ProviderPopup => (
             "  ↑↓/jk:navigate  Space:toggle  a:all/none  Esc:close".to_string(),
             "PROVIDERS",
         ),
+        InputMode::Export => (export_prompt(app), "EXPORT"),
     };
 
     let status_line = Line::from(vec![
@@ -1027,3 +1255,40 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect, tc: &ThemeColors) {
 
     frame.render_widget(Paragraph::new(status_line), area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched_text(line: &Line, matched: Style) -> Vec<String> {
+        line.spans
+            .iter()
+            .filter(|s| s.style == matched)
+            .map(|s| s.content.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_temperature_color_bands() {
+        let tc = ThemeColors::default();
+        assert_eq!(temperature_color(55.0, &tc), tc.good);
+        assert_eq!(temperature_color(70.0, &tc), tc.warning);
+        assert_eq!(temperature_color(85.0, &tc), tc.warning);
+        assert_eq!(temperature_color(91.0, &tc), tc.error);
+    }
+
+    #[test]
+    fn test_highlight_matches_is_case_insensitive() {
+        let base = Style::default();
+        let matched = Style::default().add_modifier(Modifier::BOLD);
+
+        let line = highlight_matches("Llama-3.1-8B-Instruct", "LLAMA 8b", base, matched);
+        assert_eq!(matched_text(&line, matched), ["Llama", "8B"]);
+        let full: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(full, "Llama-3.1-8B-Instruct");
+
+        let plain = highlight_matches("Qwen2.5-7B", "", base, matched);
+        assert_eq!(plain.spans.len(), 1);
+        assert!(matched_text(&plain, matched).is_empty());
+    }
+}
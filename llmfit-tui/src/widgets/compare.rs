@@ -0,0 +1,209 @@
+//! This machine next to a reference profile for the selected model, shown
+//! with `c`.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use super::centered_rect;
+use crate::theme::ThemeColors;
+use llmfit_core::fit::{FitLevel, ModelFit};
+use llmfit_core::hardware::SystemSpecs;
+
+/// One side of the comparison.
+pub struct CompareColumn<'a> {
+    pub title: &'a str,
+    pub specs: &'a SystemSpecs,
+    pub fit: &'a ModelFit,
+}
+
+/// Two-column popup: local hardware on the left, the reference profile on
+/// the right. Render it into the whole frame; it centers itself at 70% x 60%.
+pub struct HardwareCompare<'a> {
+    local: CompareColumn<'a>,
+    reference: CompareColumn<'a>,
+    tc: ThemeColors,
+}
+
+impl<'a> HardwareCompare<'a> {
+    pub fn new(local: CompareColumn<'a>, reference: CompareColumn<'a>, tc: ThemeColors) -> Self {
+        HardwareCompare {
+            local,
+            reference,
+            tc,
+        }
+    }
+
+    /// Lines for `column`; `other_tps` adds a speed ratio against the other
+    /// side.
+    fn column_lines(&self, column: &CompareColumn<'a>, other_tps: Option<f64>) -> Vec<Line<'a>> {
+        let tc = &self.tc;
+        let specs = column.specs;
+        let fit = column.fit;
+        let label = |text: &'static str| Span::styled(text, Style::default().fg(tc.muted));
+        let value = |text: String, color: Color| Span::styled(text, Style::default().fg(color));
+
+        let gpu = match (&specs.gpu_name, specs.has_gpu) {
+            (Some(name), true) if specs.gpu_count > 1 => format!("{}x {}", specs.gpu_count, name),
+            (Some(name), true) => name.clone(),
+            _ => format!("none ({})", specs.backend.label()),
+        };
+        let vram = match specs.total_gpu_vram_gb {
+            Some(vram) if specs.has_gpu && specs.unified_memory => {
+                format!("{:.0} GB shared", vram)
+            }
+            Some(vram) if specs.has_gpu => format!("{:.0} GB", vram),
+            _ => "-".to_string(),
+        };
+
+        let runnable = fit.fit_level != FitLevel::TooTight;
+        let mut speed = vec![
+            label(" Est. speed: "),
+            value(
+                if runnable {
+                    format!("~{:.1} tok/s", fit.estimated_tps)
+                } else {
+                    "-".to_string()
+                },
+                tc.fg,
+            ),
+        ];
+        if let Some(other) = other_tps
+            && runnable
+            && other > 0.0
+        {
+            speed.push(value(
+                format!(" ({:.1}x)", fit.estimated_tps / other),
+                tc.accent,
+            ));
+        }
+
+        vec![
+            Line::from(Span::styled(
+                format!(" {}", column.title),
+                Style::default()
+                    .fg(tc.accent_secondary)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(vec![label(" GPU:        "), value(gpu, tc.fg)]),
+            Line::from(vec![label(" VRAM:       "), value(vram, tc.fg)]),
+            Line::from(vec![
+                label(" RAM:        "),
+                value(format!("{:.0} GB", specs.total_ram_gb), tc.fg),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                label(" Fit:        "),
+                Span::styled(
+                    fit.fit_text().to_string(),
+                    Style::default()
+                        .fg(tc.fit_color(fit.fit_level))
+                        .add_modifier(Modifier::BOLD),
+                ),
+                value(format!(" ({})", fit.run_mode_text()), tc.muted),
+            ]),
+            Line::from(vec![
+                label(" Memory:     "),
+                value(
+                    format!(
+                        "{:.1} / {:.1} GB",
+                        fit.memory_required_gb, fit.memory_available_gb
+                    ),
+                    tc.fg,
+                ),
+            ]),
+            Line::from(vec![
+                label(" Quant:      "),
+                value(fit.best_quant.clone(), tc.good),
+            ]),
+            Line::from(speed),
+        ]
+    }
+}
+
+impl Widget for HardwareCompare<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let popup = centered_rect(70, 60, area);
+        Clear.render(popup, buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.tc.accent_secondary))
+            .style(Style::default().bg(self.tc.bg))
+            .title(format!(" Compare: {} ", self.local.fit.model.name))
+            .title_style(
+                Style::default()
+                    .fg(self.tc.accent_secondary)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .title_bottom(Line::from(Span::styled(
+                " ←→/hl:profile  ↑↓/jk:model  Esc/q/c:close ",
+                Style::default().fg(self.tc.muted),
+            )));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(inner);
+
+        let local_tps = self.local.fit.estimated_tps;
+        let left = self.column_lines(&self.local, None);
+        let right = self.column_lines(&self.reference, Some(local_tps));
+        Paragraph::new(left).render(columns[0], buf);
+        Paragraph::new(right)
+            .block(
+                Block::default()
+                    .borders(Borders::LEFT)
+                    .border_style(Style::default().fg(self.tc.border)),
+            )
+            .render(columns[1], buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llmfit_core::hardware::GpuBackend;
+    use llmfit_core::models::ModelDatabase;
+
+    #[test]
+    fn test_compare_renders_both_columns() {
+        let local = SystemSpecs::mock(10.0, 32.0, GpuBackend::Cuda);
+        let reference = SystemSpecs::new_manual(64.0, Some(24.0), GpuBackend::Cuda, 2);
+        let db = ModelDatabase::new();
+        let model = &db.get_all_models()[0];
+        let local_fit = ModelFit::analyze(model, &local);
+        let reference_fit = ModelFit::analyze(model, &reference);
+
+        let area = Rect::new(0, 0, 120, 40);
+        let mut buf = Buffer::empty(area);
+        HardwareCompare::new(
+            CompareColumn {
+                title: "This machine",
+                specs: &local,
+                fit: &local_fit,
+            },
+            CompareColumn {
+                title: "2x Reference",
+                specs: &reference,
+                fit: &reference_fit,
+            },
+            ThemeColors::default(),
+        )
+        .render(area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("This machine"));
+        assert!(text.contains("2x Reference"));
+        assert!(text.contains("10 GB"));
+        assert!(text.contains("2x Manual GPU"));
+        assert!(text.contains("48 GB"));
+    }
+}
@@ -0,0 +1,156 @@
+//! Keybinding reference shown with `?`.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use super::centered_rect;
+use crate::color_support::ColorDepth;
+use crate::theme::{Theme, ThemeColors};
+
+/// Shortcuts by section, as (keys, action).
+const SECTIONS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "Navigation",
+        &[
+            ("↑↓ / j k", "Move selection"),
+            ("PgUp / PgDn", "Scroll one page"),
+            ("Ctrl-U / Ctrl-D", "Scroll half a page"),
+            ("g / G", "Jump to top / bottom"),
+        ],
+    ),
+    (
+        "Models",
+        &[
+            ("Enter / Space", "Fit details popup"),
+            ("Enter (in popup)", "Full detail view"),
+            ("d", "Download selected model"),
+            ("i", "Installed models first"),
+            ("r", "Refresh installed models"),
+            ("e", "Export shown models (Tab: format)"),
+            ("c", "Compare with reference hardware"),
+            ("h", "Hardware scan history"),
+        ],
+    ),
+    (
+        "Filter & sort",
+        &[
+            ("/", "Search (Enter keeps, Esc clears)"),
+            ("f", "Cycle fit filter"),
+            ("s", "Cycle sort column"),
+            ("p", "Provider filter"),
+        ],
+    ),
+    (
+        "General",
+        &[
+            ("t", "Cycle theme"),
+            ("m", "Free memory panel"),
+            ("?", "Toggle this help"),
+            ("q / Esc", "Close popup, or quit"),
+        ],
+    ),
+];
+
+/// Width of the key column.
+const KEY_WIDTH: usize = 18;
+
+/// Centered modal listing every keyboard shortcut, plus the active theme and
+/// detected color depth.
+pub struct HelpOverlay<'a> {
+    theme: &'a Theme,
+    color_depth: ColorDepth,
+    tc: ThemeColors,
+}
+
+impl<'a> HelpOverlay<'a> {
+    pub fn new(theme: &'a Theme, color_depth: ColorDepth, tc: ThemeColors) -> Self {
+        HelpOverlay {
+            theme,
+            color_depth,
+            tc,
+        }
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let tc = &self.tc;
+        let mut lines = Vec::new();
+        for (section, keys) in SECTIONS {
+            lines.push(Line::from(Span::styled(
+                format!(" {}", section),
+                Style::default().fg(tc.accent).add_modifier(Modifier::BOLD),
+            )));
+            for (key, action) in *keys {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("   {:<width$}", key, width = KEY_WIDTH),
+                        Style::default().fg(tc.accent_secondary),
+                    ),
+                    Span::styled(*action, Style::default().fg(tc.fg)),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(vec![
+            Span::styled(" Theme: ", Style::default().fg(tc.muted)),
+            Span::styled(self.theme.label().to_string(), Style::default().fg(tc.fg)),
+            Span::styled("   Colors: ", Style::default().fg(tc.muted)),
+            Span::styled(self.color_depth.label(), Style::default().fg(tc.fg)),
+        ]));
+        lines
+    }
+}
+
+impl Widget for HelpOverlay<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let popup = centered_rect(50, 80, area);
+        Clear.render(popup, buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.tc.accent_secondary))
+            .style(Style::default().bg(self.tc.bg).fg(self.tc.fg))
+            .title(" Keybindings ")
+            .title_style(
+                Style::default()
+                    .fg(self.tc.accent_secondary)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .title_bottom(Line::from(Span::styled(
+                " ?/Esc:close ",
+                Style::default().fg(self.tc.muted),
+            )));
+
+        Paragraph::new(self.lines()).block(block).render(popup, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_overlay_shows_theme_and_color_depth() {
+        let area = Rect::new(0, 0, 120, 50);
+        let mut buf = Buffer::empty(area);
+        let theme = Theme::Dracula;
+        HelpOverlay::new(&theme, ColorDepth::Colors256, theme.colors()).render(area, &mut buf);
+
+        let text: String = (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(text.contains("Keybindings"));
+        assert!(text.contains("Cycle theme"));
+        assert!(text.contains("Theme: Dracula"));
+        assert!(text.contains("Colors: 256 colors"));
+    }
+}
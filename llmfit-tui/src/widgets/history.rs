@@ -0,0 +1,148 @@
+//! Past hardware scans, newest first, shown with `h`.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Cell, Clear, Paragraph, Row, StatefulWidget, Table, TableState, Widget,
+    },
+};
+
+use super::centered_rect;
+use crate::history::HistoryEntry;
+use crate::theme::ThemeColors;
+
+/// Popup table of recorded detections. `cursor` counts rows from the top,
+/// i.e. from the newest entry. Render it into the whole frame; it centers
+/// itself at 70% x 60%.
+pub struct HistoryView<'a> {
+    entries: &'a [HistoryEntry],
+    cursor: usize,
+    tc: ThemeColors,
+}
+
+impl<'a> HistoryView<'a> {
+    pub fn new(entries: &'a [HistoryEntry], cursor: usize, tc: ThemeColors) -> Self {
+        HistoryView {
+            entries,
+            cursor,
+            tc,
+        }
+    }
+
+    fn row(entry: &HistoryEntry) -> Row<'a> {
+        let specs = &entry.specs;
+        let vram = match specs.total_gpu_vram_gb {
+            Some(vram) if specs.has_gpu && specs.unified_memory => {
+                format!("{:.0} GB shared", vram)
+            }
+            Some(vram) if specs.has_gpu => format!("{:.0} GB", vram),
+            _ => "-".to_string(),
+        };
+        Row::new(vec![
+            Cell::from(entry.timestamp_label()),
+            Cell::from(entry.gpu_label()),
+            Cell::from(vram),
+            Cell::from(format!(
+                "{:.1} / {:.1} GB",
+                specs.available_ram_gb, specs.total_ram_gb
+            )),
+        ])
+    }
+}
+
+impl Widget for HistoryView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let popup = centered_rect(70, 60, area);
+        Clear.render(popup, buf);
+
+        let tc = &self.tc;
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(tc.accent_secondary))
+            .style(Style::default().bg(tc.bg))
+            .title(format!(" Hardware scan history ({}) ", self.entries.len()))
+            .title_style(
+                Style::default()
+                    .fg(tc.accent_secondary)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .title_bottom(Line::from(Span::styled(
+                " ↑↓/jk:select  Enter/c:compare  Esc/q/h:close ",
+                Style::default().fg(tc.muted),
+            )));
+
+        if self.entries.is_empty() {
+            Paragraph::new(vec![
+                Line::from(""),
+                Line::from(Span::styled(
+                    "  No scans recorded yet; each llmfit run adds one.",
+                    Style::default().fg(tc.muted),
+                )),
+            ])
+            .block(block)
+            .render(popup, buf);
+            return;
+        }
+
+        let header = Row::new(vec!["When", "GPU", "VRAM", "RAM free"])
+            .style(Style::default().fg(tc.accent).add_modifier(Modifier::BOLD));
+        let rows: Vec<Row> = self.entries.iter().rev().map(Self::row).collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(22),
+                Constraint::Min(16),
+                Constraint::Length(16),
+                Constraint::Length(18),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .style(Style::default().fg(tc.fg))
+        .row_highlight_style(
+            Style::default()
+                .bg(tc.highlight_bg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+
+        let mut state = TableState::default().with_selected(Some(self.cursor));
+        StatefulWidget::render(table, popup, buf, &mut state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llmfit_core::hardware::{GpuBackend, SystemSpecs};
+
+    #[test]
+    fn test_history_view_lists_newest_first() {
+        let entries = vec![
+            HistoryEntry {
+                timestamp: 0,
+                specs: SystemSpecs::new_manual(16.0, None, GpuBackend::CpuX86, 0),
+            },
+            HistoryEntry {
+                timestamp: 86_400,
+                specs: SystemSpecs::mock(24.0, 32.0, GpuBackend::Cuda),
+            },
+        ];
+        let area = Rect::new(0, 0, 120, 30);
+        let mut buf = Buffer::empty(area);
+        HistoryView::new(&entries, 0, ThemeColors::default()).render(area, &mut buf);
+
+        let rows: Vec<String> = (0..area.height)
+            .map(|y| (0..area.width).map(|x| buf[(x, y)].symbol()).collect())
+            .collect();
+        let newest = rows.iter().position(|r| r.contains("1970-01-02")).unwrap();
+        let oldest = rows.iter().position(|r| r.contains("1970-01-01")).unwrap();
+        assert!(newest < oldest);
+        assert!(rows[newest].contains("Manual GPU") && rows[newest].contains("24 GB"));
+        assert!(rows[oldest].contains("none"));
+        assert!(rows.iter().any(|r| r.contains("Hardware scan history (2)")));
+    }
+}
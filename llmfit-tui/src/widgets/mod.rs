@@ -0,0 +1,34 @@
+use ratatui::layout::Rect;
+
+pub mod compare;
+pub mod help_overlay;
+pub mod history;
+pub mod model_detail;
+pub mod model_list;
+pub mod sparkline;
+
+/// A rectangle `percent_x` by `percent_y` of `area`, centered in it.
+pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let width = (area.width as u32 * percent_x as u32 / 100) as u16;
+    let height = (area.height as u32 * percent_y as u32 / 100) as u16;
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_centered_rect() {
+        let area = Rect::new(0, 0, 100, 50);
+        assert_eq!(centered_rect(60, 70, area), Rect::new(20, 7, 60, 35));
+        // Offset frames stay offset
+        let area = Rect::new(10, 4, 81, 21);
+        assert_eq!(centered_rect(60, 70, area), Rect::new(26, 7, 48, 14));
+    }
+}
@@ -0,0 +1,160 @@
+//! Fit summary for the selected model, drawn as a centered popup over the
+//! model table.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use super::centered_rect;
+use crate::theme::ThemeColors;
+use llmfit_core::fit::{ModelFit, RunMode};
+use llmfit_core::hardware::GpuBackend;
+
+/// Context lengths the KV cache section is broken down at.
+const KV_CONTEXTS: [u32; 4] = [2_048, 4_096, 8_192, 32_768];
+
+/// Popup with the selected model's fit details. Render it into the whole
+/// frame; it centers itself at 60% x 70% and clears what's underneath.
+pub struct ModelDetail<'a> {
+    fit: &'a ModelFit,
+    backend: GpuBackend,
+    tc: ThemeColors,
+}
+
+impl<'a> ModelDetail<'a> {
+    pub fn new(fit: &'a ModelFit, backend: GpuBackend, tc: ThemeColors) -> Self {
+        ModelDetail { fit, backend, tc }
+    }
+
+    fn lines(&self) -> Vec<Line<'a>> {
+        let fit = self.fit;
+        let tc = &self.tc;
+        let label = |text: &'static str| Span::styled(text, Style::default().fg(tc.muted));
+        let value = |text: String, color: Color| Span::styled(text, Style::default().fg(color));
+
+        let memory_label = match fit.run_mode {
+            RunMode::Gpu | RunMode::MoeOffload | RunMode::CpuOffload => "  Est. VRAM:    ",
+            RunMode::CpuOnly => "  Est. RAM:     ",
+        };
+        let fit_color = tc.fit_color(fit.fit_level);
+
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(vec![
+                label("  Parameters:   "),
+                value(fit.model.parameter_count.clone(), tc.fg),
+            ]),
+            Line::from(vec![
+                label("  Quantization: "),
+                value(fit.best_quant.clone(), tc.good),
+                value(
+                    format!(" (published as {})", fit.model.quantization),
+                    tc.muted,
+                ),
+            ]),
+            Line::from(vec![
+                label(memory_label),
+                value(
+                    format!(
+                        "{:.1} GB of {:.1} GB ({:.0}%)",
+                        fit.memory_required_gb, fit.memory_available_gb, fit.utilization_pct
+                    ),
+                    tc.fg,
+                ),
+            ]),
+            Line::from(vec![
+                label("  Fit:          "),
+                Span::styled(
+                    fit.fit_text().to_string(),
+                    Style::default().fg(fit_color).add_modifier(Modifier::BOLD),
+                ),
+                value(format!(" ({})", fit.run_mode_text()), tc.muted),
+            ]),
+        ];
+        if let Some(layers) = fit.gpu_layers {
+            lines.push(Line::from(vec![
+                label("  GPU layers:   "),
+                value(
+                    format!("{}/{}", layers, fit.model.estimated_layer_count()),
+                    tc.mode_offload,
+                ),
+                value(format!(" (--n-gpu-layers {})", layers), tc.muted),
+            ]));
+        }
+        lines.extend([
+            Line::from(vec![
+                label("  Speed:        "),
+                value(format!("~{:.1} tok/s", fit.estimated_tps), tc.fg),
+            ]),
+            Line::from(vec![
+                label("  Backend:      "),
+                value(self.backend.label().to_string(), tc.accent),
+                value(format!(" via {}", fit.runtime_text()), tc.muted),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                "  KV cache",
+                Style::default().fg(tc.accent).add_modifier(Modifier::BOLD),
+            )),
+        ]);
+
+        for ctx in KV_CONTEXTS {
+            let mut spans = vec![
+                label("    "),
+                value(format!("{:>3}K ctx  ", ctx / 1024), tc.fg),
+                value(format!("{:>6.2} GB", fit.model.kv_cache_gb(ctx)), tc.fg),
+            ];
+            if ctx > fit.model.context_length {
+                spans.push(value("  (beyond model context)".to_string(), tc.muted));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        if !fit.notes.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "  Warnings",
+                Style::default().fg(tc.warning).add_modifier(Modifier::BOLD),
+            )));
+            for note in &fit.notes {
+                lines.push(Line::from(vec![
+                    value("    ! ".to_string(), tc.warning),
+                    value(note.clone(), tc.fg),
+                ]));
+            }
+        }
+
+        lines
+    }
+}
+
+impl Widget for ModelDetail<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let popup = centered_rect(60, 70, area);
+        Clear.render(popup, buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.tc.accent_secondary))
+            .style(Style::default().bg(self.tc.bg))
+            .title(format!(" {} ", self.fit.model.name))
+            .title_style(
+                Style::default()
+                    .fg(self.tc.accent_secondary)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .title_bottom(Line::from(Span::styled(
+                " Esc/q:close  Enter:full view ",
+                Style::default().fg(self.tc.muted),
+            )));
+
+        Paragraph::new(self.lines())
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(popup, buf);
+    }
+}
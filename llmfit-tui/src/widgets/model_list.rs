@@ -0,0 +1,132 @@
+//! Virtual model table: only the rows inside the viewport are built and
+//! drawn, so a database with thousands of models renders as fast as one
+//! with ten.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    widgets::{StatefulWidget, Table, TableState, Widget},
+};
+
+/// Lines taken by the table block's borders and the header row.
+const CHROME_ROWS: u16 = 3;
+
+/// Number of model rows that fit in a table drawn into `area`.
+pub fn viewport_rows(area: Rect) -> usize {
+    area.height.saturating_sub(CHROME_ROWS) as usize
+}
+
+/// Scroll offset that keeps `selected` visible, moving as little as possible
+/// from the previous `offset`. Also clamps the offset when the list shrinks.
+pub fn scroll_offset_for(selected: usize, offset: usize, viewport: usize, len: usize) -> usize {
+    if viewport == 0 || len == 0 {
+        return 0;
+    }
+    let max_offset = len.saturating_sub(viewport);
+    let offset = if selected < offset {
+        selected
+    } else if selected >= offset + viewport {
+        selected + 1 - viewport
+    } else {
+        offset
+    };
+    offset.min(max_offset)
+}
+
+/// Start and length of the scroll thumb on a `track`-cell track, sized by
+/// the visible fraction of the list.
+fn thumb_span(track: usize, total: usize, offset: usize, viewport: usize) -> (usize, usize) {
+    if total == 0 || track == 0 {
+        return (0, track);
+    }
+    let len = (track * viewport / total).clamp(1, track);
+    let start = (track * offset / total).min(track - len);
+    (start, len)
+}
+
+/// The model table plus a proportional scroll indicator on its right edge.
+/// `table` must hold only the rows from `offset` that fit in the viewport.
+pub struct ModelList<'a> {
+    table: Table<'a>,
+    total: usize,
+    offset: usize,
+    selected: Option<usize>,
+    indicator_style: Style,
+}
+
+impl<'a> ModelList<'a> {
+    pub fn new(table: Table<'a>, total: usize, offset: usize) -> Self {
+        ModelList {
+            table,
+            total,
+            offset,
+            selected: None,
+            indicator_style: Style::default(),
+        }
+    }
+
+    /// Highlighted row, as an index into the full list.
+    pub fn selected(mut self, selected: Option<usize>) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    pub fn indicator_style(mut self, style: Style) -> Self {
+        self.indicator_style = style;
+        self
+    }
+}
+
+impl Widget for ModelList<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut state = TableState::default()
+            .with_selected(self.selected.and_then(|s| s.checked_sub(self.offset)));
+        StatefulWidget::render(self.table, area, buf, &mut state);
+
+        let viewport = viewport_rows(area);
+        if self.total <= viewport || area.width == 0 {
+            return;
+        }
+        // Thumb runs down the right border, alongside the model rows
+        let track_top = area.y + CHROME_ROWS - 1;
+        let (start, len) = thumb_span(viewport, self.total, self.offset, viewport);
+        let x = area.right() - 1;
+        for y in track_top + start as u16..track_top + (start + len) as u16 {
+            buf[(x, y)].set_symbol("█").set_style(self.indicator_style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_offset_follows_selection() {
+        // Selection inside the window: no scrolling
+        assert_eq!(scroll_offset_for(5, 0, 10, 100), 0);
+        // Past the bottom: scroll just enough
+        assert_eq!(scroll_offset_for(10, 0, 10, 100), 1);
+        assert_eq!(scroll_offset_for(99, 0, 10, 100), 90);
+        // Above the top: snap to the selection
+        assert_eq!(scroll_offset_for(3, 20, 10, 100), 3);
+        // List shrank under a filter
+        assert_eq!(scroll_offset_for(4, 50, 10, 5), 0);
+        assert_eq!(scroll_offset_for(0, 0, 0, 100), 0);
+    }
+
+    #[test]
+    fn test_thumb_proportional_to_visible_fraction() {
+        assert_eq!(thumb_span(20, 100, 0, 20), (0, 4));
+        assert_eq!(thumb_span(20, 100, 80, 20), (16, 4));
+        // Tiny fraction still gets a one-cell thumb
+        assert_eq!(thumb_span(10, 10_000, 5_000, 10), (5, 1));
+    }
+
+    #[test]
+    fn test_viewport_rows_excludes_chrome() {
+        assert_eq!(viewport_rows(Rect::new(0, 0, 80, 23)), 20);
+        assert_eq!(viewport_rows(Rect::new(0, 0, 80, 2)), 0);
+    }
+}
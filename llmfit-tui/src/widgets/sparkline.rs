@@ -0,0 +1,173 @@
+//! Braille sparklines of free RAM and VRAM, shown beside the table with `m`.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use crate::memory_history::{HISTORY_LEN, MemoryHistory, SAMPLE_INTERVAL};
+use crate::theme::ThemeColors;
+
+/// Braille dot bits for the left and right column of a cell, top to bottom.
+const BRAILLE_DOTS: [[u8; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+/// Filled area chart of `data` in braille: two samples per cell across and
+/// four dots per cell down. The newest sample sits at the right edge, and
+/// the Y axis spans the min..max of the samples shown.
+pub struct BrailleSparkline<'a> {
+    data: &'a [f64],
+    color: Color,
+}
+
+impl<'a> BrailleSparkline<'a> {
+    pub fn new(data: &'a [f64], color: Color) -> Self {
+        BrailleSparkline { data, color }
+    }
+}
+
+/// Dots to fill for each of `data`, 1..=`rows`: the minimum gets one dot so
+/// it stays visible, the maximum the full height. A flat series sits at half
+/// height.
+fn dot_heights(data: &[f64], rows: usize) -> Vec<usize> {
+    let min = data.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = data.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    data.iter()
+        .map(|v| {
+            if range <= f64::EPSILON {
+                (rows / 2).max(1)
+            } else {
+                1 + ((v - min) / range * (rows - 1) as f64).round() as usize
+            }
+        })
+        .collect()
+}
+
+impl Widget for BrailleSparkline<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() || self.data.is_empty() {
+            return;
+        }
+        let (width, height) = (area.width as usize, area.height as usize);
+        let columns = width * 2;
+        let data = &self.data[self.data.len().saturating_sub(columns)..];
+        let offset = columns - data.len();
+
+        let mut cells = vec![0u8; width * height];
+        for (i, dots) in dot_heights(data, height * 4).into_iter().enumerate() {
+            let column = offset + i;
+            for dot in 0..dots {
+                let row = height - 1 - dot / 4;
+                cells[row * width + column / 2] |= BRAILLE_DOTS[column % 2][3 - dot % 4];
+            }
+        }
+
+        let style = Style::default().fg(self.color);
+        for (i, bits) in cells.into_iter().enumerate() {
+            if bits == 0 {
+                continue;
+            }
+            let symbol = char::from_u32(0x2800 + bits as u32).unwrap_or(' ');
+            buf[(area.x + (i % width) as u16, area.y + (i / width) as u16)]
+                .set_char(symbol)
+                .set_style(style);
+        }
+    }
+}
+
+/// Side panel with the free RAM and, when it can be read, free VRAM over the
+/// last minute.
+pub struct MemoryPanel<'a> {
+    history: &'a MemoryHistory,
+    tc: ThemeColors,
+}
+
+impl<'a> MemoryPanel<'a> {
+    pub fn new(history: &'a MemoryHistory, tc: ThemeColors) -> Self {
+        MemoryPanel { history, tc }
+    }
+
+    /// Latest value and range above its sparkline.
+    fn render_series(&self, label: &str, data: &[f64], color: Color, area: Rect, buf: &mut Buffer) {
+        let tc = &self.tc;
+        let [header, chart] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        let min = data.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = data.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let latest = data.last().copied().unwrap_or_default();
+        Paragraph::new(Line::from(vec![
+            Span::styled(
+                format!("{} ", label),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!("{:.1} GB", latest), Style::default().fg(tc.fg)),
+            Span::styled(
+                format!(" ({:.1}-{:.1})", min, max),
+                Style::default().fg(tc.muted),
+            ),
+        ]))
+        .render(header, buf);
+        BrailleSparkline::new(data, color).render(chart, buf);
+    }
+}
+
+impl Widget for MemoryPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let tc = &self.tc;
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(tc.border))
+            .style(Style::default().bg(tc.bg))
+            .title(format!(
+                " Free memory ({}s) ",
+                HISTORY_LEN as u64 * SAMPLE_INTERVAL.as_secs()
+            ))
+            .title_style(Style::default().fg(tc.title).add_modifier(Modifier::BOLD));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.history.is_empty() {
+            Paragraph::new(Span::styled(" Sampling...", Style::default().fg(tc.muted)))
+                .render(inner, buf);
+            return;
+        }
+
+        let ram = self.history.ram();
+        let vram = self.history.vram();
+        if vram.is_empty() {
+            self.render_series("RAM", &ram, tc.accent, inner, buf);
+            return;
+        }
+        let [top, bottom] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(inner);
+        self.render_series("RAM", &ram, tc.accent, top, buf);
+        self.render_series("VRAM", &vram, tc.mode_gpu, bottom, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_braille_sparkline_scales_to_range() {
+        let area = Rect::new(0, 0, 2, 1);
+        let mut buf = Buffer::empty(area);
+        // Oldest sample is dropped: only 4 columns of dots fit
+        BrailleSparkline::new(&[9.0, 10.0, 12.0, 12.0, 14.0], Color::Reset).render(area, &mut buf);
+        // 10 -> 1 dot, 12 -> 3 (of 1..=4, rounded), 14 -> 4
+        assert_eq!(buf[(0, 0)].symbol(), "⣰");
+        assert_eq!(buf[(1, 0)].symbol(), "⣾");
+
+        // Newest at the right edge; flat data sits at half height
+        let area = Rect::new(0, 0, 3, 2);
+        let mut buf = Buffer::empty(area);
+        BrailleSparkline::new(&[5.0, 5.0], Color::Reset).render(area, &mut buf);
+        assert_eq!(buf[(0, 1)].symbol(), " ");
+        assert_eq!(buf[(2, 0)].symbol(), " ");
+        assert_eq!(buf[(2, 1)].symbol(), "⣿");
+    }
+}